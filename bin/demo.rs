@@ -0,0 +1,94 @@
+//! End-to-end demo of the relay flow: build, estimate fee, sign, submit,
+//! and track a forward request to completion.
+//!
+//! Usage: `GELATO_DEMO_KEY=<hex private key> cargo run --example demo -- <chain_id> <target> [calldata] [--export <path>]`
+//!
+//! The key must belong to a sponsor with a funded Gas Tank balance on the
+//! chain. With `--export`, the signed request is written to a JSON file
+//! instead of being submitted.
+
+use std::env;
+
+use ethers::signers::{LocalWallet, Signer};
+use gelato_sdk::*;
+
+const DEMO_GAS: u64 = 200_000;
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let mut args = env::args().collect::<Vec<_>>();
+    let export_flag = args.iter().position(|a| a == "--export");
+    let export = export_flag.map(|i| {
+        let path = args.get(i + 1).cloned().unwrap_or_default();
+        args.drain(i..(i + 2).min(args.len()));
+        path
+    });
+    eyre::ensure!(
+        args.len() >= 3,
+        "Usage: demo <chain_id> <target> [calldata] [--export <path>]"
+    );
+    let chain_id: u64 = args[1].parse()?;
+    let target: ethers::types::Address = args[2].parse()?;
+    let data: ethers::types::Bytes = args
+        .get(3)
+        .map(|d| d.parse::<ethers::types::Bytes>())
+        .transpose()?
+        .unwrap_or_default();
+
+    let wallet: LocalWallet = env::var("GELATO_DEMO_KEY")?.parse()?;
+    let wallet = wallet.with_chain_id(chain_id);
+    println!("Sponsor: {:?}", wallet.address());
+
+    let gelato = GelatoClient::default();
+
+    println!("1. Checking chain support and clock skew");
+    eyre::ensure!(
+        gelato.is_chain_supported(chain_id).await?,
+        "Chain {chain_id} is not supported by Gelato"
+    );
+    let skew = gelato.check_clock_skew().await?;
+    println!(
+        "   Local clock is {}s ahead of the relay",
+        skew.offset_secs()
+    );
+
+    println!("2. Estimating fee for {DEMO_GAS} gas");
+    let quote = gelato
+        .get_fee_quote(chain_id, FeeToken::default(), DEMO_GAS.into(), false)
+        .await?;
+    println!("   Quoted fee: {} (native token)", quote.fee);
+
+    println!("3. Building and signing forward request");
+    let request = ForwardRequestBuilder::default()
+        .chain_id(chain_id)
+        .target(target)
+        .data(data)
+        .gas(DEMO_GAS)
+        .enforce_sponsor_nonce(false)
+        .sponsored_by(&wallet)
+        .fee_quote(&quote)
+        .build()
+        .await?;
+    println!("   Signature: {}", request.sponsor_signature());
+
+    if let Some(path) = export {
+        offline::write_json_file(&path, request)?;
+        println!("4. Exported signed request to {path}");
+        return Ok(());
+    }
+
+    println!("4. Submitting");
+    let resp = gelato
+        .send_forward_request_with_quote(&request, &quote)
+        .await?;
+    println!("   Task id: {}", resp.task_id());
+
+    println!("5. Tracking to completion");
+    let execution = gelato.track_task(resp.task_id(), request).await?;
+    println!(
+        "   Executed in tx {:?} at block {}",
+        execution.transaction_hash, execution.block_number
+    );
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+//! Preflight checks before relaying on a chain.
+//!
+//! Usage: `cargo run --example doctor -- <chain_id> [fee_token] [--rpc <url> --sponsor <address>]`
+//!
+//! Exits with a non-zero status if any check fails.
+
+use std::{env, process};
+
+use ethers::providers::{Http, Provider};
+use gelato_sdk::{preflight::Preflight, *};
+
+fn flag(args: &[String], name: &str) -> Option<String> {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let args = env::args().collect::<Vec<_>>();
+    eyre::ensure!(
+        args.len() >= 2,
+        "Usage: doctor <chain_id> [fee_token] [--rpc <url> --sponsor <address>]"
+    );
+    let chain_id: u64 = args[1].parse()?;
+
+    let gelato = GelatoClient::default();
+    let mut preflight = Preflight::new(&gelato, chain_id);
+    if let Some(token) = args.get(2).filter(|a| !a.starts_with("--")) {
+        preflight = preflight.fee_token(token.parse::<FeeToken>()?);
+    }
+
+    let report = match (flag(&args, "--rpc"), flag(&args, "--sponsor")) {
+        (Some(rpc), Some(sponsor)) => {
+            let provider = Provider::<Http>::try_from(rpc.as_str())?;
+            preflight
+                .run_with_sponsor(&provider, sponsor.parse()?)
+                .await
+        }
+        _ => preflight.run().await,
+    };
+
+    println!("{report}");
+    if !report.passed() {
+        process::exit(1);
+    }
+    Ok(())
+}
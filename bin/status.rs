@@ -1,14 +1,40 @@
-use std::env;
+use std::{env, time::Duration};
 
-use gelato_sdk::*;
+use gelato_sdk::{
+    timer::{FuturesTimer, Timer},
+    *,
+};
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
 
 #[tokio::main]
 async fn main() -> Result<(), reqwest::Error> {
     let gelato = GelatoClient::default();
 
-    let id = &env::args().collect::<Vec<_>>()[1];
-    let task_status = gelato.get_task_status(id.parse().unwrap()).await.unwrap();
-    println!("Task status: {task_status:?}");
+    let args = env::args().collect::<Vec<_>>();
+    let id = args[1].parse::<TaskId>().unwrap();
+    let watch = args.iter().skip(2).any(|arg| arg == "--watch");
+
+    if !watch {
+        let task_status = gelato.get_task_status(id).await.unwrap();
+        println!("Task status: {task_status:?}");
+        return Ok(());
+    }
+
+    // print a summary line on every change, until the task is terminal
+    let mut last = None;
+    loop {
+        let task_status = gelato.get_task_status(id).await.unwrap();
+        let summary = task_status.summary();
+        if last.as_ref() != Some(&task_status) {
+            println!("{summary}");
+        }
+        if task_status.task_state.is_terminal() {
+            break;
+        }
+        last = Some(task_status);
+        FuturesTimer.sleep(WATCH_INTERVAL).await;
+    }
 
     Ok(())
 }
@@ -0,0 +1,20 @@
+use std::env;
+
+use ethers::signers::LocalWallet;
+use gelato_sdk::vectors;
+
+/// Print EIP-712 test vectors for every request type as JSON
+///
+/// Usage: `vectors [private_key]`
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let key = env::args().nth(1).unwrap_or_else(|| "11".repeat(32));
+    let signer: LocalWallet = key.parse()?;
+
+    let vectors = vectors::generate(&signer).await?;
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&vectors::to_json(&vectors)?)?
+    );
+    Ok(())
+}
@@ -0,0 +1,404 @@
+use std::{thread, time::Duration};
+
+use ethers_core::types::{U256, U64};
+use reqwest::Url;
+
+use crate::{
+    client::{correlation_id, read_response},
+    offline::SignedRequest,
+    rpc::{self, Execution},
+    task::{resolve, TaskError},
+    ClientError, ClientResult, FeeToken, IntoChainId, NetworkInfo, TaskId, API_KEY_HEADER,
+    CORRELATION_ID_HEADER,
+};
+
+/// A blocking Gelato Relay Client, for use outside of async code.
+///
+/// Mirrors the request methods of the async [`GelatoClient`], and takes its
+/// URLs, API key and user agent from one. The caches, chain gate, retry
+/// policy, metrics observer and interceptors of the async client are not
+/// applied.
+///
+/// Like `reqwest::blocking`, it must not be used within an async runtime
+///
+/// [`GelatoClient`]: crate::GelatoClient
+#[derive(Debug, Clone, Default)]
+pub struct GelatoClient {
+    config: crate::GelatoClient,
+    client: reqwest::blocking::Client,
+}
+
+impl From<crate::GelatoClient> for GelatoClient {
+    fn from(config: crate::GelatoClient) -> Self {
+        Self {
+            config,
+            client: Default::default(),
+        }
+    }
+}
+
+impl GelatoClient {
+    /// Instantiate a new client with a specific URL, used for both relay and
+    /// API endpoints
+    ///
+    /// # Errors
+    ///
+    /// If the url param cannot be parsed as a URL
+    pub fn new<S: reqwest::IntoUrl>(url: S) -> ClientResult<Self> {
+        Ok(crate::GelatoClient::new(url)?.into())
+    }
+
+    /// Instantiate a client using Gelato's staging endpoints. See
+    /// [`GelatoClient::staging`](crate::GelatoClient::staging)
+    pub fn staging() -> Self {
+        crate::GelatoClient::staging().into()
+    }
+
+    /// Use a specific blocking reqwest Client
+    #[must_use]
+    pub fn with_client(mut self, client: reqwest::blocking::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// The async client providing URLs, the API key and the user agent
+    pub fn config(&self) -> &crate::GelatoClient {
+        &self.config
+    }
+
+    fn authenticate(
+        &self,
+        req: reqwest::blocking::RequestBuilder,
+    ) -> reqwest::blocking::RequestBuilder {
+        let req = req
+            .header(reqwest::header::USER_AGENT, self.config.user_agent())
+            .header(CORRELATION_ID_HEADER, correlation_id());
+        match self.config.api_key() {
+            Some(key) => req.header(API_KEY_HEADER, key),
+            None => req,
+        }
+    }
+
+    fn get_url<T>(&self, url: Url) -> ClientResult<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let resp = self.authenticate(self.client.get(url)).send()?;
+        let status = resp.status();
+        read_response(status, &resp.text()?)
+    }
+
+    fn post_url<B, T>(&self, url: Url, body: &B) -> ClientResult<T>
+    where
+        B: serde::Serialize + ?Sized,
+        T: serde::de::DeserializeOwned,
+    {
+        let resp = self.authenticate(self.client.post(url)).json(body).send()?;
+        let status = resp.status();
+        read_response(status, &resp.text()?)
+    }
+
+    fn post_sponsored<T>(
+        &self,
+        chain_id: u64,
+        endpoint: &str,
+        request: &T,
+    ) -> ClientResult<rpc::RelayResponse>
+    where
+        T: serde::Serialize,
+    {
+        let sponsor_api_key = self.config.api_key().ok_or(ClientError::MissingApiKey)?;
+        let body = rpc::Sponsored {
+            request,
+            sponsor_api_key,
+        };
+        self.post_url(self.config.relay_v2_url(chain_id, endpoint)?, &body)
+    }
+
+    /// Send a GET request to an arbitrary endpoint, resolved against the
+    /// relay URL
+    pub fn raw_get(&self, path: &str) -> ClientResult<serde_json::Value> {
+        self.get_json(path, &())
+    }
+
+    /// Send a POST request with a JSON body to an arbitrary endpoint,
+    /// resolved against the relay URL
+    pub fn raw_post<B>(&self, path: &str, body: &B) -> ClientResult<serde_json::Value>
+    where
+        B: serde::Serialize + ?Sized,
+    {
+        self.post_json(path, body)
+    }
+
+    /// Send a GET request to an arbitrary endpoint, with `query` encoded as
+    /// the query string, and deserialize the JSON response
+    pub fn get_json<T, Q>(&self, path: &str, query: &Q) -> ClientResult<T>
+    where
+        T: serde::de::DeserializeOwned,
+        Q: serde::Serialize + ?Sized,
+    {
+        let mut url = self.config.relay_url().join(path)?;
+        let query = serde_urlencoded::to_string(query)?;
+        if !query.is_empty() {
+            url.set_query(Some(&query));
+        }
+        self.get_url(url)
+    }
+
+    /// Send a POST request with a JSON body to an arbitrary endpoint, and
+    /// deserialize the JSON response
+    pub fn post_json<T, B>(&self, path: &str, body: &B) -> ClientResult<T>
+    where
+        T: serde::de::DeserializeOwned,
+        B: serde::Serialize + ?Sized,
+    {
+        self.post_url(self.config.relay_url().join(path)?, body)
+    }
+
+    /// Send a transaction over the relay
+    pub fn send_relay_transaction(
+        &self,
+        params: &rpc::RelayRequest,
+        chain_id: impl IntoChainId,
+    ) -> ClientResult<rpc::RelayResponse> {
+        let url = self
+            .config
+            .send_relay_transaction_url(chain_id.into_chain_id())?;
+        self.post_url(url, params)
+    }
+
+    /// Send a relay v2 sponsored call
+    pub fn sponsored_call(
+        &self,
+        req: &rpc::SponsoredCallRequest,
+    ) -> ClientResult<rpc::RelayResponse> {
+        self.post_sponsored(req.chain_id, "sponsored-call", req)
+    }
+
+    /// Send a signed relay v2 sponsored ERC-2771 call
+    pub fn sponsored_call_erc2771(
+        &self,
+        req: &rpc::SignedSponsoredCallErc2771,
+    ) -> ClientResult<rpc::RelayResponse> {
+        self.post_sponsored(req.chain_id, "sponsored-call-erc2771", req)
+    }
+
+    /// Send a signed relay v2 sponsored ERC-2771 call using concurrent replay
+    /// protection
+    pub fn sponsored_call_concurrent_erc2771(
+        &self,
+        req: &rpc::SignedSponsoredCallConcurrentErc2771,
+    ) -> ClientResult<rpc::RelayResponse> {
+        let request = rpc::Concurrent {
+            request: req,
+            is_concurrent: true,
+        };
+        self.post_sponsored(req.chain_id, "sponsored-call-erc2771", &request)
+    }
+
+    /// Send a relay v2 call with sync fee
+    pub fn call_with_sync_fee(
+        &self,
+        req: &rpc::CallWithSyncFeeRequest,
+    ) -> ClientResult<rpc::RelayResponse> {
+        self.post_url(
+            self.config
+                .relay_v2_url(req.chain_id, "call-with-sync-fee")?,
+            req,
+        )
+    }
+
+    /// Send a signed relay v2 ERC-2771 call with sync fee
+    pub fn call_with_sync_fee_erc2771(
+        &self,
+        req: &rpc::SignedCallWithSyncFeeErc2771,
+    ) -> ClientResult<rpc::RelayResponse> {
+        self.post_url(
+            self.config
+                .relay_v2_url(req.chain_id, "call-with-sync-fee-erc2771")?,
+            req,
+        )
+    }
+
+    /// Send a signed relay v2 ERC-2771 call with sync fee using concurrent
+    /// replay protection
+    pub fn call_with_sync_fee_concurrent_erc2771(
+        &self,
+        req: &rpc::SignedCallWithSyncFeeConcurrentErc2771,
+    ) -> ClientResult<rpc::RelayResponse> {
+        let request = rpc::Concurrent {
+            request: req,
+            is_concurrent: true,
+        };
+        self.post_url(
+            self.config
+                .relay_v2_url(req.chain_id, "call-with-sync-fee-erc2771")?,
+            &request,
+        )
+    }
+
+    /// Send a transaction forward call
+    pub fn send_forward_call(&self, params: &rpc::ForwardCall) -> ClientResult<rpc::RelayResponse> {
+        let url = self.config.send_forward_request_url(params.chain_id);
+        self.post_url(url, params)
+    }
+
+    /// Send a transaction forward request
+    pub fn send_forward_request(
+        &self,
+        params: &rpc::SignedForwardRequest,
+    ) -> ClientResult<rpc::RelayResponse> {
+        let url = self.config.send_forward_request_url(params.chain_id);
+        self.post_url(url, params)
+    }
+
+    /// Send a meta tx request
+    pub fn send_meta_tx_request(
+        &self,
+        params: &rpc::SignedMetaTxRequest,
+    ) -> ClientResult<rpc::RelayResponse> {
+        let url = self.config.send_forward_request_url(params.chain_id);
+        self.post_url(url, params)
+    }
+
+    /// Send a signed forward request or meta tx request
+    pub fn send_signed_request(&self, params: &SignedRequest) -> ClientResult<rpc::RelayResponse> {
+        match params {
+            SignedRequest::ForwardRequest(req) => self.send_forward_request(req),
+            SignedRequest::MetaTxRequest(req) => self.send_meta_tx_request(req),
+        }
+    }
+
+    /// Get a list of supported chains
+    pub fn get_gelato_relay_chains(&self) -> ClientResult<Vec<u64>> {
+        Ok(self
+            .get_url::<rpc::RelayChainsResponse>(self.config.relay_chains_url())?
+            .relays())
+    }
+
+    /// True if the chain is supported by the relay
+    pub fn is_chain_supported(&self, chain_id: impl IntoChainId) -> ClientResult<bool> {
+        Ok(self
+            .get_gelato_relay_chains()?
+            .contains(&chain_id.into_chain_id()))
+    }
+
+    /// Get a list of chains with an active fee oracle
+    pub fn get_oracle_chains(&self) -> ClientResult<Vec<u64>> {
+        Ok(self
+            .get_url::<rpc::OracleChainsResponse>(self.config.oracle_chains_url())?
+            .oracles())
+    }
+
+    /// Get the chains supported by the relay, with whether each has a fee
+    /// oracle, and the contracts known to the SDK
+    pub fn get_supported_networks(&self) -> ClientResult<Vec<NetworkInfo>> {
+        let relay_chains = self.get_gelato_relay_chains()?;
+        let oracle_chains = self.get_oracle_chains()?;
+        Ok(NetworkInfo::combine(&relay_chains, &oracle_chains))
+    }
+
+    /// Get the payment tokens accepted by the fee oracle on a chain
+    pub fn get_payment_tokens(&self, chain_id: impl IntoChainId) -> ClientResult<Vec<FeeToken>> {
+        let url = self.config.payment_tokens_url(chain_id.into_chain_id())?;
+        Ok(self
+            .get_url::<rpc::PaymentTokensResponse>(url)?
+            .payment_tokens())
+    }
+
+    /// Get the estimated fee for a specific amount of gas on a specific chain,
+    /// denominated in a specific payment token
+    pub fn get_estimated_fee(
+        &self,
+        chain_id: impl IntoChainId,
+        payment_token: impl Into<FeeToken>,
+        gas_limit: U64,
+        is_high_priority: bool,
+    ) -> ClientResult<U256> {
+        let query = rpc::EstimatedFeeRequest {
+            payment_token: payment_token.into(),
+            gas_limit,
+            is_high_priority,
+        };
+        let url = self
+            .config
+            .estimated_fee_url(chain_id.into_chain_id(), &query)?;
+        Ok(self
+            .get_url::<rpc::EstimatedFeeResponse>(url)?
+            .estimated_fee())
+    }
+
+    /// Get the estimated fee for a specific amount of gas on a specific chain,
+    /// denominated in the chain's native token
+    pub fn get_estimated_native_fee(
+        &self,
+        chain_id: impl IntoChainId,
+        gas_limit: U64,
+        is_high_priority: bool,
+    ) -> ClientResult<U256> {
+        self.get_estimated_fee(chain_id, FeeToken::default(), gas_limit, is_high_priority)
+    }
+
+    /// Fetch all statuses the API reports for a task
+    pub fn get_task_statuses(
+        &self,
+        task_id: impl Into<TaskId>,
+    ) -> ClientResult<Vec<rpc::TransactionStatus>> {
+        let url = self.config.get_task_status_url(task_id.into());
+        self.get_url::<rpc::TaskStatusResponse>(url)?
+            .into_statuses()
+    }
+
+    /// Fetch the status of a task. The first of the statuses reported by
+    /// the API
+    pub fn get_task_status(
+        &self,
+        task_id: impl Into<TaskId>,
+    ) -> ClientResult<rpc::TransactionStatus> {
+        let task_id = task_id.into();
+        self.get_task_statuses(task_id)?
+            .into_iter()
+            .next()
+            .ok_or(ClientError::TaskNotFound(task_id))
+    }
+
+    /// Fetch the status of a task from the v2 `tasks/status/{taskId}`
+    /// endpoint, served from the relay URL
+    pub fn get_task_status_v2(
+        &self,
+        task_id: impl Into<TaskId>,
+    ) -> ClientResult<rpc::TaskStatusV2> {
+        let url = self.config.get_task_status_v2_url(task_id.into());
+        self.get_url::<rpc::TaskStatusV2Response>(url)
+            .map(|resp| resp.task)
+    }
+
+    /// Block the current thread until a task reaches a terminal state,
+    /// polling every `interval`. Tolerates up to `retries` failed requests
+    pub fn wait_for_task(
+        &self,
+        task_id: impl Into<TaskId>,
+        interval: Duration,
+        retries: usize,
+    ) -> Result<Execution, TaskError> {
+        let task_id = task_id.into();
+        let mut failures = 0;
+        loop {
+            match self.get_task_status(task_id) {
+                Ok(status) => {
+                    if let Some(outcome) = resolve(status) {
+                        return outcome;
+                    }
+                }
+                Err(e) => {
+                    trace_warn!(task_id = ?task_id, error = %e, "Error while polling task");
+                    failures += 1;
+                    if failures > retries {
+                        return Err(TaskError::TooManyRetries);
+                    }
+                }
+            }
+            thread::sleep(interval);
+        }
+    }
+}
@@ -0,0 +1,647 @@
+use ethers_core::types::{
+    transaction::eip2718::TypedTransaction, Address, Bytes, NameOrAddress, TransactionRequest,
+    H256, U256,
+};
+
+use std::time::{Duration, SystemTime};
+
+use super::error::{check_calldata, check_missing, check_value, BuilderError};
+use crate::{
+    clock::{unix_secs, unix_secs_in, DeadlineClock, SystemClock},
+    rpc::{
+        random_salt, CallWithSyncFeeConcurrentErc2771, CallWithSyncFeeErc2771,
+        SponsoredCallConcurrentErc2771, SponsoredCallErc2771,
+    },
+    FeeToken, IntoChainId,
+};
+#[cfg(feature = "signers")]
+use crate::{
+    rpc::{
+        SignedCallWithSyncFeeConcurrentErc2771, SignedCallWithSyncFeeErc2771,
+        SignedSponsoredCallConcurrentErc2771, SignedSponsoredCallErc2771,
+    },
+    GelatoApi, GelatoTask,
+};
+
+/// Implement the setters shared by all ERC-2771 builders, signing as the
+/// user, and building and submitting with the user signer attached
+macro_rules! erc2771_builder {
+    ($builder:ident, $signed:ty, $submit:ident) => {
+        impl $builder {
+            /// Set `chain_id`. Defaults to 1 (ethereum)
+            pub fn chain_id(mut self, val: impl IntoChainId) -> Self {
+                self.chain_id = Some(val.into_chain_id());
+                self
+            }
+
+            /// Set `target`. Required.
+            pub fn target(mut self, val: Address) -> Self {
+                self.target = Some(val);
+                self
+            }
+
+            /// Set `data`. Defaults to empty bytes: `0x`
+            pub fn data(mut self, val: Bytes) -> Self {
+                self.data = Some(val);
+                self
+            }
+
+            /// Set `user`. Required. Overridden by the signer in `sign`
+            pub fn user(mut self, val: Address) -> Self {
+                self.user = Some(val);
+                self
+            }
+
+            /// Set `user_deadline`. Required
+            pub fn user_deadline(mut self, val: u64) -> Self {
+                self.user_deadline = Some(val);
+                self
+            }
+
+            /// Set `user_deadline` to `validity` from now, according to the
+            /// system clock
+            pub fn valid_for(mut self, validity: Duration) -> Self {
+                self.user_deadline = Some(DeadlineClock::new(SystemClock).deadline_after(validity));
+                self
+            }
+
+            /// Set `user_deadline` to `validity` from now, according to the
+            /// system clock. Unlike [`Self::valid_for`], no skew margin is
+            /// added. Fails if the deadline can't be represented
+            pub fn deadline_in(mut self, validity: Duration) -> Result<Self, BuilderError> {
+                self.user_deadline =
+                    Some(unix_secs_in(validity).ok_or(BuilderError::DeadlineOverflow(validity))?);
+                Ok(self)
+            }
+
+            /// Set `user_deadline` to a specific time
+            pub fn deadline_at(mut self, time: SystemTime) -> Self {
+                self.user_deadline = Some(unix_secs(time));
+                self
+            }
+
+            /// Build this request and sign it as the user. The user address
+            /// is set to that of the signer, and the chain id defaults to the
+            /// signer's
+            #[cfg(feature = "signers")]
+            pub async fn sign<S>(mut self, user: &S) -> Result<$signed, BuilderError>
+            where
+                S: ethers_signers::Signer,
+                S::Error: 'static,
+            {
+                if self.chain_id.is_none() {
+                    self.chain_id = Some(user.chain_id());
+                }
+                self.user = Some(user.address());
+                Ok(self.build()?.sign(user).await?)
+            }
+
+            /// Attach the user signer. The user address is set to that of the
+            /// signer, and the chain id defaults to the signer's
+            #[cfg(feature = "signers")]
+            pub fn with_user<S>(mut self, user: &S) -> Erc2771BuilderWithUser<'_, Self, S>
+            where
+                S: ethers_signers::Signer,
+            {
+                if self.chain_id.is_none() {
+                    self.chain_id = Some(user.chain_id());
+                }
+                self.user = Some(user.address());
+                Erc2771BuilderWithUser {
+                    builder: self,
+                    user,
+                }
+            }
+        }
+
+        #[cfg(feature = "signers")]
+        impl<'a, S> Erc2771BuilderWithUser<'a, $builder, S>
+        where
+            S: ethers_signers::Signer,
+            S::Error: 'static,
+        {
+            /// Build this request
+            pub async fn build(self) -> Result<$signed, BuilderError> {
+                self.builder.sign(self.user).await
+            }
+
+            /// Build and sign this request, send it to the relay, and get a
+            /// future tracking its status
+            pub async fn submit<'c, A>(
+                self,
+                client: &'c A,
+            ) -> Result<GelatoTask<'c, $signed, A>, BuilderError>
+            where
+                A: GelatoApi + ?Sized,
+            {
+                let request = self.build().await?;
+                let resp = client.$submit(&request).await?;
+                Ok(GelatoTask::new(resp.task_id(), client, request))
+            }
+        }
+    };
+}
+
+/// Implement conversion from transactions for an ERC-2771 builder with a
+/// sequential user nonce. Nonces above `u64::MAX` are left unset
+macro_rules! erc2771_from_tx {
+    ($builder:ident) => {
+        impl From<&TransactionRequest> for $builder {
+            fn from(tx: &TransactionRequest) -> Self {
+                let mut builder = $builder::default();
+
+                if let Some(NameOrAddress::Address(target)) = tx.to {
+                    builder = builder.target(target);
+                }
+                if let Some(data) = &tx.data {
+                    builder = builder.data(data.clone());
+                }
+                if let Some(nonce) = tx.nonce.and_then(|nonce| u64::try_from(nonce).ok()) {
+                    builder = builder.user_nonce(nonce);
+                }
+                if let Some(from) = tx.from {
+                    builder = builder.user(from);
+                }
+                builder.value = tx.value;
+
+                builder
+            }
+        }
+
+        impl From<&TypedTransaction> for $builder {
+            fn from(tx: &TypedTransaction) -> Self {
+                let mut builder = $builder::default();
+
+                if let Some(NameOrAddress::Address(target)) = tx.to() {
+                    builder = builder.target(*target);
+                }
+                if let Some(data) = tx.data() {
+                    builder = builder.data(data.clone());
+                }
+                if let Some(nonce) = tx.nonce().and_then(|nonce| u64::try_from(*nonce).ok()) {
+                    builder = builder.user_nonce(nonce);
+                }
+                if let Some(from) = tx.from() {
+                    builder = builder.user(*from);
+                }
+                builder.value = tx.value().copied();
+
+                builder
+            }
+        }
+    };
+}
+
+/// Builder for a [`SponsoredCallConcurrentErc2771`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SponsoredCallConcurrentErc2771Builder {
+    /// Chain id. Defaults to 1 (ethereum).
+    pub chain_id: Option<u64>,
+    /// Address of dApp's smart contract to call. Required
+    pub target: Option<Address>,
+    /// Payload for `target`. Defaults to empty bytes: `0x`
+    pub data: Option<Bytes>,
+    /// EOA of dapp's user. Required. May be set automatically by the user
+    /// signer
+    pub user: Option<Address>,
+    /// Unique salt. Defaults to a random salt
+    pub user_salt: Option<H256>,
+    /// Unix timestamp after which the request may not be executed. Required
+    pub user_deadline: Option<u64>,
+}
+
+erc2771_builder!(
+    SponsoredCallConcurrentErc2771Builder,
+    SignedSponsoredCallConcurrentErc2771,
+    sponsored_call_concurrent_erc2771
+);
+
+impl SponsoredCallConcurrentErc2771Builder {
+    /// Which keys need to be populated
+    pub fn missing_keys(&self) -> Vec<&'static str> {
+        let mut missing = vec![];
+        if self.target.is_none() {
+            missing.push("target");
+        }
+        if self.user.is_none() {
+            missing.push("user");
+        }
+        if self.user_deadline.is_none() {
+            missing.push("user_deadline");
+        }
+        missing
+    }
+
+    /// Set `user_salt`. Defaults to a random salt
+    pub fn user_salt(mut self, val: H256) -> Self {
+        self.user_salt = Some(val);
+        self
+    }
+
+    /// Build this request
+    pub fn build(self) -> Result<SponsoredCallConcurrentErc2771, BuilderError> {
+        check_missing(self.missing_keys())?;
+
+        let data = self.data.unwrap_or_default();
+        check_calldata(&data)?;
+
+        Ok(SponsoredCallConcurrentErc2771 {
+            chain_id: self.chain_id.unwrap_or(1),
+            target: self.target.unwrap(),
+            data,
+            user: self.user.unwrap(),
+            user_salt: self.user_salt.unwrap_or_else(random_salt),
+            user_deadline: self.user_deadline.unwrap(),
+        })
+    }
+}
+
+/// Builder for a [`SponsoredCallErc2771`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SponsoredCallErc2771Builder {
+    /// Chain id. Defaults to 1 (ethereum).
+    pub chain_id: Option<u64>,
+    /// Address of dApp's smart contract to call. Required
+    pub target: Option<Address>,
+    /// Payload for `target`. Defaults to empty bytes: `0x`
+    pub data: Option<Bytes>,
+    /// EOA of dapp's user. Required. May be set automatically by the user
+    /// signer
+    pub user: Option<Address>,
+    /// The user's nonce in the relay contract. Required
+    pub user_nonce: Option<u64>,
+    /// Unix timestamp after which the request may not be executed. Required
+    pub user_deadline: Option<u64>,
+    /// Native value of the transaction the builder was converted from. The
+    /// relay can't forward value, so building fails unless it is zero
+    pub value: Option<U256>,
+}
+
+erc2771_builder!(
+    SponsoredCallErc2771Builder,
+    SignedSponsoredCallErc2771,
+    sponsored_call_erc2771
+);
+erc2771_from_tx!(SponsoredCallErc2771Builder);
+
+impl SponsoredCallErc2771Builder {
+    /// Which keys need to be populated
+    pub fn missing_keys(&self) -> Vec<&'static str> {
+        let mut missing = vec![];
+        if self.target.is_none() {
+            missing.push("target");
+        }
+        if self.user.is_none() {
+            missing.push("user");
+        }
+        if self.user_nonce.is_none() {
+            missing.push("user_nonce");
+        }
+        if self.user_deadline.is_none() {
+            missing.push("user_deadline");
+        }
+        missing
+    }
+
+    /// Set `user_nonce`. Required
+    pub fn user_nonce(mut self, val: u64) -> Self {
+        self.user_nonce = Some(val);
+        self
+    }
+
+    /// Build this request
+    pub fn build(self) -> Result<SponsoredCallErc2771, BuilderError> {
+        check_missing(self.missing_keys())?;
+
+        let data = self.data.unwrap_or_default();
+        check_value(self.value)?;
+        check_calldata(&data)?;
+
+        Ok(SponsoredCallErc2771 {
+            chain_id: self.chain_id.unwrap_or(1),
+            target: self.target.unwrap(),
+            data,
+            user: self.user.unwrap(),
+            user_nonce: self.user_nonce.unwrap(),
+            user_deadline: self.user_deadline.unwrap(),
+        })
+    }
+}
+
+/// Builder for a [`CallWithSyncFeeErc2771`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CallWithSyncFeeErc2771Builder {
+    /// Chain id. Defaults to 1 (ethereum).
+    pub chain_id: Option<u64>,
+    /// Address of dApp's smart contract to call. Required
+    pub target: Option<Address>,
+    /// Payload for `target`. Defaults to empty bytes: `0x`
+    pub data: Option<Bytes>,
+    /// EOA of dapp's user. Required. May be set automatically by the user
+    /// signer
+    pub user: Option<Address>,
+    /// The user's nonce in the relay contract. Required
+    pub user_nonce: Option<u64>,
+    /// Unix timestamp after which the request may not be executed. Required
+    pub user_deadline: Option<u64>,
+    /// Token the target pays the fee in. Defaults to chain-native asset
+    /// (eth)
+    pub fee_token: Option<FeeToken>,
+    /// Whether the relay appends the relay context to the calldata. Defaults
+    /// to `true`
+    pub is_relay_context: Option<bool>,
+    /// Native value of the transaction the builder was converted from. The
+    /// relay can't forward value, so building fails unless it is zero
+    pub value: Option<U256>,
+}
+
+erc2771_builder!(
+    CallWithSyncFeeErc2771Builder,
+    SignedCallWithSyncFeeErc2771,
+    call_with_sync_fee_erc2771
+);
+erc2771_from_tx!(CallWithSyncFeeErc2771Builder);
+
+impl CallWithSyncFeeErc2771Builder {
+    /// Which keys need to be populated
+    pub fn missing_keys(&self) -> Vec<&'static str> {
+        let mut missing = vec![];
+        if self.target.is_none() {
+            missing.push("target");
+        }
+        if self.user.is_none() {
+            missing.push("user");
+        }
+        if self.user_nonce.is_none() {
+            missing.push("user_nonce");
+        }
+        if self.user_deadline.is_none() {
+            missing.push("user_deadline");
+        }
+        missing
+    }
+
+    /// Set `user_nonce`. Required
+    pub fn user_nonce(mut self, val: u64) -> Self {
+        self.user_nonce = Some(val);
+        self
+    }
+
+    /// Set `fee_token`. Defaults to chain-native asset (eth)
+    pub fn fee_token(mut self, val: impl Into<FeeToken>) -> Self {
+        self.fee_token = Some(val.into());
+        self
+    }
+
+    /// Set `is_relay_context`. Defaults to `true`
+    pub fn relay_context(mut self, val: bool) -> Self {
+        self.is_relay_context = Some(val);
+        self
+    }
+
+    /// Build this request
+    pub fn build(self) -> Result<CallWithSyncFeeErc2771, BuilderError> {
+        check_missing(self.missing_keys())?;
+
+        let data = self.data.unwrap_or_default();
+        check_value(self.value)?;
+        check_calldata(&data)?;
+
+        Ok(CallWithSyncFeeErc2771 {
+            chain_id: self.chain_id.unwrap_or(1),
+            target: self.target.unwrap(),
+            data,
+            user: self.user.unwrap(),
+            user_nonce: self.user_nonce.unwrap(),
+            user_deadline: self.user_deadline.unwrap(),
+            fee_token: self.fee_token.unwrap_or_default(),
+            is_relay_context: self.is_relay_context.unwrap_or(true),
+        })
+    }
+}
+
+/// Builder for a [`CallWithSyncFeeConcurrentErc2771`]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CallWithSyncFeeConcurrentErc2771Builder {
+    /// Chain id. Defaults to 1 (ethereum).
+    pub chain_id: Option<u64>,
+    /// Address of dApp's smart contract to call. Required
+    pub target: Option<Address>,
+    /// Payload for `target`. Defaults to empty bytes: `0x`
+    pub data: Option<Bytes>,
+    /// EOA of dapp's user. Required. May be set automatically by the user
+    /// signer
+    pub user: Option<Address>,
+    /// Unique salt. Defaults to a random salt
+    pub user_salt: Option<H256>,
+    /// Unix timestamp after which the request may not be executed. Required
+    pub user_deadline: Option<u64>,
+    /// Token the target pays the fee in. Defaults to chain-native asset
+    /// (eth)
+    pub fee_token: Option<FeeToken>,
+    /// Whether the relay appends the relay context to the calldata. Defaults
+    /// to `true`
+    pub is_relay_context: Option<bool>,
+}
+
+erc2771_builder!(
+    CallWithSyncFeeConcurrentErc2771Builder,
+    SignedCallWithSyncFeeConcurrentErc2771,
+    call_with_sync_fee_concurrent_erc2771
+);
+
+impl CallWithSyncFeeConcurrentErc2771Builder {
+    /// Which keys need to be populated
+    pub fn missing_keys(&self) -> Vec<&'static str> {
+        let mut missing = vec![];
+        if self.target.is_none() {
+            missing.push("target");
+        }
+        if self.user.is_none() {
+            missing.push("user");
+        }
+        if self.user_deadline.is_none() {
+            missing.push("user_deadline");
+        }
+        missing
+    }
+
+    /// Set `user_salt`. Defaults to a random salt
+    pub fn user_salt(mut self, val: H256) -> Self {
+        self.user_salt = Some(val);
+        self
+    }
+
+    /// Set `fee_token`. Defaults to chain-native asset (eth)
+    pub fn fee_token(mut self, val: impl Into<FeeToken>) -> Self {
+        self.fee_token = Some(val.into());
+        self
+    }
+
+    /// Set `is_relay_context`. Defaults to `true`
+    pub fn relay_context(mut self, val: bool) -> Self {
+        self.is_relay_context = Some(val);
+        self
+    }
+
+    /// Build this request
+    pub fn build(self) -> Result<CallWithSyncFeeConcurrentErc2771, BuilderError> {
+        check_missing(self.missing_keys())?;
+
+        let data = self.data.unwrap_or_default();
+        check_calldata(&data)?;
+
+        Ok(CallWithSyncFeeConcurrentErc2771 {
+            chain_id: self.chain_id.unwrap_or(1),
+            target: self.target.unwrap(),
+            data,
+            user: self.user.unwrap(),
+            user_salt: self.user_salt.unwrap_or_else(random_salt),
+            user_deadline: self.user_deadline.unwrap(),
+            fee_token: self.fee_token.unwrap_or_default(),
+            is_relay_context: self.is_relay_context.unwrap_or(true),
+        })
+    }
+}
+
+/// Builder for a signed ERC-2771 request, with the user signer attached.
+/// Values should be set before attaching the signer
+#[cfg(feature = "signers")]
+pub struct Erc2771BuilderWithUser<'a, B, S> {
+    builder: B,
+    user: &'a S,
+}
+
+#[cfg(feature = "signers")]
+impl<'a, B, S> Erc2771BuilderWithUser<'a, B, S> {
+    /// Detach the signer, returning the builder
+    pub fn into_inner(self) -> B {
+        self.builder
+    }
+}
+
+#[cfg(all(test, feature = "signers"))]
+mod test {
+    use ethers::signers::{LocalWallet, Signer};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn it_builds_concurrent_calls() {
+        let wallet: LocalWallet = "11".repeat(32).parse().unwrap();
+        let builder = SponsoredCallConcurrentErc2771Builder::default()
+            .chain_id(5)
+            .target(Address::repeat_byte(0x22))
+            .valid_for(Duration::from_secs(60));
+
+        assert_eq!(builder.missing_keys(), vec!["user"]);
+
+        let first = builder.clone().sign(&wallet).await.unwrap();
+        let second = builder.sign(&wallet).await.unwrap();
+        assert_eq!(first.user, wallet.address());
+        assert_ne!(first.user_salt, second.user_salt);
+    }
+
+    #[tokio::test]
+    async fn it_builds_sequential_calls_from_transactions() {
+        let wallet: LocalWallet = "11".repeat(32).parse().unwrap();
+        let tx: TypedTransaction = TransactionRequest::new()
+            .to(Address::repeat_byte(0x22))
+            .data(vec![1, 2, 3])
+            .nonce(7)
+            .into();
+
+        let signed = CallWithSyncFeeErc2771Builder::from(&tx)
+            .valid_for(Duration::from_secs(60))
+            .with_user(&wallet)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(signed.user, wallet.address());
+        assert_eq!(signed.user_nonce, 7);
+        assert_eq!(signed.chain_id, wallet.chain_id());
+        assert!(signed.is_relay_context);
+
+        let builder = SponsoredCallErc2771Builder::from(&tx);
+        assert_eq!(builder.missing_keys(), vec!["user", "user_deadline"]);
+
+        let tx = TransactionRequest::new()
+            .to(Address::repeat_byte(0x22))
+            .nonce(U256::MAX);
+        let builder = SponsoredCallErc2771Builder::from(&tx);
+        assert_eq!(
+            builder.missing_keys(),
+            vec!["user", "user_nonce", "user_deadline"]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_builds_concurrent_sync_fee_calls() {
+        let wallet: LocalWallet = "11".repeat(32).parse().unwrap();
+        let signed = CallWithSyncFeeConcurrentErc2771Builder::default()
+            .chain_id(5)
+            .target(Address::repeat_byte(0x22))
+            .fee_token(Address::repeat_byte(0xee))
+            .relay_context(false)
+            .valid_for(Duration::from_secs(60))
+            .with_user(&wallet)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(signed.user, wallet.address());
+        assert_eq!(*signed.fee_token, Address::repeat_byte(0xee));
+        assert!(!signed.is_relay_context);
+    }
+
+    #[tokio::test]
+    async fn it_submits_in_one_call() {
+        use crate::{testing::MockGelatoClient, TaskId};
+
+        let wallet: LocalWallet = "11".repeat(32).parse().unwrap();
+        let task_id = TaskId::from(H256::repeat_byte(1));
+        let mock = MockGelatoClient::new();
+        mock.push_response(task_id);
+
+        let task = SponsoredCallErc2771Builder::default()
+            .target(Address::repeat_byte(0x22))
+            .user_nonce(0)
+            .valid_for(Duration::from_secs(60))
+            .with_user(&wallet)
+            .submit(&mock)
+            .await
+            .unwrap();
+        assert_eq!(task.id(), task_id);
+        assert_eq!(mock.submissions().len(), 1);
+    }
+
+    #[test]
+    fn it_reports_missing_fields() {
+        let err = SponsoredCallConcurrentErc2771Builder::default()
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            BuilderError::MissingFields(ref missing) if missing == &vec!["target", "user", "user_deadline"]
+        ));
+    }
+
+    #[test]
+    fn it_computes_deadlines() {
+        let time = std::time::UNIX_EPOCH + Duration::from_secs(1_000);
+        let builder = SponsoredCallConcurrentErc2771Builder::default().deadline_at(time);
+        assert_eq!(builder.user_deadline, Some(1_000));
+
+        let deadline = SponsoredCallConcurrentErc2771Builder::default()
+            .deadline_in(Duration::from_secs(60))
+            .unwrap()
+            .user_deadline
+            .unwrap();
+        let now = unix_secs(crate::time::now());
+        assert!((now + 59..=now + 60).contains(&deadline));
+
+        assert!(matches!(
+            SponsoredCallConcurrentErc2771Builder::default().deadline_in(Duration::MAX),
+            Err(BuilderError::DeadlineOverflow(_))
+        ));
+    }
+}
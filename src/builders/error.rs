@@ -0,0 +1,227 @@
+use ethers_core::types::U256;
+
+use std::time::Duration;
+
+use crate::{
+    rpc::{Erc2771Error, ForwardRequestError, MetaTxRequestError},
+    ClientError, PaymentType,
+};
+
+/// Errors produced when building requests
+#[derive(Debug, thiserror::Error)]
+pub enum BuilderError {
+    /// Required values were not set. Contains the names of the missing
+    /// builder fields
+    #[error("Missing required values in build: {}", .0.join(", "))]
+    MissingFields(Vec<&'static str>),
+    /// The payment type can't be used with the request type
+    #[error("Payment type {0:?} may not be used with this request")]
+    InappropriatePaymentType(PaymentType),
+    /// The request carries native value, which the relay can't forward
+    #[error("Value {0} can't be relayed. Gelato relays do not forward native value")]
+    ValueNotSupported(U256),
+    /// Calldata exceeds the relay's payload limit
+    #[error("Calldata is {size} bytes. The relay accepts at most {limit} bytes")]
+    CalldataTooLarge {
+        /// Size of the calldata
+        size: usize,
+        /// Maximum size accepted by the relay
+        limit: usize,
+    },
+    /// Gas limit exceeds the relay's cap for the chain
+    #[error("Gas limit {gas} exceeds the relay cap of {cap} on chain {chain_id}")]
+    GasAboveCap {
+        /// Requested gas limit
+        gas: U256,
+        /// Maximum gas limit
+        cap: U256,
+        /// Chain id
+        chain_id: u64,
+    },
+    /// The fee buffer would lower `max_fee`. Buffers must be at least 1.0,
+    /// i.e. 10_000 bps
+    #[error("Fee buffer of {0} bps is below 10000 bps and would lower max_fee")]
+    InvalidFeeBuffer(u32),
+    /// A deadline relative to now lies beyond the times the system can
+    /// represent
+    #[error("A deadline {0:?} from now can't be represented")]
+    DeadlineOverflow(Duration),
+    /// An on-chain nonce does not fit the request's nonce type
+    #[error("Nonce {0} is too large for a request nonce")]
+    NonceTooLarge(U256),
+    /// The fee quote used to price the request has expired
+    #[error("Fee quote expired. Re-estimate the fee before building")]
+    FeeQuoteExpired,
+    /// No `GelatoRelayForwarder` address is known for the chain
+    #[error("No GelatoRelayForwarder address known for chain {0}")]
+    UnknownForwarder(u64),
+    /// No `GelatoMetaBox` address is known for the chain
+    #[error("No GelatoMetaBox address known for chain {0}")]
+    UnknownMetaBox(u64),
+    /// A request to the Gelato API made while building failed
+    #[error("{0}")]
+    Client(#[from] ClientError),
+    /// A provider request made while building failed
+    #[error("{0}")]
+    Provider(Box<dyn std::error::Error + Send + Sync>),
+    /// Signing the built forward request failed
+    #[error("{0}")]
+    ForwardRequest(#[from] ForwardRequestError),
+    /// Signing the built meta tx request failed
+    #[error("{0}")]
+    MetaTxRequest(#[from] MetaTxRequestError),
+    /// Signing the built ERC-2771 request failed
+    #[error("{0}")]
+    Erc2771(#[from] Erc2771Error),
+}
+
+/// Ensure all required values are set
+pub(crate) fn check_missing(missing: Vec<&'static str>) -> Result<(), BuilderError> {
+    if !missing.is_empty() {
+        return Err(BuilderError::MissingFields(missing));
+    }
+    Ok(())
+}
+
+/// Ensure no native value is attached, as the relay can't forward it
+pub(crate) fn check_value(value: Option<U256>) -> Result<(), BuilderError> {
+    match value {
+        Some(value) if !value.is_zero() => Err(BuilderError::ValueNotSupported(value)),
+        _ => Ok(()),
+    }
+}
+
+/// Ensure calldata fits within the relay's payload limits
+pub(crate) fn check_calldata(data: &[u8]) -> Result<(), BuilderError> {
+    if data.len() > crate::utils::MAX_CALLDATA_SIZE {
+        return Err(BuilderError::CalldataTooLarge {
+            size: data.len(),
+            limit: crate::utils::MAX_CALLDATA_SIZE,
+        });
+    }
+    Ok(())
+}
+
+/// Ensure the gas limit is within the cap. If no cap is given, the chain's
+/// default cap is used. Chains with no default cap are not checked
+pub(crate) fn check_gas(gas: U256, cap: Option<U256>, chain_id: u64) -> Result<(), BuilderError> {
+    if let Some(cap) = cap.or_else(|| crate::utils::get_max_gas(chain_id)) {
+        if gas > cap {
+            return Err(BuilderError::GasAboveCap { gas, cap, chain_id });
+        }
+    }
+    Ok(())
+}
+
+/// Ensure a `GelatoRelayForwarder` is known for the chain, so that the
+/// request can be signed
+pub(crate) fn check_forwarder(chain_id: u64) -> Result<(), BuilderError> {
+    crate::get_forwarder(chain_id)
+        .map(|_| ())
+        .ok_or(BuilderError::UnknownForwarder(chain_id))
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_core::types::Address;
+
+    use super::*;
+    use crate::{ForwardRequestBuilder, MetaTxRequestBuilder};
+
+    #[test]
+    fn it_rejects_chains_without_contracts() {
+        let err = ForwardRequestBuilder::default()
+            .chain_id(1234u64)
+            .target(Address::repeat_byte(1))
+            .sponsor_address(Address::repeat_byte(2))
+            .max_fee(1u64)
+            .gas(100_000u64)
+            .nonce(0)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, BuilderError::UnknownForwarder(1234)));
+    }
+
+    #[test]
+    fn it_builds_on_supported_chains() {
+        let forward = ForwardRequestBuilder::default()
+            .chain_id(5u64)
+            .target(Address::repeat_byte(1))
+            .sponsor_address(Address::repeat_byte(2))
+            .max_fee(1u64)
+            .gas(100_000u64)
+            .nonce(0)
+            .build()
+            .unwrap();
+        assert_eq!(forward.chain_id, 5);
+
+        let meta_tx = MetaTxRequestBuilder::default()
+            .chain_id(5u64)
+            .target(Address::repeat_byte(1))
+            .user_address(Address::repeat_byte(2))
+            .max_fee(1u64)
+            .gas(100_000u64)
+            .nonce(0)
+            .build()
+            .unwrap();
+        assert_eq!(meta_tx.chain_id, 5);
+    }
+
+    #[test]
+    fn it_rejects_value_bearing_transactions() {
+        let tx = ethers_core::types::TransactionRequest::new()
+            .to(Address::repeat_byte(1))
+            .from(Address::repeat_byte(2))
+            .value(1);
+        let err = ForwardRequestBuilder::from(&tx)
+            .max_fee(1u64)
+            .gas(100_000u64)
+            .nonce(0)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, BuilderError::ValueNotSupported(value) if value == 1.into()));
+    }
+
+    #[test]
+    fn it_rejects_oversized_calldata() {
+        use crate::utils::MAX_CALLDATA_SIZE;
+
+        assert!(check_calldata(&[]).is_ok());
+        assert!(check_calldata(&vec![0; MAX_CALLDATA_SIZE]).is_ok());
+        assert!(matches!(
+            check_calldata(&vec![0; MAX_CALLDATA_SIZE + 1]),
+            Err(BuilderError::CalldataTooLarge { size, limit })
+                if size == MAX_CALLDATA_SIZE + 1 && limit == MAX_CALLDATA_SIZE
+        ));
+
+        let err = ForwardRequestBuilder::default()
+            .chain_id(5u64)
+            .target(Address::repeat_byte(1))
+            .data(vec![0; MAX_CALLDATA_SIZE + 1].into())
+            .sponsor_address(Address::repeat_byte(2))
+            .max_fee(1u64)
+            .gas(100_000u64)
+            .nonce(0)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, BuilderError::CalldataTooLarge { .. }));
+    }
+
+    #[test]
+    fn it_caps_gas() {
+        let cap = crate::get_max_gas(5).unwrap();
+        assert!(check_gas(cap, None, 5).is_ok());
+        assert!(matches!(
+            check_gas(cap + 1, None, 5),
+            Err(BuilderError::GasAboveCap { gas, cap: limit, chain_id: 5 })
+                if gas == cap + 1 && limit == cap
+        ));
+
+        // an explicit cap replaces the chain's
+        assert!(check_gas(1_000u64.into(), Some(1_000u64.into()), 5).is_ok());
+        assert!(check_gas(1_001u64.into(), Some(1_000u64.into()), 5).is_err());
+
+        // chains without a known cap are not checked
+        assert!(check_gas(U256::MAX, None, 1234).is_ok());
+    }
+}
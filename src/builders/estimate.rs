@@ -0,0 +1,302 @@
+#[cfg(feature = "providers")]
+use ethers_contract::abigen;
+use ethers_core::types::{U256, U64};
+#[cfg(feature = "providers")]
+use ethers_core::{
+    abi::{AbiDecode, AbiEncode},
+    types::{Address, Bytes, TransactionRequest},
+};
+#[cfg(feature = "providers")]
+use ethers_providers::Middleware;
+
+use super::error::BuilderError;
+use crate::{FeeToken, GelatoApi};
+
+#[cfg(feature = "providers")]
+abigen!(
+    GelatoMetaBox,
+    r#"[
+        function nonce(address user) external view returns (uint256)
+    ]"#
+);
+
+// increase `val` by `percent` percent, saturating
+fn pad(val: U256, percent: u64) -> U256 {
+    val.saturating_mul(U256::from(percent) + 100) / 100
+}
+
+/// A fee buffer factor in basis points. Negative and NaN factors are treated
+/// as 0, and rejected by [`apply_fee_buffer`]
+pub(crate) fn buffer_bps(factor: f64) -> u32 {
+    (factor * 10_000.0).round() as u32
+}
+
+/// `max_fee` multiplied by a buffer in basis points, if any. Buffers below
+/// 1.0 would lower `max_fee`, so are rejected
+pub(crate) fn apply_fee_buffer(
+    max_fee: U256,
+    buffer_bps: Option<u32>,
+) -> Result<U256, BuilderError> {
+    let bps = match buffer_bps {
+        Some(bps) if bps < 10_000 => return Err(BuilderError::InvalidFeeBuffer(bps)),
+        Some(bps) => U256::from(bps),
+        None => return Ok(max_fee),
+    };
+    Ok(match max_fee.checked_mul(bps) {
+        Some(buffered) => buffered / 10_000,
+        None => (max_fee / 10_000).saturating_mul(bps),
+    })
+}
+
+/// The fee oracle's estimate for `gas` on a chain, increased by
+/// `margin_percent` percent. Uses the high-priority quote if requested
+pub(crate) async fn estimate_max_fee<A>(
+    client: &A,
+    chain_id: u64,
+    fee_token: FeeToken,
+    gas: Option<U256>,
+    is_high_priority: bool,
+    margin_percent: u64,
+) -> Result<U256, BuilderError>
+where
+    A: GelatoApi + ?Sized,
+{
+    let gas = gas.ok_or_else(|| BuilderError::MissingFields(vec!["gas"]))?;
+    let gas_limit = U64::from(gas.min(U256::from(u64::MAX)).as_u64());
+    let fee = client
+        .get_estimated_fee(chain_id, fee_token, gas_limit, is_high_priority)
+        .await?;
+    Ok(pad(fee, margin_percent))
+}
+
+/// The provider's gas estimate for a call from `from` to `target`, increased
+/// by `padding_percent` percent
+#[cfg(feature = "providers")]
+pub(crate) async fn estimate_gas<M>(
+    provider: &M,
+    from: Option<Address>,
+    target: Option<Address>,
+    data: Bytes,
+    padding_percent: u64,
+) -> Result<U256, BuilderError>
+where
+    M: Middleware,
+    M::Error: 'static,
+{
+    let target = target.ok_or_else(|| BuilderError::MissingFields(vec!["target"]))?;
+    let mut tx = TransactionRequest::new().to(target).data(data);
+    if let Some(from) = from {
+        tx = tx.from(from);
+    }
+    let gas = provider
+        .estimate_gas(&tx.into())
+        .await
+        .map_err(|e| BuilderError::Provider(Box::new(e)))?;
+    Ok(pad(gas, padding_percent))
+}
+
+/// The user's current nonce in the chain's `GelatoMetaBox`
+#[cfg(feature = "providers")]
+pub(crate) async fn fetch_nonce<M>(
+    provider: &M,
+    chain_id: u64,
+    user: Option<Address>,
+) -> Result<usize, BuilderError>
+where
+    M: Middleware,
+    M::Error: 'static,
+{
+    let user = user.ok_or_else(|| BuilderError::MissingFields(vec!["user"]))?;
+    let meta_box = crate::get_meta_box(chain_id).ok_or(BuilderError::UnknownMetaBox(chain_id))?;
+
+    let tx = TransactionRequest::new()
+        .to(meta_box)
+        .data(NonceCall { user }.encode());
+
+    let output = provider
+        .call(&tx.into(), None)
+        .await
+        .map_err(|e| BuilderError::Provider(Box::new(e)))?;
+    decode_nonce(&output)
+}
+
+// the nonce returned by `GelatoMetaBox::nonce`
+#[cfg(feature = "providers")]
+fn decode_nonce(output: &[u8]) -> Result<usize, BuilderError> {
+    let nonce = U256::decode(output).map_err(|e| BuilderError::Provider(Box::new(e)))?;
+    usize::try_from(nonce).map_err(|_| BuilderError::NonceTooLarge(nonce))
+}
+
+/// Calldata as received by an ERC-2771 recipient: `data` with the original
+/// sender appended
+#[cfg(feature = "providers")]
+pub(crate) fn erc2771_calldata(data: Option<&Bytes>, sender: Option<Address>) -> Bytes {
+    let mut calldata = data.map(|data| data.to_vec()).unwrap_or_default();
+    if let Some(sender) = sender {
+        calldata.extend_from_slice(sender.as_bytes());
+    }
+    calldata.into()
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_core::types::Address;
+
+    use crate::MetaTxRequestBuilder;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn it_estimates_max_fee_with_margin() {
+        let mock = crate::testing::MockGelatoClient::new().with_estimated_fee(1_000u64.into());
+
+        let builder = crate::ForwardRequestBuilder::default()
+            .target(Address::repeat_byte(1))
+            .gas(100_000u64)
+            .estimate_max_fee(&mock, 20)
+            .await
+            .unwrap();
+        assert_eq!(builder.max_fee, Some(1_200u64.into()));
+
+        let mock = mock.with_high_priority_fee(2_000u64.into());
+        let builder = builder
+            .high_priority(true)
+            .estimate_max_fee(&mock, 0)
+            .await
+            .unwrap();
+        assert_eq!(builder.max_fee, Some(2_000u64.into()));
+
+        let err = MetaTxRequestBuilder::default()
+            .estimate_max_fee(&mock, 20)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BuilderError::MissingFields(_)));
+    }
+
+    #[test]
+    fn it_pads_estimates() {
+        assert_eq!(pad(1_000u64.into(), 0), 1_000u64.into());
+        assert_eq!(pad(1_000u64.into(), 25), 1_250u64.into());
+    }
+
+    #[test]
+    fn it_applies_fee_buffers() {
+        assert_eq!(buffer_bps(1.2), 12_000);
+        assert_eq!(buffer_bps(-1.0), 0);
+        assert_eq!(buffer_bps(f64::NAN), 0);
+        assert_eq!(
+            apply_fee_buffer(1_000u64.into(), None).unwrap(),
+            1_000u64.into()
+        );
+        assert_eq!(
+            apply_fee_buffer(1_000u64.into(), Some(buffer_bps(1.5))).unwrap(),
+            1_500u64.into()
+        );
+        assert!(apply_fee_buffer(U256::MAX, Some(20_000)).unwrap() > U256::MAX / 2);
+        assert!(matches!(
+            apply_fee_buffer(1_000u64.into(), Some(buffer_bps(0.8))),
+            Err(BuilderError::InvalidFeeBuffer(8_000))
+        ));
+        assert!(matches!(
+            apply_fee_buffer(1_000u64.into(), Some(buffer_bps(f64::NAN))),
+            Err(BuilderError::InvalidFeeBuffer(0))
+        ));
+
+        let request = crate::ForwardRequestBuilder::default()
+            .target(Address::repeat_byte(1))
+            .sponsor_address(Address::repeat_byte(2))
+            .max_fee(1_000u64)
+            .fee_buffer_bps(11_000)
+            .gas(100_000u64)
+            .nonce(0)
+            .build()
+            .unwrap();
+        assert_eq!(request.max_fee, 1_100u64.into());
+    }
+
+    #[cfg(feature = "providers")]
+    #[tokio::test]
+    async fn it_estimates_gas_with_padding() {
+        use ethers_core::types::transaction::eip2718::TypedTransaction;
+        use ethers_providers::{MockProvider, Provider};
+
+        let target = Address::repeat_byte(1);
+        let user = Address::repeat_byte(2);
+        let data = Bytes::from(vec![1, 2, 3]);
+
+        let (provider, mock) = Provider::mocked();
+        mock.push::<U256, _>(100_000u64.into()).unwrap();
+        let builder = crate::ForwardRequestBuilder::default()
+            .chain_id(5u64)
+            .target(target)
+            .data(data.clone())
+            .estimate_gas(&provider, 20)
+            .await
+            .unwrap();
+        assert_eq!(builder.gas, Some(120_000u64.into()));
+        let tx: TypedTransaction = TransactionRequest::new()
+            .to(target)
+            .data(data.clone())
+            .from(crate::get_forwarder(5).unwrap())
+            .into();
+        mock.assert_request("eth_estimateGas", [tx]).unwrap();
+
+        // no meta box is known, so the call is estimated as sent by the user
+        mock.push::<U256, _>(50_000u64.into()).unwrap();
+        let builder = MetaTxRequestBuilder::default()
+            .chain_id(5u64)
+            .target(target)
+            .data(data.clone())
+            .user_address(user)
+            .estimate_gas(&provider, 0)
+            .await
+            .unwrap();
+        assert_eq!(builder.gas, Some(50_000u64.into()));
+        let tx: TypedTransaction = TransactionRequest::new()
+            .to(target)
+            .data(data)
+            .from(user)
+            .into();
+        mock.assert_request("eth_estimateGas", [tx]).unwrap();
+
+        let err = crate::ForwardRequestBuilder::default()
+            .estimate_gas(&Provider::new(MockProvider::new()), 0)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BuilderError::MissingFields(_)));
+    }
+
+    #[cfg(feature = "providers")]
+    #[tokio::test]
+    async fn it_requires_a_user_and_known_meta_box_for_nonces() {
+        let provider = ethers_providers::Provider::new(ethers_providers::MockProvider::new());
+
+        let err = MetaTxRequestBuilder::default()
+            .fetch_nonce(&provider)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BuilderError::MissingFields(_)));
+
+        let err = MetaTxRequestBuilder::default()
+            .chain_id(1234u64)
+            .user_address(Address::repeat_byte(1))
+            .fetch_nonce(&provider)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BuilderError::UnknownMetaBox(1234)));
+    }
+
+    #[cfg(feature = "providers")]
+    #[test]
+    fn it_decodes_nonces() {
+        assert_eq!(decode_nonce(&U256::from(7).encode()).unwrap(), 7);
+        assert!(matches!(
+            decode_nonce(&U256::MAX.encode()),
+            Err(BuilderError::NonceTooLarge(nonce)) if nonce == U256::MAX
+        ));
+        assert!(matches!(
+            decode_nonce(&[1, 2, 3]),
+            Err(BuilderError::Provider(_))
+        ));
+    }
+}
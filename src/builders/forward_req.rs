@@ -1,48 +1,111 @@
 use ethers_core::types::{
-    transaction::eip2718::TypedTransaction, Address, Bytes, NameOrAddress, TransactionRequest, U64,
+    transaction::eip2718::TypedTransaction, Address, Bytes, NameOrAddress, TransactionRequest, U256,
 };
 
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+#[cfg(feature = "providers")]
+use super::estimate::estimate_gas;
+use super::{
+    error::{check_calldata, check_forwarder, check_gas, check_missing, check_value, BuilderError},
+    estimate::{apply_fee_buffer, buffer_bps, estimate_max_fee},
+};
+#[cfg(feature = "signers")]
+use crate::{rpc::SignedForwardRequest, GelatoTask};
 use crate::{
-    rpc::{ForwardRequest, SignedForwardRequest},
-    FeeToken, PaymentType,
+    rpc::{FeeQuote, ForwardRequest},
+    FeeToken, GelatoApi, IntoChainId, PaymentType,
 };
+#[cfg(feature = "providers")]
+use ethers_providers::Middleware;
 
 /// Builder for a [`ForwardRequest`]
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+///
+/// Serializable, so partially-specified requests can be loaded from config
+/// files and completed programmatically. U256 values are written as decimal
+/// strings, and read from numbers, decimal strings or hex strings.
+/// `fee_quote_expiry` is not serialized
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct ForwardRequestBuilder {
     /// Chain id. Defaults to 1 (ethereum).
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub chain_id: Option<u64>,
     /// Address of dApp's smart contract to call. Required
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub target: Option<Address>,
     /// Payload for `target`. Defaults to empty bytes: `0x`
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<Bytes>,
     /// paymentToken for Gelato Executors. Defaults to chain-native asset (eth)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub fee_token: Option<FeeToken>,
     /// Type identifier for Gelato's payment. Can be 1, 2 or 3.
     /// Defaults to 1: `AsyncGasTank`
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub payment_type: Option<PaymentType>,
     /// Maximum fee sponsor is willing to pay Gelato Executors. Required
-    pub max_fee: Option<U64>,
+    #[serde(
+        with = "crate::ser::optional_decimal_u256_ser",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_fee: Option<U256>,
+    /// Expiry of the fee quote `max_fee` was taken from, if any
+    #[serde(skip)]
+    pub fee_quote_expiry: Option<SystemTime>,
+    /// Factor `max_fee` is multiplied by at build time, in basis points,
+    /// e.g. 12_000 for 1.2x. Defaults to no buffer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_buffer_bps: Option<u32>,
+    /// Whether builder-level fee estimation requests the oracle's
+    /// high-priority quote. Defaults to `false`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub high_priority: Option<bool>,
     /// Gas limit. Required
-    pub gas: Option<U64>,
+    #[serde(
+        with = "crate::ser::optional_decimal_u256_ser",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub gas: Option<U256>,
+    /// Override for the relay's gas cap. Defaults to the known cap for
+    /// `chain_id`, if any. Requests with `gas` above the cap are rejected at
+    /// build time
+    #[serde(
+        with = "crate::ser::optional_decimal_u256_ser",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub gas_cap: Option<U256>,
     /// EOA address that pays Gelato Executors.
     /// Required. May be set automatically by the sponsor signer
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub sponsor: Option<Address>,
     /// Chain ID of where sponsor holds a Gas Tank balance with Gelato
     /// Usually the same as `chain_id`
     /// relevant for payment type 1: `AsyncGasTank`
     /// Required. May be set automatically by the sponsor signer
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub sponsor_chain_id: Option<u64>,
     /// Smart contract nonce for sponsor to sign.
     /// Can be 0 if enforceSponsorNonce is always false.
     /// Required.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub nonce: Option<usize>,
     /// Whether or not to enforce replay protection using sponsor's nonce.
     /// Defaults to `true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub enforce_sponsor_nonce: Option<bool>,
     /// Whether or not ordering matters for concurrently submitted transactions.
     /// Defaults to `true` if not provided.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub enforce_sponsor_nonce_ordering: Option<bool>,
+    /// Native value of the transaction the builder was converted from. The
+    /// relay can't forward value, so building fails unless it is zero
+    #[serde(
+        with = "crate::ser::optional_decimal_u256_ser",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub value: Option<U256>,
 }
 
 impl From<&TransactionRequest> for ForwardRequestBuilder {
@@ -53,7 +116,7 @@ impl From<&TransactionRequest> for ForwardRequestBuilder {
             builder = builder.target(target);
         }
         if let Some(gas) = tx.gas {
-            builder = builder.gas(gas.as_u64());
+            builder = builder.gas(gas);
         }
         if let Some(data) = &tx.data {
             builder = builder.data(data.clone());
@@ -64,6 +127,7 @@ impl From<&TransactionRequest> for ForwardRequestBuilder {
         if let Some(from) = tx.from {
             builder = builder.sponsor_address(from);
         }
+        builder.value = tx.value;
 
         builder
     }
@@ -77,7 +141,7 @@ impl From<&TypedTransaction> for ForwardRequestBuilder {
             builder = builder.target(*target);
         }
         if let Some(gas) = tx.gas() {
-            builder = builder.gas(gas.as_u64());
+            builder = builder.gas(*gas);
         }
         if let Some(data) = tx.data() {
             builder = builder.data(data.clone());
@@ -88,6 +152,7 @@ impl From<&TypedTransaction> for ForwardRequestBuilder {
         if let Some(from) = tx.from() {
             builder = builder.sponsor_address(*from);
         }
+        builder.value = tx.value().copied();
 
         builder
     }
@@ -117,8 +182,8 @@ impl ForwardRequestBuilder {
     }
 
     /// Set `chain_id`. Defaults to 1 (ethereum)
-    pub fn chain_id(mut self, val: u64) -> Self {
-        self.chain_id = Some(val);
+    pub fn chain_id(mut self, val: impl IntoChainId) -> Self {
+        self.chain_id = Some(val.into_chain_id());
         self
     }
 
@@ -147,17 +212,109 @@ impl ForwardRequestBuilder {
     }
 
     /// Set `max_fee`. Required
-    pub fn max_fee(mut self, val: impl Into<U64>) -> Self {
+    pub fn max_fee(mut self, val: impl Into<U256>) -> Self {
         self.max_fee = Some(val.into());
         self
     }
 
+    /// Set a factor `max_fee` is multiplied by at build time, whether it was
+    /// set manually or from the fee oracle, e.g. 1.2 to tolerate a 20% fee
+    /// increase. Stored in basis points. Building fails with
+    /// [`BuilderError::InvalidFeeBuffer`] if the factor is below 1.0
+    pub fn fee_buffer(mut self, val: f64) -> Self {
+        self.fee_buffer_bps = Some(buffer_bps(val));
+        self
+    }
+
+    /// Set a factor `max_fee` is multiplied by at build time, in basis
+    /// points, e.g. 12_000 for 1.2x
+    pub fn fee_buffer_bps(mut self, val: u32) -> Self {
+        self.fee_buffer_bps = Some(val);
+        self
+    }
+
+    /// Set `high_priority`. If true, [`Self::estimate_max_fee`] requests the
+    /// oracle's high-priority quote. Defaults to `false`
+    pub fn high_priority(mut self, val: bool) -> Self {
+        self.high_priority = Some(val);
+        self
+    }
+
+    /// Set `max_fee` and `fee_token` from a fee quote. Building fails if the
+    /// quote has expired
+    pub fn fee_quote(mut self, quote: &FeeQuote) -> Self {
+        self.max_fee = Some(quote.fee);
+        self.fee_token = Some(quote.payment_token);
+        self.fee_quote_expiry = Some(quote.expires_at);
+        self
+    }
+
+    /// Set `max_fee` from the fee oracle's estimate for `gas` on `chain_id`,
+    /// in `fee_token`, increased by `margin_percent` percent. Requests the
+    /// high-priority quote if `high_priority` is set. `gas` must be set
+    /// first
+    pub async fn estimate_max_fee<A>(
+        mut self,
+        client: &A,
+        margin_percent: u64,
+    ) -> Result<Self, BuilderError>
+    where
+        A: GelatoApi + ?Sized,
+    {
+        self.max_fee = Some(
+            estimate_max_fee(
+                client,
+                self.chain_id.unwrap_or(1),
+                self.fee_token.unwrap_or_default(),
+                self.gas,
+                self.high_priority.unwrap_or_default(),
+                margin_percent,
+            )
+            .await?,
+        );
+        self.fee_quote_expiry = None;
+        Ok(self)
+    }
+
+    /// Set `gas` from the provider's estimate for calling `target` with
+    /// `data` from the chain's `GelatoRelayForwarder`, increased by
+    /// `padding_percent` percent. `target` must be set first
+    #[cfg(feature = "providers")]
+    pub async fn estimate_gas<M>(
+        mut self,
+        provider: &M,
+        padding_percent: u64,
+    ) -> Result<Self, BuilderError>
+    where
+        M: Middleware,
+        M::Error: 'static,
+    {
+        self.gas = Some(
+            estimate_gas(
+                provider,
+                crate::get_forwarder(self.chain_id.unwrap_or(1)),
+                self.target,
+                self.data.clone().unwrap_or_default(),
+                padding_percent,
+            )
+            .await?,
+        );
+        Ok(self)
+    }
+
     /// Set `gas`. Required
-    pub fn gas(mut self, val: impl Into<U64>) -> Self {
+    pub fn gas(mut self, val: impl Into<U256>) -> Self {
         self.gas = Some(val.into());
         self
     }
 
+    /// Override the relay gas cap for this request. Defaults to the known cap
+    /// for `chain_id`
+    pub fn gas_cap(mut self, val: impl Into<U256>) -> Self {
+        self.gas_cap = Some(val.into());
+        self
+    }
+
     /// Set the sponsor address. Note that this will be overridden if
     /// `sponsored_by` is also called. Required.
     pub fn sponsor_address(mut self, sponsor: Address) -> Self {
@@ -167,6 +324,7 @@ impl ForwardRequestBuilder {
 
     /// Sponsor the request with a specific signer. Note taht this will
     /// override the existing sponsor address with that of the signer. Required
+    #[cfg(feature = "signers")]
     pub fn sponsored_by<S>(mut self, sponsor: &S) -> SponsoredForwardRequestBuilder<S>
     where
         S: ethers_signers::Signer,
@@ -184,8 +342,8 @@ impl ForwardRequestBuilder {
     }
 
     /// Set `sponsor_chain_id`. Defaults to 1 (ethereum)
-    pub fn sponsor_chain_id(mut self, val: u64) -> Self {
-        self.sponsor_chain_id = Some(val);
+    pub fn sponsor_chain_id(mut self, val: impl IntoChainId) -> Self {
+        self.sponsor_chain_id = Some(val.into_chain_id());
         self
     }
 
@@ -208,22 +366,36 @@ impl ForwardRequestBuilder {
     }
 
     /// Build this request
-    pub fn build(self) -> eyre::Result<ForwardRequest> {
-        let missing = self.missing_keys();
-        eyre::ensure!(
-            missing.is_empty(),
-            "Missing required values in build: {}",
-            missing.join(", ")
-        );
+    pub fn build(self) -> Result<ForwardRequest, BuilderError> {
+        check_missing(self.missing_keys())?;
+
+        let payment_type = self.payment_type.unwrap_or(PaymentType::AsyncGasTank);
+        if payment_type == PaymentType::Synchronous {
+            return Err(BuilderError::InappropriatePaymentType(payment_type));
+        }
+
+        if matches!(self.fee_quote_expiry, Some(expiry) if crate::time::now() > expiry) {
+            return Err(BuilderError::FeeQuoteExpired);
+        }
+
+        let chain_id = self.chain_id.unwrap_or(1);
+        check_forwarder(chain_id)?;
+
+        let gas = self.gas.unwrap();
+        check_gas(gas, self.gas_cap, chain_id)?;
+
+        let data = self.data.unwrap_or_default();
+        check_value(self.value)?;
+        check_calldata(&data)?;
 
         Ok(ForwardRequest {
-            chain_id: self.chain_id.unwrap_or(1),
+            chain_id,
             target: self.target.unwrap(),
-            data: self.data.unwrap_or_default(),
+            data,
             fee_token: self.fee_token.unwrap_or_default(),
-            payment_type: self.payment_type.unwrap_or(PaymentType::AsyncGasTank),
-            max_fee: self.max_fee.unwrap(),
-            gas: self.gas.unwrap(),
+            payment_type,
+            max_fee: apply_fee_buffer(self.max_fee.unwrap(), self.fee_buffer_bps)?,
+            gas,
             sponsor: self.sponsor.unwrap(),
             sponsor_chain_id: self.sponsor_chain_id.unwrap_or(1),
             nonce: self.nonce.unwrap_or_default(),
@@ -234,19 +406,21 @@ impl ForwardRequestBuilder {
 }
 
 /// Builder for a [`SignedForwardRequest`]
+#[cfg(feature = "signers")]
 pub struct SponsoredForwardRequestBuilder<'a, S> {
     builder: ForwardRequestBuilder,
     sponsor: &'a S,
 }
 
+#[cfg(feature = "signers")]
 impl<'a, S> SponsoredForwardRequestBuilder<'a, S>
 where
     S: ethers_signers::Signer,
     S::Error: 'static,
 {
     /// Set `chain_id`. Defaults to 1 (ethereum)
-    pub fn chain_id(mut self, val: u64) -> Self {
-        self.builder.chain_id = Some(val);
+    pub fn chain_id(mut self, val: impl IntoChainId) -> Self {
+        self.builder.chain_id = Some(val.into_chain_id());
         self
     }
 
@@ -275,17 +449,89 @@ where
     }
 
     /// Set `max_fee`. Required
-    pub fn max_fee(mut self, val: impl Into<U64>) -> Self {
+    pub fn max_fee(mut self, val: impl Into<U256>) -> Self {
         self.builder.max_fee = Some(val.into());
         self
     }
 
+    /// Set a factor `max_fee` is multiplied by at build time, whether it was
+    /// set manually or from the fee oracle, e.g. 1.2 to tolerate a 20% fee
+    /// increase. Stored in basis points. Building fails with
+    /// [`BuilderError::InvalidFeeBuffer`] if the factor is below 1.0
+    pub fn fee_buffer(mut self, val: f64) -> Self {
+        self.builder.fee_buffer_bps = Some(buffer_bps(val));
+        self
+    }
+
+    /// Set a factor `max_fee` is multiplied by at build time, in basis
+    /// points, e.g. 12_000 for 1.2x
+    pub fn fee_buffer_bps(mut self, val: u32) -> Self {
+        self.builder.fee_buffer_bps = Some(val);
+        self
+    }
+
+    /// Set `high_priority`. If true, fee estimation requests the oracle's
+    /// high-priority quote. Defaults to `false`
+    pub fn high_priority(mut self, val: bool) -> Self {
+        self.builder.high_priority = Some(val);
+        self
+    }
+
+    /// Set `max_fee` and `fee_token` from a fee quote. Building fails if the
+    /// quote has expired
+    pub fn fee_quote(mut self, quote: &FeeQuote) -> Self {
+        self.builder = self.builder.fee_quote(quote);
+        self
+    }
+
+    /// Set `max_fee` from the fee oracle's estimate for `gas` on `chain_id`,
+    /// in `fee_token`, increased by `margin_percent` percent. Requests the
+    /// high-priority quote if `high_priority` is set. `gas` must be set
+    /// first
+    pub async fn estimate_max_fee<A>(
+        mut self,
+        client: &A,
+        margin_percent: u64,
+    ) -> Result<Self, BuilderError>
+    where
+        A: GelatoApi + ?Sized,
+    {
+        self.builder = self
+            .builder
+            .estimate_max_fee(client, margin_percent)
+            .await?;
+        Ok(self)
+    }
+
+    /// Set `gas` from the provider's estimate, increased by
+    /// `padding_percent` percent. `target` must be set first
+    #[cfg(feature = "providers")]
+    pub async fn estimate_gas<M>(
+        mut self,
+        provider: &M,
+        padding_percent: u64,
+    ) -> Result<Self, BuilderError>
+    where
+        M: Middleware,
+        M::Error: 'static,
+    {
+        self.builder = self.builder.estimate_gas(provider, padding_percent).await?;
+        Ok(self)
+    }
+
     /// Set `gas`. Required
-    pub fn gas(mut self, val: impl Into<U64>) -> Self {
+    pub fn gas(mut self, val: impl Into<U256>) -> Self {
         self.builder.gas = Some(val.into());
         self
     }
 
+    /// Override the relay gas cap for this request. Defaults to the known cap
+    /// for `chain_id`
+    pub fn gas_cap(mut self, val: impl Into<U256>) -> Self {
+        self.builder.gas_cap = Some(val.into());
+        self
+    }
+
     /// Set `sponsor_address` unsetting the existing sponsor signer
     pub fn sponsor_address(mut self, address: Address) -> ForwardRequestBuilder {
         self.builder.sponsor = Some(address);
@@ -305,8 +551,8 @@ where
     }
 
     /// Set `sponsor_chain_id`. Defaults to 1 (ethereum)
-    pub fn sponsor_chain_id(mut self, val: u64) -> Self {
-        self.builder.sponsor_chain_id = Some(val);
+    pub fn sponsor_chain_id(mut self, val: impl IntoChainId) -> Self {
+        self.builder.sponsor_chain_id = Some(val.into_chain_id());
         self
     }
 
@@ -329,7 +575,55 @@ where
     }
 
     /// Build this request
-    pub async fn build(self) -> eyre::Result<SignedForwardRequest> {
+    pub async fn build(self) -> Result<SignedForwardRequest, BuilderError> {
         Ok(self.builder.build()?.sponsor(self.sponsor).await?)
     }
+
+    /// Build and sign this request, send it to the relay, and get a future
+    /// tracking its status
+    pub async fn submit<'c, A>(
+        self,
+        client: &'c A,
+    ) -> Result<GelatoTask<'c, SignedForwardRequest, A>, BuilderError>
+    where
+        A: GelatoApi + ?Sized,
+    {
+        let request = self.build().await?;
+        let resp = client.send_forward_request(&request).await?;
+        Ok(GelatoTask::new(resp.task_id(), client, request))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_loads_partial_requests() {
+        let builder: ForwardRequestBuilder = serde_json::from_str(
+            r#"{
+                "chain_id": 5,
+                "target": "0x2222222222222222222222222222222222222222",
+                "payment_type": 1,
+                "gas": 100000,
+                "max_fee": "0x3e8"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(builder.chain_id, Some(5));
+        assert_eq!(builder.payment_type, Some(PaymentType::AsyncGasTank));
+        assert_eq!(builder.gas, Some(100_000u64.into()));
+        assert_eq!(builder.max_fee, Some(1_000u64.into()));
+        assert_eq!(builder.missing_keys(), vec!["sponsor", "nonce"]);
+
+        let json = serde_json::to_value(&builder).unwrap();
+        assert_eq!(json["gas"], "100000");
+        assert!(json.get("sponsor").is_none());
+        assert_eq!(
+            serde_json::from_value::<ForwardRequestBuilder>(json).unwrap(),
+            builder
+        );
+
+        assert!(serde_json::from_str::<ForwardRequestBuilder>(r#"{"gass": 1}"#).is_err());
+    }
 }
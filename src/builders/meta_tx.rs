@@ -1,45 +1,109 @@
 use ethers_core::types::{
-    transaction::eip2718::TypedTransaction, Address, Bytes, NameOrAddress, TransactionRequest, U64,
+    transaction::eip2718::TypedTransaction, Address, Bytes, NameOrAddress, TransactionRequest, U256,
 };
 
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "providers")]
+use super::estimate::{erc2771_calldata, estimate_gas, fetch_nonce};
+use super::{
+    error::{check_calldata, check_gas, check_missing, check_value, BuilderError},
+    estimate::{apply_fee_buffer, buffer_bps, estimate_max_fee},
+};
 use crate::{
-    rpc::{MetaTxRequest, SignedMetaTxRequest},
-    FeeToken, PaymentType,
+    clock::{unix_secs, unix_secs_in, DeadlineClock},
+    rpc::{FeeQuote, MetaTxRequest},
+    Deadline, FeeToken, GelatoApi, IntoChainId, PaymentType,
 };
+#[cfg(feature = "signers")]
+use crate::{rpc::SignedMetaTxRequest, GelatoTask};
+#[cfg(feature = "providers")]
+use ethers_providers::Middleware;
 
 /// Builder for a [`MetaTxRequest`]
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+///
+/// Serializable, so partially-specified requests can be loaded from config
+/// files and completed programmatically. U256 values are written as decimal
+/// strings, and read from numbers, decimal strings or hex strings.
+/// `fee_quote_expiry` is not serialized
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct MetaTxRequestBuilder {
     /// Chain id. Defaults to 1 (ethereum).
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub chain_id: Option<u64>,
     /// Address of dApp's smart contract to call. Required
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub target: Option<Address>,
     /// Payload for `target`. Defaults to empty bytes: `0x`
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<Bytes>,
     /// paymentToken for Gelato Executors. Defaults to chain-native asset (eth)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub fee_token: Option<FeeToken>,
     /// Type identifier for Gelato's payment. Can be 1, 2 or 3.
     /// Defaults to 1: `AsyncGasTank`
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub payment_type: Option<PaymentType>,
     /// Maximum fee sponsor is willing to pay Gelato Executors. Required
-    pub max_fee: Option<U64>,
+    #[serde(
+        with = "crate::ser::optional_decimal_u256_ser",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_fee: Option<U256>,
+    /// Expiry of the fee quote `max_fee` was taken from, if any
+    #[serde(skip)]
+    pub fee_quote_expiry: Option<SystemTime>,
+    /// Factor `max_fee` is multiplied by at build time, in basis points,
+    /// e.g. 12_000 for 1.2x. Defaults to no buffer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_buffer_bps: Option<u32>,
+    /// Whether builder-level fee estimation requests the oracle's
+    /// high-priority quote. Defaults to `false`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub high_priority: Option<bool>,
     /// Gas limit. Required
-    pub gas: Option<U64>,
+    #[serde(
+        with = "crate::ser::optional_decimal_u256_ser",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub gas: Option<U256>,
+    /// Override for the relay's gas cap. Defaults to the known cap for
+    /// `chain_id`, if any. Requests with `gas` above the cap are rejected at
+    /// build time
+    #[serde(
+        with = "crate::ser::optional_decimal_u256_ser",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub gas_cap: Option<U256>,
     /// EOA of dapp's user. Required
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<Address>,
     /// EOA address that pays Gelato Executors.
     /// Optional. User pays if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub sponsor: Option<Address>,
     /// Chain ID of where sponsor holds a Gas Tank balance with Gelato
     /// Usually the same as `chain_id`
     /// relevant for payment type 1: `AsyncGasTank`
     /// Required. May be set automatically by the sponsor signer
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub sponsor_chain_id: Option<u64>,
     /// Smart contract nonce for sponsor to sign.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub nonce: Option<usize>,
     /// Deadline for executing this MetaTxRequest. If set to 0, no deadline is
-    /// enforced
-    pub deadline: Option<u64>,
+    /// enforced. Omitted from the request unless set
+    #[serde(skip_serializing_if = "Deadline::is_omitted")]
+    pub deadline: Deadline,
+    /// Native value of the transaction the builder was converted from. The
+    /// relay can't forward value, so building fails unless it is zero
+    #[serde(
+        with = "crate::ser::optional_decimal_u256_ser",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub value: Option<U256>,
 }
 
 impl From<&TransactionRequest> for MetaTxRequestBuilder {
@@ -50,7 +114,7 @@ impl From<&TransactionRequest> for MetaTxRequestBuilder {
             builder = builder.target(target);
         }
         if let Some(gas) = tx.gas {
-            builder = builder.gas(gas.as_u64());
+            builder = builder.gas(gas);
         }
         if let Some(data) = &tx.data {
             builder = builder.data(data.clone());
@@ -61,6 +125,7 @@ impl From<&TransactionRequest> for MetaTxRequestBuilder {
         if let Some(from) = tx.from {
             builder = builder.user_address(from);
         }
+        builder.value = tx.value;
 
         builder
     }
@@ -74,7 +139,7 @@ impl From<&TypedTransaction> for MetaTxRequestBuilder {
             builder = builder.target(*target);
         }
         if let Some(gas) = tx.gas() {
-            builder = builder.gas(gas.as_u64());
+            builder = builder.gas(*gas);
         }
         if let Some(data) = tx.data() {
             builder = builder.data(data.clone());
@@ -85,6 +150,7 @@ impl From<&TypedTransaction> for MetaTxRequestBuilder {
         if let Some(from) = tx.from() {
             builder = builder.user_address(*from);
         }
+        builder.value = tx.value().copied();
 
         builder
     }
@@ -113,8 +179,8 @@ impl MetaTxRequestBuilder {
     }
 
     /// Set `chain_id`. Defaults to 1 (ethereum)
-    pub fn chain_id(mut self, val: u64) -> Self {
-        self.chain_id = Some(val);
+    pub fn chain_id(mut self, val: impl IntoChainId) -> Self {
+        self.chain_id = Some(val.into_chain_id());
         self
     }
 
@@ -143,17 +209,121 @@ impl MetaTxRequestBuilder {
     }
 
     /// Set `max_fee`. Required
-    pub fn max_fee(mut self, val: impl Into<U64>) -> Self {
+    pub fn max_fee(mut self, val: impl Into<U256>) -> Self {
         self.max_fee = Some(val.into());
         self
     }
 
+    /// Set a factor `max_fee` is multiplied by at build time, whether it was
+    /// set manually or from the fee oracle, e.g. 1.2 to tolerate a 20% fee
+    /// increase. Stored in basis points. Building fails with
+    /// [`BuilderError::InvalidFeeBuffer`] if the factor is below 1.0
+    pub fn fee_buffer(mut self, val: f64) -> Self {
+        self.fee_buffer_bps = Some(buffer_bps(val));
+        self
+    }
+
+    /// Set a factor `max_fee` is multiplied by at build time, in basis
+    /// points, e.g. 12_000 for 1.2x
+    pub fn fee_buffer_bps(mut self, val: u32) -> Self {
+        self.fee_buffer_bps = Some(val);
+        self
+    }
+
+    /// Set `high_priority`. If true, [`Self::estimate_max_fee`] requests the
+    /// oracle's high-priority quote. Defaults to `false`
+    pub fn high_priority(mut self, val: bool) -> Self {
+        self.high_priority = Some(val);
+        self
+    }
+
+    /// Set `max_fee` and `fee_token` from a fee quote. Building fails if the
+    /// quote has expired
+    pub fn fee_quote(mut self, quote: &FeeQuote) -> Self {
+        self.max_fee = Some(quote.fee);
+        self.fee_token = Some(quote.payment_token);
+        self.fee_quote_expiry = Some(quote.expires_at);
+        self
+    }
+
+    /// Set `max_fee` from the fee oracle's estimate for `gas` on `chain_id`,
+    /// in `fee_token`, increased by `margin_percent` percent. Requests the
+    /// high-priority quote if `high_priority` is set. `gas` must be set
+    /// first
+    pub async fn estimate_max_fee<A>(
+        mut self,
+        client: &A,
+        margin_percent: u64,
+    ) -> Result<Self, BuilderError>
+    where
+        A: GelatoApi + ?Sized,
+    {
+        self.max_fee = Some(
+            estimate_max_fee(
+                client,
+                self.chain_id.unwrap_or(1),
+                self.fee_token.unwrap_or_default(),
+                self.gas,
+                self.high_priority.unwrap_or_default(),
+                margin_percent,
+            )
+            .await?,
+        );
+        self.fee_quote_expiry = None;
+        Ok(self)
+    }
+
+    /// Set `gas` from the provider's estimate for calling `target` with
+    /// `data` from the chain's `GelatoMetaBox`, with `user` appended to the
+    /// calldata as the ERC-2771 sender. On chains without a known
+    /// `GelatoMetaBox`, the call is estimated as sent by `user` directly.
+    /// Increased by `padding_percent` percent. `target` must be set first
+    #[cfg(feature = "providers")]
+    pub async fn estimate_gas<M>(
+        mut self,
+        provider: &M,
+        padding_percent: u64,
+    ) -> Result<Self, BuilderError>
+    where
+        M: Middleware,
+        M::Error: 'static,
+    {
+        let (from, data) = match crate::get_meta_box(self.chain_id.unwrap_or(1)) {
+            Some(meta_box) => (
+                Some(meta_box),
+                erc2771_calldata(self.data.as_ref(), self.user),
+            ),
+            None => (self.user, self.data.clone().unwrap_or_default()),
+        };
+        self.gas = Some(estimate_gas(provider, from, self.target, data, padding_percent).await?);
+        Ok(self)
+    }
+
+    /// Set `nonce` to `user`'s current nonce in the chain's `GelatoMetaBox`,
+    /// read via the provider. `user` must be set first
+    #[cfg(feature = "providers")]
+    pub async fn fetch_nonce<M>(mut self, provider: &M) -> Result<Self, BuilderError>
+    where
+        M: Middleware,
+        M::Error: 'static,
+    {
+        self.nonce = Some(fetch_nonce(provider, self.chain_id.unwrap_or(1), self.user).await?);
+        Ok(self)
+    }
+
     /// Set `gas`. Required
-    pub fn gas(mut self, val: impl Into<U64>) -> Self {
+    pub fn gas(mut self, val: impl Into<U256>) -> Self {
         self.gas = Some(val.into());
         self
     }
 
+    /// Override the relay gas cap for this request. Defaults to the known cap
+    /// for `chain_id`
+    pub fn gas_cap(mut self, val: impl Into<U256>) -> Self {
+        self.gas_cap = Some(val.into());
+        self
+    }
+
     /// Set `user`. Required. May be set automatically by `user_signer`
     pub fn user_address(mut self, val: Address) -> Self {
         self.user = Some(val);
@@ -162,6 +332,7 @@ impl MetaTxRequestBuilder {
 
     /// Set a signer that will sign the request. Note that this will override
     /// the existing user with the address of that of the signer
+    #[cfg(feature = "signers")]
     pub fn with_user<S>(mut self, user: &S) -> MetaTxRequestBuilderWithUser<S>
     where
         S: ethers_signers::Signer,
@@ -182,14 +353,20 @@ impl MetaTxRequestBuilder {
     }
 
     /// Sponsor the request with a specific signer. Note that this will
-    /// override the existing sponsor address with that of the signer
+    /// override the existing sponsor address with that of the signer, and set
+    /// `sponsor_chain_id` to the signer's. `chain_id` defaults to the signer's
+    /// if it was not set
+    #[cfg(feature = "signers")]
     pub fn sponsored_by<S>(mut self, sponsor: &S) -> MetaTxRequestBuilderWithSponsor<S>
     where
         S: ethers_signers::Signer,
         S::Error: 'static,
     {
+        if self.chain_id.is_none() {
+            self.chain_id = Some(sponsor.chain_id());
+        }
         self.sponsor = Some(sponsor.address());
-        self.chain_id = Some(sponsor.chain_id());
+        self.sponsor_chain_id = Some(sponsor.chain_id());
         MetaTxRequestBuilderWithSponsor {
             builder: self,
             sponsor,
@@ -198,8 +375,8 @@ impl MetaTxRequestBuilder {
 
     /// Set `sponsor_chain_id`. Defaults to 1 (ethereum) if a sponsor is set. May be set
     /// automatically if `sponsored_by` is called
-    pub fn sponsor_chain_id(mut self, val: u64) -> Self {
-        self.sponsor_chain_id = Some(val);
+    pub fn sponsor_chain_id(mut self, val: impl IntoChainId) -> Self {
+        self.sponsor_chain_id = Some(val.into_chain_id());
         self
     }
 
@@ -210,31 +387,67 @@ impl MetaTxRequestBuilder {
     }
 
     /// Set `deadline`. If set to 0, no deadline is
-    /// enforced
-    pub fn deadline(mut self, val: u64) -> Self {
-        self.deadline = Some(val);
+    /// enforced. Accepts a timestamp or a [`Deadline`], e.g.
+    /// [`Deadline::ZERO`] to serialize an explicit 0
+    pub fn deadline(mut self, val: impl Into<Deadline>) -> Self {
+        self.deadline = val.into();
         self
     }
+
+    /// Set `deadline` to `validity` from now, according to the clock. The
+    /// clock's skew margin is added to the deadline
+    pub fn deadline_from(mut self, clock: &DeadlineClock, validity: Duration) -> Self {
+        self.deadline = Deadline::At(clock.deadline_after(validity));
+        self
+    }
+
+    /// Set `deadline` to `validity` from now, according to the system clock.
+    /// Fails if the deadline can't be represented
+    pub fn deadline_in(mut self, validity: Duration) -> Result<Self, BuilderError> {
+        self.deadline =
+            Deadline::At(unix_secs_in(validity).ok_or(BuilderError::DeadlineOverflow(validity))?);
+        Ok(self)
+    }
+
+    /// Set `deadline` to a specific time
+    pub fn deadline_at(mut self, time: SystemTime) -> Self {
+        self.deadline = Deadline::At(unix_secs(time));
+        self
+    }
+
     /// Build this request
-    pub fn build(self) -> eyre::Result<MetaTxRequest> {
-        let missing = self.missing_keys();
-        eyre::ensure!(
-            missing.is_empty(),
-            "Missing required values in build: {}",
-            missing.join(", ")
-        );
+    pub fn build(self) -> Result<MetaTxRequest, BuilderError> {
+        check_missing(self.missing_keys())?;
+
+        let payment_type = self.payment_type.unwrap_or(PaymentType::AsyncGasTank);
+        if payment_type == PaymentType::Synchronous {
+            return Err(BuilderError::InappropriatePaymentType(payment_type));
+        }
+
+        if matches!(self.fee_quote_expiry, Some(expiry) if crate::time::now() > expiry) {
+            return Err(BuilderError::FeeQuoteExpired);
+        }
+
+        let chain_id = self.chain_id.unwrap_or(1);
+
+        let gas = self.gas.unwrap();
+        check_gas(gas, self.gas_cap, chain_id)?;
+
+        let data = self.data.unwrap_or_default();
+        check_value(self.value)?;
+        check_calldata(&data)?;
 
         // default value IF there's a sponsor set
         let sponsor_chain_id = self.sponsor.map(|_| self.sponsor_chain_id.unwrap_or(1));
 
         Ok(MetaTxRequest {
-            chain_id: self.chain_id.unwrap_or(1),
+            chain_id,
             target: self.target.unwrap(),
-            data: self.data.unwrap_or_default(),
+            data,
             fee_token: self.fee_token.unwrap_or_default(),
-            payment_type: self.payment_type.unwrap_or(PaymentType::AsyncGasTank),
-            max_fee: self.max_fee.unwrap(),
-            gas: self.gas.unwrap(),
+            payment_type,
+            max_fee: apply_fee_buffer(self.max_fee.unwrap(), self.fee_buffer_bps)?,
+            gas,
             user: self.user.unwrap(),
             sponsor: self.sponsor,
             sponsor_chain_id,
@@ -245,15 +458,17 @@ impl MetaTxRequestBuilder {
 }
 
 /// Builder for a [`SignedMetaTxRequest`] with sponsor but no user yet set
+#[cfg(feature = "signers")]
 pub struct MetaTxRequestBuilderWithSponsor<'a, S> {
     builder: MetaTxRequestBuilder,
     sponsor: &'a S,
 }
 
+#[cfg(feature = "signers")]
 impl<'a, S> MetaTxRequestBuilderWithSponsor<'a, S> {
     /// Set `chain_id`. Defaults to 1 (ethereum)
-    pub fn chain_id(mut self, val: u64) -> Self {
-        self.builder.chain_id = Some(val);
+    pub fn chain_id(mut self, val: impl IntoChainId) -> Self {
+        self.builder.chain_id = Some(val.into_chain_id());
         self
     }
 
@@ -282,17 +497,101 @@ impl<'a, S> MetaTxRequestBuilderWithSponsor<'a, S> {
     }
 
     /// Set `max_fee`. Required
-    pub fn max_fee(mut self, val: impl Into<U64>) -> Self {
+    pub fn max_fee(mut self, val: impl Into<U256>) -> Self {
         self.builder.max_fee = Some(val.into());
         self
     }
 
+    /// Set a factor `max_fee` is multiplied by at build time, whether it was
+    /// set manually or from the fee oracle, e.g. 1.2 to tolerate a 20% fee
+    /// increase. Stored in basis points. Building fails with
+    /// [`BuilderError::InvalidFeeBuffer`] if the factor is below 1.0
+    pub fn fee_buffer(mut self, val: f64) -> Self {
+        self.builder.fee_buffer_bps = Some(buffer_bps(val));
+        self
+    }
+
+    /// Set a factor `max_fee` is multiplied by at build time, in basis
+    /// points, e.g. 12_000 for 1.2x
+    pub fn fee_buffer_bps(mut self, val: u32) -> Self {
+        self.builder.fee_buffer_bps = Some(val);
+        self
+    }
+
+    /// Set `high_priority`. If true, fee estimation requests the oracle's
+    /// high-priority quote. Defaults to `false`
+    pub fn high_priority(mut self, val: bool) -> Self {
+        self.builder.high_priority = Some(val);
+        self
+    }
+
+    /// Set `max_fee` and `fee_token` from a fee quote. Building fails if the
+    /// quote has expired
+    pub fn fee_quote(mut self, quote: &FeeQuote) -> Self {
+        self.builder = self.builder.fee_quote(quote);
+        self
+    }
+
+    /// Set `max_fee` from the fee oracle's estimate for `gas` on `chain_id`,
+    /// in `fee_token`, increased by `margin_percent` percent. Requests the
+    /// high-priority quote if `high_priority` is set. `gas` must be set
+    /// first
+    pub async fn estimate_max_fee<A>(
+        mut self,
+        client: &A,
+        margin_percent: u64,
+    ) -> Result<Self, BuilderError>
+    where
+        A: GelatoApi + ?Sized,
+    {
+        self.builder = self
+            .builder
+            .estimate_max_fee(client, margin_percent)
+            .await?;
+        Ok(self)
+    }
+
+    /// Set `gas` from the provider's estimate, increased by
+    /// `padding_percent` percent. `target` must be set first
+    #[cfg(feature = "providers")]
+    pub async fn estimate_gas<M>(
+        mut self,
+        provider: &M,
+        padding_percent: u64,
+    ) -> Result<Self, BuilderError>
+    where
+        M: Middleware,
+        M::Error: 'static,
+    {
+        self.builder = self.builder.estimate_gas(provider, padding_percent).await?;
+        Ok(self)
+    }
+
+    /// Set `nonce` to the user's current nonce in the chain's
+    /// `GelatoMetaBox`, read via the provider
+    #[cfg(feature = "providers")]
+    pub async fn fetch_nonce<M>(mut self, provider: &M) -> Result<Self, BuilderError>
+    where
+        M: Middleware,
+        M::Error: 'static,
+    {
+        self.builder = self.builder.fetch_nonce(provider).await?;
+        Ok(self)
+    }
+
     /// Set `gas`. Required
-    pub fn gas(mut self, val: impl Into<U64>) -> Self {
+    pub fn gas(mut self, val: impl Into<U256>) -> Self {
         self.builder.gas = Some(val.into());
         self
     }
 
+    /// Override the relay gas cap for this request. Defaults to the known cap
+    /// for `chain_id`
+    pub fn gas_cap(mut self, val: impl Into<U256>) -> Self {
+        self.builder.gas_cap = Some(val.into());
+        self
+    }
+
     /// Set `user`. Required. May be set automatically by `user_signer`
     pub fn user_address(mut self, val: Address) -> Self {
         self.builder.user = Some(val);
@@ -338,8 +637,8 @@ impl<'a, S> MetaTxRequestBuilderWithSponsor<'a, S> {
     }
 
     /// Set `sponsor_chain_id`. Defaults to 1 (ethereum) if a sponsor is set
-    pub fn sponsor_chain_id(mut self, val: u64) -> Self {
-        self.builder.sponsor_chain_id = Some(val);
+    pub fn sponsor_chain_id(mut self, val: impl IntoChainId) -> Self {
+        self.builder.sponsor_chain_id = Some(val.into_chain_id());
         self
     }
 
@@ -350,32 +649,55 @@ impl<'a, S> MetaTxRequestBuilderWithSponsor<'a, S> {
     }
 
     /// Set `deadline`. If set to 0, no deadline is
-    /// enforced
-    pub fn deadline(mut self, val: u64) -> Self {
-        self.builder.deadline = Some(val);
+    /// enforced. Accepts a timestamp or a [`Deadline`], e.g.
+    /// [`Deadline::ZERO`] to serialize an explicit 0
+    pub fn deadline(mut self, val: impl Into<Deadline>) -> Self {
+        self.builder.deadline = val.into();
+        self
+    }
+
+    /// Set `deadline` to `validity` from now, according to the clock. The
+    /// clock's skew margin is added to the deadline
+    pub fn deadline_from(mut self, clock: &DeadlineClock, validity: Duration) -> Self {
+        self.builder.deadline = Deadline::At(clock.deadline_after(validity));
+        self
+    }
+
+    /// Set `deadline` to `validity` from now, according to the system clock.
+    /// Fails if the deadline can't be represented
+    pub fn deadline_in(mut self, validity: Duration) -> Result<Self, BuilderError> {
+        self.builder = self.builder.deadline_in(validity)?;
+        Ok(self)
+    }
+
+    /// Set `deadline` to a specific time
+    pub fn deadline_at(mut self, time: SystemTime) -> Self {
+        self.builder.deadline = Deadline::At(unix_secs(time));
         self
     }
 
     /// Build this request
-    pub fn build(self) -> eyre::Result<MetaTxRequest> {
+    pub fn build(self) -> Result<MetaTxRequest, BuilderError> {
         self.builder.build()
     }
 }
 
 /// Builder for a [`SignedMetaTxRequest`] with no sponsor
+#[cfg(feature = "signers")]
 pub struct MetaTxRequestBuilderWithUser<'a, S> {
     builder: MetaTxRequestBuilder,
     user: &'a S,
 }
 
+#[cfg(feature = "signers")]
 impl<'a, S> MetaTxRequestBuilderWithUser<'a, S>
 where
     S: ethers_signers::Signer,
     S::Error: 'static,
 {
     /// Set `chain_id`. Defaults to 1 (ethereum)
-    pub fn chain_id(mut self, val: u64) -> Self {
-        self.builder.chain_id = Some(val);
+    pub fn chain_id(mut self, val: impl IntoChainId) -> Self {
+        self.builder.chain_id = Some(val.into_chain_id());
         self
     }
 
@@ -404,17 +726,101 @@ where
     }
 
     /// Set `max_fee`. Required
-    pub fn max_fee(mut self, val: impl Into<U64>) -> Self {
+    pub fn max_fee(mut self, val: impl Into<U256>) -> Self {
         self.builder.max_fee = Some(val.into());
         self
     }
 
+    /// Set a factor `max_fee` is multiplied by at build time, whether it was
+    /// set manually or from the fee oracle, e.g. 1.2 to tolerate a 20% fee
+    /// increase. Stored in basis points. Building fails with
+    /// [`BuilderError::InvalidFeeBuffer`] if the factor is below 1.0
+    pub fn fee_buffer(mut self, val: f64) -> Self {
+        self.builder.fee_buffer_bps = Some(buffer_bps(val));
+        self
+    }
+
+    /// Set a factor `max_fee` is multiplied by at build time, in basis
+    /// points, e.g. 12_000 for 1.2x
+    pub fn fee_buffer_bps(mut self, val: u32) -> Self {
+        self.builder.fee_buffer_bps = Some(val);
+        self
+    }
+
+    /// Set `high_priority`. If true, fee estimation requests the oracle's
+    /// high-priority quote. Defaults to `false`
+    pub fn high_priority(mut self, val: bool) -> Self {
+        self.builder.high_priority = Some(val);
+        self
+    }
+
+    /// Set `max_fee` and `fee_token` from a fee quote. Building fails if the
+    /// quote has expired
+    pub fn fee_quote(mut self, quote: &FeeQuote) -> Self {
+        self.builder = self.builder.fee_quote(quote);
+        self
+    }
+
+    /// Set `max_fee` from the fee oracle's estimate for `gas` on `chain_id`,
+    /// in `fee_token`, increased by `margin_percent` percent. Requests the
+    /// high-priority quote if `high_priority` is set. `gas` must be set
+    /// first
+    pub async fn estimate_max_fee<A>(
+        mut self,
+        client: &A,
+        margin_percent: u64,
+    ) -> Result<Self, BuilderError>
+    where
+        A: GelatoApi + ?Sized,
+    {
+        self.builder = self
+            .builder
+            .estimate_max_fee(client, margin_percent)
+            .await?;
+        Ok(self)
+    }
+
+    /// Set `gas` from the provider's estimate, increased by
+    /// `padding_percent` percent. `target` must be set first
+    #[cfg(feature = "providers")]
+    pub async fn estimate_gas<M>(
+        mut self,
+        provider: &M,
+        padding_percent: u64,
+    ) -> Result<Self, BuilderError>
+    where
+        M: Middleware,
+        M::Error: 'static,
+    {
+        self.builder = self.builder.estimate_gas(provider, padding_percent).await?;
+        Ok(self)
+    }
+
+    /// Set `nonce` to the user's current nonce in the chain's
+    /// `GelatoMetaBox`, read via the provider
+    #[cfg(feature = "providers")]
+    pub async fn fetch_nonce<M>(mut self, provider: &M) -> Result<Self, BuilderError>
+    where
+        M: Middleware,
+        M::Error: 'static,
+    {
+        self.builder = self.builder.fetch_nonce(provider).await?;
+        Ok(self)
+    }
+
     /// Set `gas`. Required
-    pub fn gas(mut self, val: impl Into<U64>) -> Self {
+    pub fn gas(mut self, val: impl Into<U256>) -> Self {
         self.builder.gas = Some(val.into());
         self
     }
 
+    /// Override the relay gas cap for this request. Defaults to the known cap
+    /// for `chain_id`
+    pub fn gas_cap(mut self, val: impl Into<U256>) -> Self {
+        self.builder.gas_cap = Some(val.into());
+        self
+    }
+
     /// Set `user_address`. Note that this will unset the existing signer
     pub fn user_address(mut self, val: Address) -> MetaTxRequestBuilder {
         self.builder.user = Some(val);
@@ -460,8 +866,8 @@ where
     }
 
     /// Set `sponsor_chain_id`. Defaults to 1 (ethereum) if a sponsor is set
-    pub fn sponsor_chain_id(mut self, val: u64) -> Self {
-        self.builder.sponsor_chain_id = Some(val);
+    pub fn sponsor_chain_id(mut self, val: impl IntoChainId) -> Self {
+        self.builder.sponsor_chain_id = Some(val.into_chain_id());
         self
     }
 
@@ -472,25 +878,67 @@ where
     }
 
     /// Set `deadline`. If set to 0, no deadline is
-    /// enforced
-    pub fn deadline(mut self, val: u64) -> Self {
-        self.builder.deadline = Some(val);
+    /// enforced. Accepts a timestamp or a [`Deadline`], e.g.
+    /// [`Deadline::ZERO`] to serialize an explicit 0
+    pub fn deadline(mut self, val: impl Into<Deadline>) -> Self {
+        self.builder.deadline = val.into();
         self
     }
 
-    /// Build this request
-    pub async fn build(self) -> eyre::Result<SignedMetaTxRequest> {
+    /// Set `deadline` to `validity` from now, according to the clock. The
+    /// clock's skew margin is added to the deadline
+    pub fn deadline_from(mut self, clock: &DeadlineClock, validity: Duration) -> Self {
+        self.builder.deadline = Deadline::At(clock.deadline_after(validity));
+        self
+    }
+
+    /// Set `deadline` to `validity` from now, according to the system clock.
+    /// Fails if the deadline can't be represented
+    pub fn deadline_in(mut self, validity: Duration) -> Result<Self, BuilderError> {
+        self.builder = self.builder.deadline_in(validity)?;
+        Ok(self)
+    }
+
+    /// Set `deadline` to a specific time
+    pub fn deadline_at(mut self, time: SystemTime) -> Self {
+        self.builder.deadline = Deadline::At(unix_secs(time));
+        self
+    }
+
+    /// Build this request and sign it as the user. `chain_id` defaults to the
+    /// user signer's. An explicitly set `chain_id` is kept, as the EIP-712
+    /// domain is taken from the request rather than from the signer
+    pub async fn build(mut self) -> Result<SignedMetaTxRequest, BuilderError> {
+        if self.builder.chain_id.is_none() {
+            self.builder.chain_id = Some(self.user.chain_id());
+        }
         Ok(self.builder.build()?.sign(self.user).await?)
     }
+
+    /// Build and sign this request, send it to the relay, and get a future
+    /// tracking its status
+    pub async fn submit<'c, A>(
+        self,
+        client: &'c A,
+    ) -> Result<GelatoTask<'c, SignedMetaTxRequest, A>, BuilderError>
+    where
+        A: GelatoApi + ?Sized,
+    {
+        let request = self.build().await?;
+        let resp = client.send_meta_tx_request(&request).await?;
+        Ok(GelatoTask::new(resp.task_id(), client, request))
+    }
 }
 
 /// Builder for a [`SignedMetaTxRequest`] with user and sponsor
+#[cfg(feature = "signers")]
 pub struct MetaTxRequestBuilderWithUserAndSponsor<'a, 'b, S, T> {
     builder: MetaTxRequestBuilder,
     user: &'a S,
     sponsor: &'b T,
 }
 
+#[cfg(feature = "signers")]
 impl<'a, 'b, S, T> MetaTxRequestBuilderWithUserAndSponsor<'a, 'b, S, T>
 where
     S: ethers_signers::Signer,
@@ -499,8 +947,8 @@ where
     T::Error: 'static,
 {
     /// Set `chain_id`. Defaults to 1 (ethereum)
-    pub fn chain_id(mut self, val: u64) -> Self {
-        self.builder.chain_id = Some(val);
+    pub fn chain_id(mut self, val: impl IntoChainId) -> Self {
+        self.builder.chain_id = Some(val.into_chain_id());
         self
     }
 
@@ -529,17 +977,101 @@ where
     }
 
     /// Set `max_fee`. Required
-    pub fn max_fee(mut self, val: impl Into<U64>) -> Self {
+    pub fn max_fee(mut self, val: impl Into<U256>) -> Self {
         self.builder.max_fee = Some(val.into());
         self
     }
 
+    /// Set a factor `max_fee` is multiplied by at build time, whether it was
+    /// set manually or from the fee oracle, e.g. 1.2 to tolerate a 20% fee
+    /// increase. Stored in basis points. Building fails with
+    /// [`BuilderError::InvalidFeeBuffer`] if the factor is below 1.0
+    pub fn fee_buffer(mut self, val: f64) -> Self {
+        self.builder.fee_buffer_bps = Some(buffer_bps(val));
+        self
+    }
+
+    /// Set a factor `max_fee` is multiplied by at build time, in basis
+    /// points, e.g. 12_000 for 1.2x
+    pub fn fee_buffer_bps(mut self, val: u32) -> Self {
+        self.builder.fee_buffer_bps = Some(val);
+        self
+    }
+
+    /// Set `high_priority`. If true, fee estimation requests the oracle's
+    /// high-priority quote. Defaults to `false`
+    pub fn high_priority(mut self, val: bool) -> Self {
+        self.builder.high_priority = Some(val);
+        self
+    }
+
+    /// Set `max_fee` and `fee_token` from a fee quote. Building fails if the
+    /// quote has expired
+    pub fn fee_quote(mut self, quote: &FeeQuote) -> Self {
+        self.builder = self.builder.fee_quote(quote);
+        self
+    }
+
+    /// Set `max_fee` from the fee oracle's estimate for `gas` on `chain_id`,
+    /// in `fee_token`, increased by `margin_percent` percent. Requests the
+    /// high-priority quote if `high_priority` is set. `gas` must be set
+    /// first
+    pub async fn estimate_max_fee<A>(
+        mut self,
+        client: &A,
+        margin_percent: u64,
+    ) -> Result<Self, BuilderError>
+    where
+        A: GelatoApi + ?Sized,
+    {
+        self.builder = self
+            .builder
+            .estimate_max_fee(client, margin_percent)
+            .await?;
+        Ok(self)
+    }
+
+    /// Set `gas` from the provider's estimate, increased by
+    /// `padding_percent` percent. `target` must be set first
+    #[cfg(feature = "providers")]
+    pub async fn estimate_gas<M>(
+        mut self,
+        provider: &M,
+        padding_percent: u64,
+    ) -> Result<Self, BuilderError>
+    where
+        M: Middleware,
+        M::Error: 'static,
+    {
+        self.builder = self.builder.estimate_gas(provider, padding_percent).await?;
+        Ok(self)
+    }
+
+    /// Set `nonce` to the user's current nonce in the chain's
+    /// `GelatoMetaBox`, read via the provider
+    #[cfg(feature = "providers")]
+    pub async fn fetch_nonce<M>(mut self, provider: &M) -> Result<Self, BuilderError>
+    where
+        M: Middleware,
+        M::Error: 'static,
+    {
+        self.builder = self.builder.fetch_nonce(provider).await?;
+        Ok(self)
+    }
+
     /// Set `gas`. Required
-    pub fn gas(mut self, val: impl Into<U64>) -> Self {
+    pub fn gas(mut self, val: impl Into<U256>) -> Self {
         self.builder.gas = Some(val.into());
         self
     }
 
+    /// Override the relay gas cap for this request. Defaults to the known cap
+    /// for `chain_id`
+    pub fn gas_cap(mut self, val: impl Into<U256>) -> Self {
+        self.builder.gas_cap = Some(val.into());
+        self
+    }
+
     /// Set `user_address`. Note that this will unset the existing signer
     pub fn user_address(mut self, val: Address) -> MetaTxRequestBuilderWithSponsor<'b, T> {
         self.builder.user = Some(val);
@@ -595,8 +1127,8 @@ where
     }
 
     /// Set `sponsor_chain_id`. Defaults to 1 (ethereum) if a sponsor is set
-    pub fn sponsor_chain_id(mut self, val: u64) -> Self {
-        self.builder.sponsor_chain_id = Some(val);
+    pub fn sponsor_chain_id(mut self, val: impl IntoChainId) -> Self {
+        self.builder.sponsor_chain_id = Some(val.into_chain_id());
         self
     }
 
@@ -607,18 +1139,139 @@ where
     }
 
     /// Set `deadline`. If set to 0, no deadline is
-    /// enforced
-    pub fn deadline(mut self, val: u64) -> Self {
-        self.builder.deadline = Some(val);
+    /// enforced. Accepts a timestamp or a [`Deadline`], e.g.
+    /// [`Deadline::ZERO`] to serialize an explicit 0
+    pub fn deadline(mut self, val: impl Into<Deadline>) -> Self {
+        self.builder.deadline = val.into();
         self
     }
 
-    /// Build this request
-    pub async fn build(self) -> eyre::Result<SignedMetaTxRequest> {
+    /// Set `deadline` to `validity` from now, according to the clock. The
+    /// clock's skew margin is added to the deadline
+    pub fn deadline_from(mut self, clock: &DeadlineClock, validity: Duration) -> Self {
+        self.builder.deadline = Deadline::At(clock.deadline_after(validity));
+        self
+    }
+
+    /// Set `deadline` to `validity` from now, according to the system clock.
+    /// Fails if the deadline can't be represented
+    pub fn deadline_in(mut self, validity: Duration) -> Result<Self, BuilderError> {
+        self.builder = self.builder.deadline_in(validity)?;
+        Ok(self)
+    }
+
+    /// Set `deadline` to a specific time
+    pub fn deadline_at(mut self, time: SystemTime) -> Self {
+        self.builder.deadline = Deadline::At(unix_secs(time));
+        self
+    }
+
+    /// Build this request and sign it as the user and sponsor. `chain_id` and
+    /// `sponsor_chain_id` default to those of the user and sponsor signers.
+    /// Explicitly set chain ids are kept, as the EIP-712 domains are taken
+    /// from the request rather than from the signers
+    pub async fn build(mut self) -> Result<SignedMetaTxRequest, BuilderError> {
+        if self.builder.chain_id.is_none() {
+            self.builder.chain_id = Some(self.user.chain_id());
+        }
+        if self.builder.sponsor_chain_id.is_none() {
+            self.builder.sponsor_chain_id = Some(self.sponsor.chain_id());
+        }
         Ok(self
             .builder
             .build()?
             .sign_with_sponsor(self.user, self.sponsor)
             .await?)
     }
+
+    /// Build and sign this request, send it to the relay, and get a future
+    /// tracking its status
+    pub async fn submit<'c, A>(
+        self,
+        client: &'c A,
+    ) -> Result<GelatoTask<'c, SignedMetaTxRequest, A>, BuilderError>
+    where
+        A: GelatoApi + ?Sized,
+    {
+        let request = self.build().await?;
+        let resp = client.send_meta_tx_request(&request).await?;
+        Ok(GelatoTask::new(resp.task_id(), client, request))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_computes_deadlines() {
+        let deadline = MetaTxRequestBuilder::default()
+            .deadline_in(Duration::from_secs(60))
+            .unwrap()
+            .deadline;
+        let now = unix_secs(crate::time::now());
+        assert!((now + 59..=now + 60).contains(&deadline.timestamp()));
+
+        let time = std::time::UNIX_EPOCH + Duration::from_secs(1_000);
+        let builder = MetaTxRequestBuilder::default().deadline_at(time);
+        assert_eq!(builder.deadline.timestamp(), 1_000);
+
+        assert!(matches!(
+            MetaTxRequestBuilder::default().deadline_in(Duration::MAX),
+            Err(BuilderError::DeadlineOverflow(_))
+        ));
+    }
+
+    #[cfg(feature = "signers")]
+    #[tokio::test]
+    async fn it_defaults_chain_ids_to_signers() {
+        use ethers_signers::{LocalWallet, Signer};
+
+        let user: LocalWallet = "11".repeat(32).parse().unwrap();
+        let user = user.with_chain_id(137u64);
+        let sponsor: LocalWallet = "22".repeat(32).parse().unwrap();
+        let sponsor = sponsor.with_chain_id(5u64);
+        let builder = MetaTxRequestBuilder::default()
+            .target(Address::repeat_byte(0x33))
+            .data(vec![1, 2, 3].into())
+            .fee_token(Address::repeat_byte(0x44))
+            .payment_type(PaymentType::AsyncGasTank)
+            .max_fee(1_000u64)
+            .gas(100_000u64)
+            .nonce(0)
+            .deadline_in(Duration::from_secs(60))
+            .unwrap();
+
+        let signed = builder
+            .clone()
+            .with_user(&user)
+            .sponsored_by(&sponsor)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(signed.chain_id, 137);
+        assert_eq!(signed.sponsor_chain_id, Some(5));
+
+        let signed = builder
+            .clone()
+            .sponsored_by(&sponsor)
+            .with_user(&user)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(signed.chain_id, 5);
+        assert_eq!(signed.sponsor_chain_id, Some(5));
+
+        // explicit chain ids win over the signers'
+        let signed = builder
+            .chain_id(100)
+            .with_user(&user)
+            .sponsored_by(&sponsor)
+            .sponsor_chain_id(1)
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(signed.chain_id, 100);
+        assert_eq!(signed.sponsor_chain_id, Some(1));
+    }
 }
@@ -1,5 +1,16 @@
+mod error;
+pub use error::BuilderError;
+
+mod estimate;
+
+mod erc2771;
+pub use erc2771::*;
+
 mod forward_req;
 pub use forward_req::*;
 
 mod meta_tx;
 pub use meta_tx::MetaTxRequestBuilder;
+
+mod template;
+pub use template::RequestTemplate;
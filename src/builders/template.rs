@@ -0,0 +1,123 @@
+use ethers_core::types::Bytes;
+
+use std::sync::{Mutex, MutexGuard};
+
+use super::{error::BuilderError, ForwardRequestBuilder, MetaTxRequestBuilder};
+use crate::rpc::{ForwardRequest, MetaTxRequest};
+
+/// A template for repeatedly submitting the same shape of request.
+///
+/// Captures every value of a [`ForwardRequestBuilder`] or
+/// [`MetaTxRequestBuilder`] except `nonce` and `data`, and stamps out
+/// requests with new calldata and an auto-incrementing local nonce. The
+/// nonce is only incremented when a request is built successfully. Use
+/// [`RequestTemplate::set_next_nonce`] to resynchronize after a request is
+/// dropped
+#[derive(Debug)]
+pub struct RequestTemplate<B> {
+    builder: B,
+    next_nonce: Mutex<usize>,
+}
+
+impl<B> RequestTemplate<B> {
+    /// Instantiate a template from a builder. The first request uses
+    /// `first_nonce`
+    pub fn new(builder: B, first_nonce: usize) -> Self {
+        Self {
+            builder,
+            next_nonce: Mutex::new(first_nonce),
+        }
+    }
+
+    /// The builder requests are stamped from
+    pub fn builder(&self) -> &B {
+        &self.builder
+    }
+
+    fn nonce(&self) -> MutexGuard<'_, usize> {
+        self.next_nonce.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The nonce of the next request
+    pub fn next_nonce(&self) -> usize {
+        *self.nonce()
+    }
+
+    /// Set the nonce of the next request
+    pub fn set_next_nonce(&self, nonce: usize) {
+        *self.nonce() = nonce;
+    }
+}
+
+impl<B: Clone> RequestTemplate<B> {
+    // build with the next nonce, incrementing it on success
+    fn stamp_with<T>(
+        &self,
+        build: impl FnOnce(B, usize) -> Result<T, BuilderError>,
+    ) -> Result<T, BuilderError> {
+        let mut nonce = self.nonce();
+        let request = build(self.builder.clone(), *nonce)?;
+        *nonce += 1;
+        Ok(request)
+    }
+}
+
+impl RequestTemplate<ForwardRequestBuilder> {
+    /// Build a forward request calling the target with `data`, using the
+    /// next nonce
+    pub fn stamp(&self, data: impl Into<Bytes>) -> Result<ForwardRequest, BuilderError> {
+        self.stamp_with(|builder, nonce| builder.data(data.into()).nonce(nonce).build())
+    }
+}
+
+impl RequestTemplate<MetaTxRequestBuilder> {
+    /// Build a meta tx request calling the target with `data`, using the
+    /// next nonce
+    pub fn stamp(&self, data: impl Into<Bytes>) -> Result<MetaTxRequest, BuilderError> {
+        self.stamp_with(|builder, nonce| builder.data(data.into()).nonce(nonce).build())
+    }
+}
+
+impl From<ForwardRequestBuilder> for RequestTemplate<ForwardRequestBuilder> {
+    fn from(builder: ForwardRequestBuilder) -> Self {
+        let nonce = builder.nonce.unwrap_or_default();
+        Self::new(builder, nonce)
+    }
+}
+
+impl From<MetaTxRequestBuilder> for RequestTemplate<MetaTxRequestBuilder> {
+    fn from(builder: MetaTxRequestBuilder) -> Self {
+        let nonce = builder.nonce.unwrap_or_default();
+        Self::new(builder, nonce)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_core::types::Address;
+
+    use super::*;
+
+    #[test]
+    fn it_stamps_requests_with_incrementing_nonces() {
+        let template: RequestTemplate<_> = ForwardRequestBuilder::default()
+            .target(Address::repeat_byte(1))
+            .sponsor_address(Address::repeat_byte(2))
+            .max_fee(1_000u64)
+            .gas(100_000u64)
+            .nonce(3)
+            .into();
+
+        let first = template.stamp(vec![1]).unwrap();
+        let second = template.stamp(vec![2]).unwrap();
+        assert_eq!((first.nonce, second.nonce), (3, 4));
+        assert_eq!(second.data, Bytes::from(vec![2]));
+
+        template.set_next_nonce(10);
+        assert_eq!(template.stamp(vec![3]).unwrap().nonce, 10);
+
+        let failing = RequestTemplate::new(MetaTxRequestBuilder::default(), 0);
+        assert!(failing.stamp(vec![]).is_err());
+        assert_eq!(failing.next_nonce(), 0);
+    }
+}
@@ -0,0 +1,94 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::time::Instant;
+
+/// A shared map whose entries expire a fixed time after insertion. Clones
+/// share entries
+#[derive(Debug, Clone)]
+pub(crate) struct TtlCache<K, V> {
+    ttl: Duration,
+    entries: Arc<Mutex<HashMap<K, (Instant, V)>>>,
+}
+
+impl<K, V> TtlCache<K, V>
+where
+    K: Eq + Hash,
+    V: Clone,
+{
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Default::default(),
+        }
+    }
+
+    /// Get the entry for `key`, if it has not expired
+    pub(crate) fn get(&self, key: &K) -> Option<V> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        match entries.get(key) {
+            Some((inserted, value)) if inserted.elapsed() < self.ttl => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Get the entry for `key`, inserting `value()` if it is missing or has
+    /// expired
+    pub(crate) fn get_or_insert_with(&self, key: K, value: impl FnOnce() -> V) -> V {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let ttl = self.ttl;
+        entries.retain(|_, (inserted, _)| inserted.elapsed() < ttl);
+        entries
+            .entry(key)
+            .or_insert_with(|| (Instant::now(), value()))
+            .1
+            .clone()
+    }
+
+    pub(crate) fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        // drop expired entries so that the map doesn't grow unbounded
+        let ttl = self.ttl;
+        entries.retain(|_, (inserted, _)| inserted.elapsed() < ttl);
+        entries.insert(key, (Instant::now(), value));
+    }
+
+    /// Drop all entries
+    pub(crate) fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_expires_entries() {
+        let cache = TtlCache::new(Duration::from_secs(60));
+        cache.insert(1, "a");
+        assert_eq!(cache.get(&1), Some("a"));
+        assert_eq!(cache.get(&2), None);
+
+        cache.clear();
+        assert_eq!(cache.get(&1), None);
+
+        assert_eq!(cache.get_or_insert_with(1, || "b"), "b");
+        assert_eq!(cache.get_or_insert_with(1, || "c"), "b");
+
+        let expired = TtlCache::new(Duration::ZERO);
+        expired.insert(1, "a");
+        assert_eq!(expired.get(&1), None);
+    }
+}
@@ -0,0 +1,75 @@
+use ethers_core::types::{Address, U256};
+
+use crate::utils::{get_forwarder, get_max_gas, get_meta_box};
+
+/// Named EVM chains, re-exported from ethers. Converts to and from numeric
+/// chain ids, and may be passed anywhere an [`IntoChainId`] is accepted
+///
+/// [`IntoChainId`]: crate::IntoChainId
+pub use ethers_core::types::Chain;
+
+/// A chain supported by the relay, with the metadata known for it. See
+/// [`GelatoClient::get_supported_networks`]
+///
+/// [`GelatoClient::get_supported_networks`]: crate::GelatoClient::get_supported_networks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkInfo {
+    /// Chain id
+    pub chain_id: u64,
+    /// The chain, if known to ethers
+    pub chain: Option<Chain>,
+    /// True if the chain has an active fee oracle
+    pub has_oracle: bool,
+    /// The `GelatoRelayForwarder` contract, if known to the SDK
+    pub forwarder: Option<Address>,
+    /// The `GelatoMetaBox` contract, if known to the SDK
+    pub meta_box: Option<Address>,
+    /// The default gas cap builders check requests against, if any. See
+    /// [`get_max_gas`](crate::get_max_gas)
+    pub max_gas: Option<U256>,
+}
+
+impl NetworkInfo {
+    /// The metadata known for a chain
+    pub fn new(chain_id: u64, has_oracle: bool) -> Self {
+        Self {
+            chain_id,
+            chain: Chain::try_from(chain_id).ok(),
+            has_oracle,
+            forwarder: get_forwarder(chain_id),
+            meta_box: get_meta_box(chain_id),
+            max_gas: get_max_gas(chain_id),
+        }
+    }
+
+    /// The chain's name, if the chain is known to ethers
+    pub fn name(&self) -> Option<String> {
+        self.chain.map(|chain| chain.to_string())
+    }
+
+    // combine the relay and oracle chain lists, in relay order
+    pub(crate) fn combine(relay_chains: &[u64], oracle_chains: &[u64]) -> Vec<Self> {
+        relay_chains
+            .iter()
+            .map(|chain_id| Self::new(*chain_id, oracle_chains.contains(chain_id)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_combines_network_info() {
+        let networks = NetworkInfo::combine(&[5, 1234], &[5]);
+        assert_eq!(networks.len(), 2);
+        assert_eq!(networks[0].chain, Some(Chain::Goerli));
+        assert_eq!(networks[0].name(), Some("goerli".to_owned()));
+        assert!(networks[0].has_oracle);
+        assert_eq!(networks[0].forwarder, get_forwarder(5));
+        assert_eq!(networks[0].max_gas, get_max_gas(5));
+        assert_eq!(networks[1].chain, None);
+        assert!(!networks[1].has_oracle);
+    }
+}
@@ -0,0 +1,292 @@
+use std::{fmt::Debug, future::Future, pin::Pin};
+
+use ethers_core::types::{U256, U64};
+
+use crate::{
+    offline::SignedRequest, rpc, ClientResult, FeeToken, GelatoClient, NetworkInfo, TaskId,
+};
+
+/// A boxed future returned by [`GelatoApi`] methods
+#[cfg(not(target_arch = "wasm32"))]
+pub type ApiFuture<'a, T> = Pin<Box<dyn Future<Output = ClientResult<T>> + Send + 'a>>;
+
+/// A boxed future returned by [`GelatoApi`] methods. Not `Send` on wasm32
+#[cfg(target_arch = "wasm32")]
+pub type ApiFuture<'a, T> = Pin<Box<dyn Future<Output = ClientResult<T>> + 'a>>;
+
+/// The Gelato Relay API. Implemented by [`GelatoClient`], and may be
+/// implemented by mocks or alternative transports.
+///
+/// The trait is object-safe, so it can be used as `&dyn GelatoApi`. Its
+/// methods take concrete argument types, unlike the more flexible inherent
+/// methods of [`GelatoClient`]
+pub trait GelatoApi: Debug + Send + Sync {
+    /// Send a transaction over the relay
+    fn send_relay_transaction<'a>(
+        &'a self,
+        params: &'a rpc::RelayRequest,
+        chain_id: u64,
+    ) -> ApiFuture<'a, rpc::RelayResponse>;
+
+    /// Send a relay v2 sponsored call
+    fn sponsored_call<'a>(
+        &'a self,
+        req: &'a rpc::SponsoredCallRequest,
+    ) -> ApiFuture<'a, rpc::RelayResponse>;
+
+    /// Send a signed relay v2 sponsored ERC-2771 call
+    fn sponsored_call_erc2771<'a>(
+        &'a self,
+        req: &'a rpc::SignedSponsoredCallErc2771,
+    ) -> ApiFuture<'a, rpc::RelayResponse>;
+
+    /// Send a signed relay v2 sponsored ERC-2771 call using concurrent replay
+    /// protection
+    fn sponsored_call_concurrent_erc2771<'a>(
+        &'a self,
+        req: &'a rpc::SignedSponsoredCallConcurrentErc2771,
+    ) -> ApiFuture<'a, rpc::RelayResponse>;
+
+    /// Send a relay v2 call with sync fee
+    fn call_with_sync_fee<'a>(
+        &'a self,
+        req: &'a rpc::CallWithSyncFeeRequest,
+    ) -> ApiFuture<'a, rpc::RelayResponse>;
+
+    /// Send a signed relay v2 ERC-2771 call with sync fee
+    fn call_with_sync_fee_erc2771<'a>(
+        &'a self,
+        req: &'a rpc::SignedCallWithSyncFeeErc2771,
+    ) -> ApiFuture<'a, rpc::RelayResponse>;
+
+    /// Send a signed relay v2 ERC-2771 call with sync fee using concurrent
+    /// replay protection
+    fn call_with_sync_fee_concurrent_erc2771<'a>(
+        &'a self,
+        req: &'a rpc::SignedCallWithSyncFeeConcurrentErc2771,
+    ) -> ApiFuture<'a, rpc::RelayResponse>;
+
+    /// Send a transaction forward call
+    fn send_forward_call<'a>(
+        &'a self,
+        params: &'a rpc::ForwardCall,
+    ) -> ApiFuture<'a, rpc::RelayResponse>;
+
+    /// Send a transaction forward request
+    fn send_forward_request<'a>(
+        &'a self,
+        params: &'a rpc::SignedForwardRequest,
+    ) -> ApiFuture<'a, rpc::RelayResponse>;
+
+    /// Send a meta tx request
+    fn send_meta_tx_request<'a>(
+        &'a self,
+        params: &'a rpc::SignedMetaTxRequest,
+    ) -> ApiFuture<'a, rpc::RelayResponse>;
+
+    /// Send a signed forward request or meta tx request
+    fn send_signed_request<'a>(
+        &'a self,
+        params: &'a SignedRequest,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        match params {
+            SignedRequest::ForwardRequest(req) => self.send_forward_request(req),
+            SignedRequest::MetaTxRequest(req) => self.send_meta_tx_request(req),
+        }
+    }
+
+    /// Get a list of supported chains
+    fn get_gelato_relay_chains(&self) -> ApiFuture<'_, Vec<u64>>;
+
+    /// Check if a chain id is supported by the relay
+    fn is_chain_supported(&self, chain_id: u64) -> ApiFuture<'_, bool> {
+        Box::pin(async move { Ok(self.get_gelato_relay_chains().await?.contains(&chain_id)) })
+    }
+
+    /// Get a list of chains with an active fee oracle
+    fn get_oracle_chains(&self) -> ApiFuture<'_, Vec<u64>>;
+
+    /// Get the chains supported by the relay, with whether each has a fee
+    /// oracle, and the contracts known to the SDK
+    fn get_supported_networks(&self) -> ApiFuture<'_, Vec<NetworkInfo>> {
+        Box::pin(async move {
+            let (relay_chains, oracle_chains) = futures_util::future::try_join(
+                self.get_gelato_relay_chains(),
+                self.get_oracle_chains(),
+            )
+            .await?;
+            Ok(NetworkInfo::combine(&relay_chains, &oracle_chains))
+        })
+    }
+
+    /// Get the payment tokens accepted by the fee oracle on a chain
+    fn get_payment_tokens(&self, chain_id: u64) -> ApiFuture<'_, Vec<FeeToken>>;
+
+    /// Get the estimated fee for a specific amount of gas on a specific
+    /// chain, denominated in a specific payment token
+    fn get_estimated_fee(
+        &self,
+        chain_id: u64,
+        payment_token: FeeToken,
+        gas_limit: U64,
+        is_high_priority: bool,
+    ) -> ApiFuture<'_, U256>;
+
+    /// Get the estimated fee for a specific amount of gas on a specific
+    /// chain, denominated in the chain's native token
+    fn get_estimated_native_fee(
+        &self,
+        chain_id: u64,
+        gas_limit: U64,
+        is_high_priority: bool,
+    ) -> ApiFuture<'_, U256> {
+        self.get_estimated_fee(chain_id, FeeToken::default(), gas_limit, is_high_priority)
+    }
+
+    /// Fetch the status of a task
+    fn get_task_status(&self, task_id: TaskId) -> ApiFuture<'_, rpc::TransactionStatus>;
+
+    /// Fetch all statuses the API reports for a task
+    fn get_task_statuses(&self, task_id: TaskId) -> ApiFuture<'_, Vec<rpc::TransactionStatus>> {
+        Box::pin(async move { Ok(vec![self.get_task_status(task_id).await?]) })
+    }
+
+    /// Fetch the status of a task from the v2 status endpoint. Defaults to
+    /// converting the v1 status
+    fn get_task_status_v2(&self, task_id: TaskId) -> ApiFuture<'_, rpc::TaskStatusV2> {
+        Box::pin(async move { Ok(self.get_task_status(task_id).await?.into()) })
+    }
+}
+
+impl GelatoApi for GelatoClient {
+    fn send_relay_transaction<'a>(
+        &'a self,
+        params: &'a rpc::RelayRequest,
+        chain_id: u64,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        Box::pin(GelatoClient::send_relay_transaction(self, params, chain_id))
+    }
+
+    fn sponsored_call<'a>(
+        &'a self,
+        req: &'a rpc::SponsoredCallRequest,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        Box::pin(GelatoClient::sponsored_call(self, req))
+    }
+
+    fn sponsored_call_erc2771<'a>(
+        &'a self,
+        req: &'a rpc::SignedSponsoredCallErc2771,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        Box::pin(GelatoClient::sponsored_call_erc2771(self, req))
+    }
+
+    fn sponsored_call_concurrent_erc2771<'a>(
+        &'a self,
+        req: &'a rpc::SignedSponsoredCallConcurrentErc2771,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        Box::pin(GelatoClient::sponsored_call_concurrent_erc2771(self, req))
+    }
+
+    fn call_with_sync_fee<'a>(
+        &'a self,
+        req: &'a rpc::CallWithSyncFeeRequest,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        Box::pin(GelatoClient::call_with_sync_fee(self, req))
+    }
+
+    fn call_with_sync_fee_erc2771<'a>(
+        &'a self,
+        req: &'a rpc::SignedCallWithSyncFeeErc2771,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        Box::pin(GelatoClient::call_with_sync_fee_erc2771(self, req))
+    }
+
+    fn call_with_sync_fee_concurrent_erc2771<'a>(
+        &'a self,
+        req: &'a rpc::SignedCallWithSyncFeeConcurrentErc2771,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        Box::pin(GelatoClient::call_with_sync_fee_concurrent_erc2771(
+            self, req,
+        ))
+    }
+
+    fn send_forward_call<'a>(
+        &'a self,
+        params: &'a rpc::ForwardCall,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        Box::pin(GelatoClient::send_forward_call(self, params))
+    }
+
+    fn send_forward_request<'a>(
+        &'a self,
+        params: &'a rpc::SignedForwardRequest,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        Box::pin(GelatoClient::send_forward_request(self, params))
+    }
+
+    fn send_meta_tx_request<'a>(
+        &'a self,
+        params: &'a rpc::SignedMetaTxRequest,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        Box::pin(GelatoClient::send_meta_tx_request(self, params))
+    }
+
+    fn get_gelato_relay_chains(&self) -> ApiFuture<'_, Vec<u64>> {
+        Box::pin(GelatoClient::get_gelato_relay_chains(self))
+    }
+
+    fn get_oracle_chains(&self) -> ApiFuture<'_, Vec<u64>> {
+        Box::pin(GelatoClient::get_oracle_chains(self))
+    }
+
+    fn get_supported_networks(&self) -> ApiFuture<'_, Vec<NetworkInfo>> {
+        Box::pin(GelatoClient::get_supported_networks(self))
+    }
+
+    fn get_payment_tokens(&self, chain_id: u64) -> ApiFuture<'_, Vec<FeeToken>> {
+        Box::pin(GelatoClient::get_payment_tokens(self, chain_id))
+    }
+
+    fn get_estimated_fee(
+        &self,
+        chain_id: u64,
+        payment_token: FeeToken,
+        gas_limit: U64,
+        is_high_priority: bool,
+    ) -> ApiFuture<'_, U256> {
+        Box::pin(GelatoClient::get_estimated_fee(
+            self,
+            chain_id,
+            payment_token,
+            gas_limit,
+            is_high_priority,
+        ))
+    }
+
+    fn get_task_status(&self, task_id: TaskId) -> ApiFuture<'_, rpc::TransactionStatus> {
+        Box::pin(GelatoClient::get_task_status(self, task_id))
+    }
+
+    fn get_task_statuses(&self, task_id: TaskId) -> ApiFuture<'_, Vec<rpc::TransactionStatus>> {
+        Box::pin(GelatoClient::get_task_statuses(self, task_id))
+    }
+
+    fn get_task_status_v2(&self, task_id: TaskId) -> ApiFuture<'_, rpc::TaskStatusV2> {
+        Box::pin(GelatoClient::get_task_status_v2(self, task_id))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GelatoTask;
+
+    #[test]
+    fn it_tracks_tasks_through_trait_objects() {
+        let client = GelatoClient::default();
+        let api: &dyn GelatoApi = &client;
+        let task_id = TaskId::from(ethers_core::types::H256::repeat_byte(1));
+        let _task: GelatoTask<'_, (), dyn GelatoApi> = GelatoTask::new(task_id, api, ());
+    }
+}
@@ -0,0 +1,487 @@
+use std::{sync::Arc, time::Duration};
+
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Url,
+};
+
+use super::{ApiKey, GelatoClient, Interceptor, MetricsObserver, RetryPolicy};
+use crate::IntoChainId;
+
+/// Errors building a [`GelatoClient`]
+#[derive(Debug, thiserror::Error)]
+pub enum ClientBuildError {
+    /// A base URL could not be parsed
+    #[error("Invalid URL {url:?}: {source}")]
+    InvalidUrl {
+        /// The offending URL
+        url: String,
+        /// Underlying error
+        source: url::ParseError,
+    },
+    /// A base URL was not an http(s) URL
+    #[error("Unsupported URL {0}. Expected an http or https URL")]
+    UnsupportedUrl(Url),
+    /// A default header name or value was invalid
+    #[error("Invalid header {0:?}")]
+    InvalidHeader(String),
+    /// The proxy URL was invalid
+    #[error("Invalid proxy URL {url:?}: {source}")]
+    InvalidProxy {
+        /// The offending URL
+        url: String,
+        /// Underlying error
+        source: reqwest::Error,
+    },
+    /// Headers, timeouts or a proxy were configured alongside a custom
+    /// reqwest Client, which they can't be applied to
+    #[error("Headers, timeouts and proxies can't be applied to a custom reqwest Client. Configure them on the Client instead")]
+    CustomClientConflict,
+    /// The reqwest Client could not be built
+    #[error("{0}")]
+    Reqwest(#[from] reqwest::Error),
+}
+
+/// Configures and builds a [`GelatoClient`]
+///
+/// URLs and headers are validated when the client is built
+#[derive(Debug, Default)]
+pub struct GelatoClientBuilder {
+    relay_url: Option<String>,
+    api_url: Option<String>,
+    chain_urls: Vec<(u64, String)>,
+    api_key: Option<String>,
+    app_id: Option<String>,
+    headers: Vec<(String, String)>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<String>,
+    proxy_auth: Option<(String, String)>,
+    no_proxy: Option<String>,
+    client: Option<reqwest::Client>,
+    retry: Option<RetryPolicy>,
+    metrics: Option<Arc<dyn MetricsObserver>>,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+}
+
+fn parse_base_url(url: String) -> Result<Url, ClientBuildError> {
+    // without a trailing slash, the last path segment would be replaced
+    // when joining endpoint paths
+    let with_slash = if url.ends_with('/') {
+        url.clone()
+    } else {
+        format!("{url}/")
+    };
+    let parsed: Url = with_slash
+        .parse()
+        .map_err(|source| ClientBuildError::InvalidUrl { url, source })?;
+
+    match parsed.scheme() {
+        "http" | "https" if !parsed.cannot_be_a_base() => Ok(parsed),
+        _ => Err(ClientBuildError::UnsupportedUrl(parsed)),
+    }
+}
+
+impl GelatoClientBuilder {
+    /// Instantiate a builder with the default configuration
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the base URL for both relay and API endpoints
+    #[must_use]
+    pub fn url(self, url: impl Into<String>) -> Self {
+        let url = url.into();
+        self.relay_url(url.clone()).api_url(url)
+    }
+
+    /// Use Gelato's staging endpoints, see [`GELATO_STAGING_RELAY_URL`] and
+    /// [`GELATO_STAGING_API_URL`]
+    ///
+    /// [`GELATO_STAGING_RELAY_URL`]: crate::GELATO_STAGING_RELAY_URL
+    /// [`GELATO_STAGING_API_URL`]: crate::GELATO_STAGING_API_URL
+    #[must_use]
+    pub fn staging(self) -> Self {
+        self.relay_url(crate::GELATO_STAGING_RELAY_URL)
+            .api_url(crate::GELATO_STAGING_API_URL)
+    }
+
+    /// Set the base URL for relay submission endpoints
+    #[must_use]
+    pub fn relay_url(mut self, url: impl Into<String>) -> Self {
+        self.relay_url = Some(url.into());
+        self
+    }
+
+    /// Set the base URL for fee oracle and task status endpoints
+    #[must_use]
+    pub fn api_url(mut self, url: impl Into<String>) -> Self {
+        self.api_url = Some(url.into());
+        self
+    }
+
+    /// Set the base URL for the relay and fee oracle endpoints of one chain.
+    /// See [`GelatoClient::with_chain_url`]
+    #[must_use]
+    pub fn chain_url(mut self, chain_id: impl IntoChainId, url: impl Into<String>) -> Self {
+        self.chain_urls.push((chain_id.into_chain_id(), url.into()));
+        self
+    }
+
+    /// Authenticate every request with a sponsor API key
+    #[must_use]
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Identify an application in the `User-Agent` header, after the SDK
+    /// identifier. See [`GelatoClient::with_app_id`]
+    #[must_use]
+    pub fn app_id(mut self, app_id: impl Into<String>) -> Self {
+        self.app_id = Some(app_id.into());
+        self
+    }
+
+    /// Send a header with every request. A `User-Agent` header is
+    /// overridden by the SDK's, see [`Self::app_id`]
+    #[must_use]
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set the total timeout of each request. Unavailable on wasm32, where
+    /// the browser manages request timeouts
+    #[cfg(not(target_arch = "wasm32"))]
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout for establishing connections. Unavailable on wasm32
+    #[cfg(not(target_arch = "wasm32"))]
+    #[must_use]
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Send all requests through an HTTP or HTTPS proxy. Without a proxy,
+    /// the `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY` environment variables
+    /// are respected. Unavailable on wasm32
+    #[cfg(not(target_arch = "wasm32"))]
+    #[must_use]
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Authenticate with the proxy using basic auth. Has no effect without
+    /// [`Self::proxy`]
+    #[cfg(not(target_arch = "wasm32"))]
+    #[must_use]
+    pub fn proxy_basic_auth(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.proxy_auth = Some((username.into(), password.into()));
+        self
+    }
+
+    /// Bypass the proxy for a comma-separated list of hosts, domains and IP
+    /// ranges, in the format of the `NO_PROXY` environment variable. Has no
+    /// effect without [`Self::proxy`]
+    #[cfg(not(target_arch = "wasm32"))]
+    #[must_use]
+    pub fn no_proxy(mut self, hosts: impl Into<String>) -> Self {
+        self.no_proxy = Some(hosts.into());
+        self
+    }
+
+    /// Use a custom reqwest Client. Conflicts with headers, timeouts and
+    /// proxies, which must be configured on the Client itself
+    #[must_use]
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Retry transient request failures according to `policy`
+    #[must_use]
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn build_proxy(&self, url: &str) -> Result<reqwest::Proxy, ClientBuildError> {
+        let mut proxy =
+            reqwest::Proxy::all(url).map_err(|source| ClientBuildError::InvalidProxy {
+                url: url.to_owned(),
+                source,
+            })?;
+        if let Some((username, password)) = &self.proxy_auth {
+            proxy = proxy.basic_auth(username, password);
+        }
+        if let Some(hosts) = &self.no_proxy {
+            proxy = proxy.no_proxy(reqwest::NoProxy::from_string(hosts));
+        }
+        Ok(proxy)
+    }
+
+    /// Report every request to a metrics observer
+    #[must_use]
+    pub fn metrics(mut self, observer: impl MetricsObserver + 'static) -> Self {
+        self.metrics = Some(Arc::new(observer));
+        self
+    }
+
+    /// Pass every request through an interceptor, after those already
+    /// installed
+    #[must_use]
+    pub fn interceptor(mut self, interceptor: impl Interceptor + 'static) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
+    fn build_http_client(&self) -> Result<reqwest::Client, ClientBuildError> {
+        let configured = !self.headers.is_empty()
+            || self.timeout.is_some()
+            || self.connect_timeout.is_some()
+            || self.proxy.is_some();
+
+        if let Some(client) = &self.client {
+            if configured {
+                return Err(ClientBuildError::CustomClientConflict);
+            }
+            return Ok(client.clone());
+        }
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| ClientBuildError::InvalidHeader(name.clone()))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|_| ClientBuildError::InvalidHeader(name.to_string()))?;
+            headers.append(name, value);
+        }
+
+        #[allow(unused_mut)]
+        let mut builder = reqwest::Client::builder().default_headers(headers);
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(timeout) = self.timeout {
+                builder = builder.timeout(timeout);
+            }
+            if let Some(timeout) = self.connect_timeout {
+                builder = builder.connect_timeout(timeout);
+            }
+            if let Some(url) = &self.proxy {
+                builder = builder.proxy(self.build_proxy(url)?);
+            }
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Build the client
+    ///
+    /// # Errors
+    ///
+    /// If a URL, header or proxy is invalid, or headers, timeouts or a proxy
+    /// are configured alongside a custom reqwest Client
+    pub fn build(self) -> Result<GelatoClient, ClientBuildError> {
+        let client = self.build_http_client()?;
+        let mut gelato = GelatoClient {
+            client,
+            api_key: self.api_key.map(ApiKey),
+            retry: self.retry,
+            metrics: self.metrics,
+            interceptors: self.interceptors,
+            ..Default::default()
+        };
+        if let Some(app_id) = self.app_id {
+            gelato = gelato.with_app_id(app_id);
+        }
+        if let Some(url) = self.relay_url {
+            gelato.url = parse_base_url(url)?;
+        }
+        if let Some(url) = self.api_url {
+            gelato.api_url = parse_base_url(url)?;
+        }
+        for (chain_id, url) in self.chain_urls {
+            gelato.chain_urls.insert(chain_id, parse_base_url(url)?);
+        }
+        Ok(gelato)
+    }
+}
+
+impl GelatoClient {
+    /// Configure a client with a [`GelatoClientBuilder`]
+    pub fn builder() -> GelatoClientBuilder {
+        GelatoClientBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::SDK_USER_AGENT;
+
+    #[test]
+    fn it_validates_urls() {
+        let client = GelatoClient::builder()
+            .url("http://localhost:8080/gelato")
+            .build()
+            .unwrap();
+        assert_eq!(client.relay_url().as_str(), "http://localhost:8080/gelato/");
+        assert_eq!(client.api_url(), client.relay_url());
+
+        assert!(matches!(
+            GelatoClient::builder().relay_url("not a url").build(),
+            Err(ClientBuildError::InvalidUrl { .. })
+        ));
+        assert!(matches!(
+            GelatoClient::builder().api_url("ftp://example.com").build(),
+            Err(ClientBuildError::UnsupportedUrl(_))
+        ));
+    }
+
+    #[test]
+    fn it_overrides_chain_urls() {
+        let client = GelatoClient::builder()
+            .chain_url(5u64, "https://mirror.example.com")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            client.send_relay_transaction_url(5).unwrap().as_str(),
+            "https://mirror.example.com/relays/5"
+        );
+        assert_eq!(
+            client.relay_v2_url(5, "sponsored-call").unwrap().as_str(),
+            "https://mirror.example.com/relays/v2/sponsored-call"
+        );
+        assert_eq!(
+            client.payment_tokens_url(5).unwrap().as_str(),
+            "https://mirror.example.com/oracles/5/paymentTokens"
+        );
+        assert_eq!(
+            client.send_relay_transaction_url(1).unwrap().as_str(),
+            "https://relay.gelato.digital/relays/1"
+        );
+        assert_eq!(client.chain_api_url(1u64), client.api_url());
+    }
+
+    #[test]
+    fn it_keeps_chain_url_path_prefixes() {
+        let client = GelatoClient::builder()
+            .chain_url(5u64, "https://mirror.example.com/gelato")
+            .build()
+            .unwrap()
+            .with_chain_url(1u64, "https://mirror.example.com/mainnet")
+            .unwrap();
+
+        assert_eq!(
+            client.send_relay_transaction_url(5).unwrap().as_str(),
+            "https://mirror.example.com/gelato/relays/5"
+        );
+        let query = crate::rpc::EstimatedFeeRequest {
+            payment_token: Default::default(),
+            gas_limit: 100_000u64.into(),
+            is_high_priority: false,
+        };
+        assert_eq!(
+            client.estimated_fee_url(5, &query).unwrap().path(),
+            "/gelato/oracles/5/estimate"
+        );
+        assert_eq!(
+            client.send_relay_transaction_url(1).unwrap().as_str(),
+            "https://mirror.example.com/mainnet/relays/1"
+        );
+    }
+
+    #[test]
+    fn it_keeps_base_url_path_prefixes_for_task_status() {
+        let client = GelatoClient::builder()
+            .url("https://mirror.example.com/gelato")
+            .build()
+            .unwrap();
+        let task_id = crate::TaskId::from(ethers_core::types::H256::repeat_byte(1));
+
+        assert_eq!(
+            client.get_task_status_url(task_id).path(),
+            format!("/gelato/tasks/GelatoMetaBox/{task_id}/")
+        );
+        assert_eq!(
+            client.get_task_status_v2_url(task_id).path(),
+            format!("/gelato/tasks/status/{task_id}")
+        );
+    }
+
+    #[test]
+    fn it_uses_staging_presets() {
+        let client = GelatoClient::builder().staging().build().unwrap();
+        assert_eq!(client.relay_url().as_str(), crate::GELATO_STAGING_RELAY_URL);
+        assert_eq!(client.api_url().as_str(), crate::GELATO_STAGING_API_URL);
+        assert_eq!(client.relay_url(), GelatoClient::staging().relay_url());
+        assert_eq!(
+            GelatoClient::production().relay_url().as_str(),
+            crate::GELATO_RELAY_URL
+        );
+    }
+
+    #[test]
+    fn it_identifies_the_app() {
+        let client = GelatoClient::builder().build().unwrap();
+        assert_eq!(client.user_agent(), SDK_USER_AGENT);
+
+        let client = GelatoClient::builder()
+            .app_id("my-dapp/1.2.0")
+            .build()
+            .unwrap();
+        assert_eq!(
+            client.user_agent(),
+            format!("gelato-sdk-rs/{} my-dapp/1.2.0", env!("CARGO_PKG_VERSION"))
+        );
+    }
+
+    #[test]
+    fn it_rejects_conflicting_config() {
+        assert!(matches!(
+            GelatoClient::builder()
+                .client(Default::default())
+                .timeout(Duration::from_secs(1))
+                .build(),
+            Err(ClientBuildError::CustomClientConflict)
+        ));
+        assert!(matches!(
+            GelatoClient::builder().header("bad header", "x").build(),
+            Err(ClientBuildError::InvalidHeader(_))
+        ));
+    }
+
+    #[test]
+    fn it_configures_proxies() {
+        GelatoClient::builder()
+            .proxy("http://proxy.example.com:3128")
+            .proxy_basic_auth("user", "pass")
+            .no_proxy("localhost,10.0.0.0/8")
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            GelatoClient::builder().proxy("not a url").build(),
+            Err(ClientBuildError::InvalidProxy { .. })
+        ));
+        assert!(matches!(
+            GelatoClient::builder()
+                .client(Default::default())
+                .proxy("http://proxy.example.com:3128")
+                .build(),
+            Err(ClientBuildError::CustomClientConflict)
+        ));
+    }
+}
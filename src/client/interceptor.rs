@@ -0,0 +1,89 @@
+use std::fmt::Debug;
+
+/// Intercepts the HTTP requests made by a [`GelatoClient`], e.g. for custom
+/// authentication, signing headers, audit logging or chaos testing.
+///
+/// Interceptors see every attempt, including retries. Requests pass through
+/// interceptors in the order they were installed, and responses in reverse
+/// order. Methods are called synchronously and should not block
+///
+/// [`GelatoClient`]: crate::GelatoClient
+pub trait Interceptor: Debug + Send + Sync {
+    /// Inspect or modify an outgoing request
+    fn on_request(&self, _request: &mut reqwest::Request) {}
+
+    /// Inspect the status and headers of a response. The body is left for
+    /// the client to read
+    fn on_response(&self, _response: &reqwest::Response) {}
+
+    /// Inspect a request that failed without a response
+    fn on_error(&self, _error: &reqwest::Error) {}
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::GelatoClient;
+
+    #[derive(Debug, Default)]
+    struct Recorder {
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Interceptor for Recorder {
+        fn on_request(&self, request: &mut reqwest::Request) {
+            request
+                .headers_mut()
+                .insert("x-audit", "1".parse().unwrap());
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("request {}", request.url().path()));
+        }
+
+        fn on_error(&self, _error: &reqwest::Error) {
+            self.events.lock().unwrap().push("error".to_owned());
+        }
+    }
+
+    #[tokio::test]
+    async fn it_intercepts_requests() {
+        let recorder = Recorder::default();
+        let events = recorder.events.clone();
+        // nothing listens on port 1, so the request fails to connect
+        let client = GelatoClient::new("http://127.0.0.1:1/")
+            .unwrap()
+            .with_interceptor(recorder);
+
+        assert!(client.raw_get("relays/").await.is_err());
+        assert_eq!(*events.lock().unwrap(), ["request /relays/", "error"]);
+    }
+
+    #[derive(Debug, Default)]
+    struct CorrelationIds(Arc<Mutex<Vec<String>>>);
+
+    impl Interceptor for CorrelationIds {
+        fn on_request(&self, request: &mut reqwest::Request) {
+            let id = &request.headers()[crate::CORRELATION_ID_HEADER];
+            self.0.lock().unwrap().push(id.to_str().unwrap().to_owned());
+        }
+    }
+
+    #[tokio::test]
+    async fn it_sends_correlation_ids() {
+        let ids = CorrelationIds::default();
+        let seen = ids.0.clone();
+        let client = GelatoClient::new("http://127.0.0.1:1/")
+            .unwrap()
+            .with_interceptor(ids);
+
+        assert!(client.raw_get("relays/").await.is_err());
+        assert!(client.raw_get("relays/").await.is_err());
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[0].len(), 16);
+        assert_ne!(seen[0], seen[1]);
+    }
+}
@@ -0,0 +1,95 @@
+use std::{fmt::Debug, time::Duration};
+
+use reqwest::{Method, StatusCode, Url};
+
+/// A request made by a [`GelatoClient`]
+///
+/// [`GelatoClient`]: crate::GelatoClient
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestInfo {
+    /// HTTP method
+    pub method: Method,
+    /// Low-cardinality endpoint label: the URL path, with chain ids, task
+    /// ids and other identifiers replaced by `{id}`, e.g.
+    /// `/oracles/{id}/estimate`
+    pub endpoint: String,
+}
+
+impl RequestInfo {
+    pub(crate) fn new(method: Method, url: &Url) -> Self {
+        Self {
+            method,
+            endpoint: endpoint_label(url),
+        }
+    }
+}
+
+/// The outcome of a request made by a [`GelatoClient`]
+///
+/// [`GelatoClient`]: crate::GelatoClient
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestOutcome {
+    /// Time until the final response headers were received, or the final
+    /// attempt failed. Includes retries
+    pub latency: Duration,
+    /// HTTP status of the final response. `None` if no response was
+    /// received
+    pub status: Option<StatusCode>,
+    /// Number of attempts made, including retries
+    pub attempts: usize,
+}
+
+/// Observes the requests made by a [`GelatoClient`], e.g. to record metrics.
+/// Methods are called synchronously and should not block
+///
+/// [`GelatoClient`]: crate::GelatoClient
+pub trait MetricsObserver: Debug + Send + Sync {
+    /// Called before a request is first sent
+    fn request_started(&self, _request: &RequestInfo) {}
+
+    /// Called once a request has finished, after any retries
+    fn request_finished(&self, _request: &RequestInfo, _outcome: &RequestOutcome) {}
+}
+
+// identifiers are decimal chain ids, or hex task ids and addresses
+fn is_identifier(segment: &str) -> bool {
+    let hex = segment.strip_prefix("0x").unwrap_or(segment);
+    segment.bytes().all(|b| b.is_ascii_digit())
+        || (hex.len() >= 40 && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+fn endpoint_label(url: &Url) -> String {
+    url.path()
+        .split('/')
+        .map(|segment| {
+            if !segment.is_empty() && is_identifier(segment) {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_labels_endpoints() {
+        let label = |url: &str| endpoint_label(&url.parse().unwrap());
+        assert_eq!(
+            label("https://api.gelato.digital/oracles/5/estimate?gasLimit=1"),
+            "/oracles/{id}/estimate"
+        );
+        assert_eq!(
+            label("https://api.gelato.digital/tasks/GelatoMetaBox/0xce52ae7a6a3032848d76b161ac4c131fa995dcc67e3be5392dfb8466275d6679/"),
+            "/tasks/GelatoMetaBox/{id}/"
+        );
+        assert_eq!(
+            label("https://relay.gelato.digital/relays/v2/sponsored-call"),
+            "/relays/v2/sponsored-call"
+        );
+    }
+}
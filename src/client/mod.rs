@@ -0,0 +1,1721 @@
+use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
+
+use futures_util::{lock::Mutex as AsyncMutex, stream, StreamExt};
+use reqwest::{IntoUrl, StatusCode, Url};
+
+use ethers_core::types::{transaction::eip712::Eip712, H256, U256, U64};
+use once_cell::sync::Lazy;
+
+use crate::{
+    cache::TtlCache,
+    clock::ClockSkew,
+    json_get, json_post,
+    offline::{self, ImportError, SignedRequest},
+    rpc::{self},
+    task::GelatoTask,
+    timer::{FuturesTimer, Timer},
+    FeeToken, IntoChainId, NetworkInfo, TaskId,
+};
+
+mod api;
+pub use api::*;
+
+mod builder;
+pub use builder::*;
+
+mod interceptor;
+pub use interceptor::Interceptor;
+
+mod metrics;
+pub use metrics::*;
+
+mod retry;
+pub(crate) use retry::GelatoRequest;
+pub use retry::RetryPolicy;
+
+/// Base URL of Gelato's production relay submission endpoints
+pub const GELATO_RELAY_URL: &str = "https://relay.gelato.digital/";
+
+/// Base URL of Gelato's production fee oracle and task status endpoints
+pub const GELATO_API_URL: &str = "https://api.gelato.digital/";
+
+/// Base URL of Gelato's staging relay submission endpoints, for testing
+/// integrations against testnets
+pub const GELATO_STAGING_RELAY_URL: &str = "https://relay.staging.gelato.digital/";
+
+/// Base URL of Gelato's staging fee oracle and task status endpoints
+pub const GELATO_STAGING_API_URL: &str = "https://api.staging.gelato.digital/";
+
+static DEFAULT_URL: Lazy<reqwest::Url> = Lazy::new(|| GELATO_RELAY_URL.parse().unwrap());
+
+static DEFAULT_API_URL: Lazy<reqwest::Url> = Lazy::new(|| GELATO_API_URL.parse().unwrap());
+
+static STAGING_URL: Lazy<reqwest::Url> = Lazy::new(|| GELATO_STAGING_RELAY_URL.parse().unwrap());
+
+static STAGING_API_URL: Lazy<reqwest::Url> = Lazy::new(|| GELATO_STAGING_API_URL.parse().unwrap());
+
+/// Header carrying the sponsor API key, when one is configured
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Header carrying the idempotency key of forward request and meta tx
+/// submissions: the EIP-712 digest of the request
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Header carrying a random id for each request, also recorded in the
+/// tracing span of the client call. Retries of a request reuse its id
+pub const CORRELATION_ID_HEADER: &str = "X-Correlation-Id";
+
+/// Default `User-Agent` header, identifying the SDK and its version
+pub const SDK_USER_AGENT: &str = concat!("gelato-sdk-rs/", env!("CARGO_PKG_VERSION"));
+
+/// Default validity of fee quotes
+pub const DEFAULT_QUOTE_TTL: Duration = Duration::from_secs(60);
+
+/// Default granularity of the fee estimate cache. Cached estimates are
+/// requested for the gas limit rounded up to a multiple of this
+pub const DEFAULT_FEE_GAS_BUCKET: u64 = 10_000;
+
+/// Default number of requests `send_many` keeps in flight
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 8;
+
+/// Default validity of the cached supported-chain list
+pub const DEFAULT_CHAIN_CACHE_TTL: Duration = Duration::from_secs(300);
+
+// chain id, payment token, gas bucket, priority
+type FeeKey = (u64, FeeToken, U64, bool);
+
+/// Gelato Client Errors
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    /// Reqwest Error
+    #[error("{0}")]
+    Reqwest(#[from] reqwest::Error),
+    /// Url Parsing Error
+    #[error("{0}")]
+    UrlParse(#[from] url::ParseError),
+    /// Serde Json deser Error
+    #[error("{0}")]
+    SerdeError(#[from] serde_json::Error),
+    /// JSON response did not match the expected type
+    #[error("Unexpected response at `{path}`: {source}{}", describe_value(.value))]
+    Deserialize {
+        /// Path of the offending field, e.g. `data[0].lastCheck.taskState`
+        path: String,
+        /// The offending value, if it could be located in the response
+        value: Option<serde_json::Value>,
+        /// Underlying error
+        source: serde_json::Error,
+    },
+    /// Query string serialization Error
+    #[error("{0}")]
+    QueryEncode(#[from] serde_urlencoded::ser::Error),
+    /// Attempted to submit a request priced off an expired fee quote
+    #[error("Fee quote expired. Re-estimate the fee before submitting")]
+    FeeQuoteExpired,
+    /// Could not import a previously exported request
+    #[error("{0}")]
+    Import(#[from] ImportError),
+    /// Refused to submit a request for a chain the relay does not support
+    #[error("Chain {0} is not supported by the relay")]
+    UnsupportedChain(u64),
+    /// The API returned an error body
+    #[error("Gelato API error{}: {message}", .code.as_ref().map(|c| format!(" {c}")).unwrap_or_default())]
+    Api {
+        /// Error code, if the API provided one
+        code: Option<String>,
+        /// Error message
+        message: String,
+    },
+    /// The status endpoint returned an error body
+    #[error("{0}")]
+    TaskStatus(rpc::TaskStatusError),
+    /// The server responded with a non-2xx status
+    #[error("HTTP error {status}: {body}")]
+    Http {
+        /// Response status
+        status: StatusCode,
+        /// Response body text
+        body: String,
+    },
+    /// Sponsored relay v2 requests require an API key, see
+    /// [`GelatoClient::with_api_key`]
+    #[error("Sponsored requests require an API key")]
+    MissingApiKey,
+    /// The API returned no status for the task
+    #[error("No status found for task {0:?}")]
+    TaskNotFound(TaskId),
+    /// Server response had no parseable `Date` header
+    #[error("Server response had no valid Date header")]
+    NoServerDate,
+    /// Refused to submit a request because shutdown was triggered
+    #[error("Shutdown was triggered before the request was submitted")]
+    Shutdown,
+    /// Error from an underlying ethers provider
+    #[cfg(feature = "providers")]
+    #[error("{0}")]
+    Provider(ethers_providers::ProviderError),
+    /// Other Error
+    #[error("{0}")]
+    Other(String),
+}
+
+/// Broad classification of API errors, for matching on common failures.
+///
+/// The API does not consistently provide error codes, so classification
+/// relies on the error message, and may fall back to [`ApiErrorKind::Other`]
+/// as messages change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiErrorKind {
+    /// The chain is not supported by the relay
+    UnsupportedChain,
+    /// `maxFee` is lower than the current fee
+    MaxFeeTooLow,
+    /// The sponsor's balance can't cover the fee
+    InsufficientBalance,
+    /// A signature did not verify
+    InvalidSignature,
+    /// Too many requests
+    RateLimited,
+    /// Task id unknown to the API
+    TaskNotFound,
+    /// Any other error
+    Other,
+}
+
+impl ApiErrorKind {
+    /// Classify an API error message
+    pub fn classify(message: &str) -> Self {
+        let message = message.to_lowercase();
+        let has = |s: &str| message.contains(s);
+
+        if has("chain") && (has("not supported") || has("unsupported")) {
+            Self::UnsupportedChain
+        } else if has("maxfee") || has("max fee") {
+            Self::MaxFeeTooLow
+        } else if has("balance") {
+            Self::InsufficientBalance
+        } else if has("signature") {
+            Self::InvalidSignature
+        } else if has("rate limit") || has("too many") {
+            Self::RateLimited
+        } else if has("not found") {
+            Self::TaskNotFound
+        } else {
+            Self::Other
+        }
+    }
+}
+
+impl ClientError {
+    /// The classification of an API error. `None` for other errors. HTTP
+    /// errors are classified by their error body, if any
+    pub fn api_error_kind(&self) -> Option<ApiErrorKind> {
+        match self {
+            ClientError::Api { message, .. } => Some(ApiErrorKind::classify(message)),
+            ClientError::TaskNotFound(_) => Some(ApiErrorKind::TaskNotFound),
+            ClientError::TaskStatus(error) => Some(ApiErrorKind::classify(&error.message)),
+            ClientError::Http { status, body } => {
+                match serde_json::from_str::<ApiErrorBody>(body) {
+                    Ok(body) => Some(ApiErrorKind::classify(&body.message)),
+                    Err(_) if *status == StatusCode::TOO_MANY_REQUESTS => {
+                        Some(ApiErrorKind::RateLimited)
+                    }
+                    Err(_) => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// The HTTP status of the response that caused the error, if any
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            ClientError::Http { status, .. } => Some(*status),
+            ClientError::Reqwest(e) => e.status(),
+            _ => None,
+        }
+    }
+}
+
+// error body returned by the API, e.g. `{"message": "..."}`
+#[derive(serde::Deserialize)]
+struct ApiErrorBody {
+    #[serde(default)]
+    code: Option<ApiErrorCode>,
+    message: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum ApiErrorCode {
+    Number(i64),
+    String(String),
+}
+
+impl From<ApiErrorCode> for String {
+    fn from(code: ApiErrorCode) -> Self {
+        match code {
+            ApiErrorCode::Number(code) => code.to_string(),
+            ApiErrorCode::String(code) => code,
+        }
+    }
+}
+
+#[cfg(feature = "providers")]
+impl From<ClientError> for ethers_providers::ProviderError {
+    fn from(e: ClientError) -> Self {
+        match e {
+            ClientError::Provider(e) => e,
+            e => ethers_providers::ProviderError::JsonRpcClientError(Box::new(e)),
+        }
+    }
+}
+
+#[cfg(feature = "middleware-errors")]
+impl ethers_providers::RpcError for ClientError {
+    fn as_error_response(&self) -> Option<&ethers_providers::JsonRpcError> {
+        match self {
+            ClientError::Provider(e) => ethers_providers::RpcError::as_error_response(e),
+            _ => None,
+        }
+    }
+
+    fn as_serde_error(&self) -> Option<&serde_json::Error> {
+        match self {
+            ClientError::SerdeError(e) | ClientError::Deserialize { source: e, .. } => Some(e),
+            ClientError::Provider(e) => ethers_providers::RpcError::as_serde_error(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "middleware-errors")]
+impl ethers_providers::MiddlewareError for ClientError {
+    type Inner = ethers_providers::ProviderError;
+
+    fn from_err(e: Self::Inner) -> Self {
+        ClientError::Provider(e)
+    }
+
+    fn as_inner(&self) -> Option<&Self::Inner> {
+        match self {
+            ClientError::Provider(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Gelato Client Results
+pub type ClientResult<T> = Result<T, ClientError>;
+
+// longest rendering of an offending value in error messages
+const MAX_VALUE_DISPLAY: usize = 120;
+
+fn describe_value(value: &Option<serde_json::Value>) -> String {
+    let value = match value {
+        Some(value) => value.to_string(),
+        None => return String::new(),
+    };
+    match value.char_indices().nth(MAX_VALUE_DISPLAY) {
+        Some((idx, _)) => format!(". Got: {}...", &value[..idx]),
+        None => format!(". Got: {value}"),
+    }
+}
+
+/// Read a response body with [`deserialize_response`]. If the status is not
+/// 2xx, the status and body are returned as [`ClientError::Http`]. API error
+/// bodies are classified by [`ClientError::api_error_kind`]
+pub(crate) fn read_response<T>(status: StatusCode, text: &str) -> ClientResult<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    if !status.is_success() {
+        return Err(ClientError::Http {
+            status,
+            body: text.to_owned(),
+        });
+    }
+    deserialize_response(text)
+}
+
+/// Deserialize a JSON response, reporting the path and value of the
+/// offending field on failure. Responses that don't match `T` but are API
+/// error bodies become [`ClientError::Api`]
+pub(crate) fn deserialize_response<T>(text: &str) -> ClientResult<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let de = &mut serde_json::Deserializer::from_str(text);
+    serde_path_to_error::deserialize(de).map_err(|e| {
+        if let Ok(body) = serde_json::from_str::<ApiErrorBody>(text) {
+            return ClientError::Api {
+                code: body.code.map(Into::into),
+                message: body.message,
+            };
+        }
+
+        let path = e.path().clone();
+        let pointer: String = path
+            .iter()
+            .filter_map(|segment| match segment {
+                serde_path_to_error::Segment::Seq { index } => Some(format!("/{index}")),
+                serde_path_to_error::Segment::Map { key } => {
+                    Some(format!("/{}", key.replace('~', "~0").replace('/', "~1")))
+                }
+                _ => None,
+            })
+            .collect();
+        let value = serde_json::from_str::<serde_json::Value>(text)
+            .ok()
+            .and_then(|v| v.pointer(&pointer).cloned());
+
+        ClientError::Deserialize {
+            path: path.to_string(),
+            value,
+            source: e.into_inner(),
+        }
+    })
+}
+
+/// A random id correlating a request with the tracing span of the client
+/// call and the relay's logs
+pub(crate) fn correlation_id() -> String {
+    format!("{:016x}", rand::random::<u64>())
+}
+
+/// The idempotency key of a request: its EIP-712 digest
+fn idempotency_key<T>(req: &T) -> ClientResult<H256>
+where
+    T: Eip712,
+    T::Error: std::fmt::Display,
+{
+    req.encode_eip712()
+        .map(H256::from)
+        .map_err(|e| ClientError::Other(format!("Could not compute request digest: {e}")))
+}
+
+/// Round a gas limit up to a multiple of the fee gas bucket. Limits that
+/// can't be rounded without overflowing are left as they are
+fn round_up_gas(gas_limit: U64, bucket: u64) -> U64 {
+    let gas_limit = gas_limit.as_u64();
+    let remainder = gas_limit % bucket;
+    if remainder == 0 {
+        return gas_limit.into();
+    }
+    gas_limit
+        .checked_add(bucket - remainder)
+        .unwrap_or(gas_limit)
+        .into()
+}
+
+/// Record the task id of a submission on the current span
+fn record_task(result: ClientResult<rpc::RelayResponse>) -> ClientResult<rpc::RelayResponse> {
+    if let Ok(resp) = &result {
+        trace_record!("task_id", resp.task_id());
+    }
+    result
+}
+
+// Sponsor API key. Redacted from debug output
+#[derive(Clone)]
+struct ApiKey(String);
+
+impl std::fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ApiKey([redacted])")
+    }
+}
+
+/// A Gelato Relay Client
+///
+/// Relay submission endpoints are served from the relay URL, while fee
+/// oracles and task statuses are served from the API URL
+#[derive(Debug, Clone)]
+pub struct GelatoClient {
+    url: reqwest::Url,
+    api_url: reqwest::Url,
+    client: reqwest::Client,
+    api_key: Option<ApiKey>,
+    user_agent: String,
+    retry: Option<RetryPolicy>,
+    metrics: Option<Arc<dyn MetricsObserver>>,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    timer: Arc<dyn Timer>,
+    quote_ttl: Duration,
+    fee_cache: Option<TtlCache<FeeKey, U256>>,
+    fee_gas_bucket: u64,
+    chain_gate: bool,
+    chain_cache: TtlCache<(), Vec<u64>>,
+    chain_urls: HashMap<u64, Url>,
+    submitted: Option<TtlCache<H256, Submission>>,
+}
+
+// a submission under an idempotency key. Locked while in flight, and holds
+// the response once accepted
+type Submission = Arc<AsyncMutex<Option<rpc::RelayResponse>>>;
+
+impl Default for GelatoClient {
+    fn default() -> Self {
+        Self {
+            url: DEFAULT_URL.clone(),
+            api_url: DEFAULT_API_URL.clone(),
+            client: Default::default(),
+            api_key: None,
+            user_agent: SDK_USER_AGENT.to_owned(),
+            retry: None,
+            metrics: None,
+            interceptors: Vec::new(),
+            timer: Arc::new(FuturesTimer),
+            quote_ttl: DEFAULT_QUOTE_TTL,
+            fee_cache: None,
+            fee_gas_bucket: DEFAULT_FEE_GAS_BUCKET,
+            chain_gate: false,
+            chain_cache: TtlCache::new(DEFAULT_CHAIN_CACHE_TTL),
+            chain_urls: HashMap::new(),
+            submitted: None,
+        }
+    }
+}
+
+impl GelatoClient {
+    /// Instantiate a new client with a specific URL, used for both relay and
+    /// API endpoints
+    ///
+    /// # Errors
+    ///
+    /// If the url param cannot be parsed as a URL
+    pub fn new<S>(url: S) -> ClientResult<Self>
+    where
+        S: IntoUrl,
+    {
+        let url = url.into_url()?;
+        Ok(Self {
+            api_url: url.clone(),
+            url,
+            ..Default::default()
+        })
+    }
+
+    /// Instantiate a client using Gelato's production endpoints. Equivalent
+    /// to [`GelatoClient::default`]
+    pub fn production() -> Self {
+        Default::default()
+    }
+
+    /// Instantiate a client using Gelato's staging endpoints, see
+    /// [`GELATO_STAGING_RELAY_URL`] and [`GELATO_STAGING_API_URL`]
+    pub fn staging() -> Self {
+        Self {
+            url: STAGING_URL.clone(),
+            api_url: STAGING_API_URL.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Instantiate a new client with a specific URL and a reqwest Client. The
+    /// URL is used for both relay and API endpoints
+    ///
+    /// # Errors
+    ///
+    /// If the url param cannot be parsed as a URL
+    pub fn new_with_client<S>(url: S, client: reqwest::Client) -> ClientResult<Self>
+    where
+        S: AsRef<str>,
+    {
+        let url: Url = url.as_ref().parse()?;
+        Ok(Self {
+            api_url: url.clone(),
+            url,
+            client,
+            ..Default::default()
+        })
+    }
+
+    /// Set the base URL for relay submission endpoints
+    ///
+    /// # Errors
+    ///
+    /// If the url param cannot be parsed as a URL
+    pub fn with_relay_url<S>(mut self, url: S) -> ClientResult<Self>
+    where
+        S: IntoUrl,
+    {
+        self.url = url.into_url()?;
+        Ok(self)
+    }
+
+    /// Set the base URL for fee oracle and task status endpoints
+    ///
+    /// # Errors
+    ///
+    /// If the url param cannot be parsed as a URL
+    pub fn with_api_url<S>(mut self, url: S) -> ClientResult<Self>
+    where
+        S: IntoUrl,
+    {
+        self.api_url = url.into_url()?;
+        Ok(self)
+    }
+
+    /// The base URL for relay submission endpoints
+    pub fn relay_url(&self) -> &Url {
+        &self.url
+    }
+
+    /// The base URL for fee oracle and task status endpoints
+    pub fn api_url(&self) -> &Url {
+        &self.api_url
+    }
+
+    /// Use a specific base URL for the relay and fee oracle endpoints of one
+    /// chain, e.g. a private relay mirror. Endpoint paths are resolved
+    /// relative to the URL, so any path prefix is kept. Other chains, and
+    /// chain-agnostic endpoints such as task statuses, use the default URLs
+    ///
+    /// # Errors
+    ///
+    /// If the url param cannot be parsed as a URL
+    pub fn with_chain_url<S>(mut self, chain_id: impl IntoChainId, url: S) -> ClientResult<Self>
+    where
+        S: IntoUrl,
+    {
+        let mut url = url.into_url()?;
+        // without a trailing slash, the last path segment would be replaced
+        // when joining endpoint paths
+        if !url.path().ends_with('/') {
+            let path = format!("{}/", url.path());
+            url.set_path(&path);
+        }
+        self.chain_urls.insert(chain_id.into_chain_id(), url);
+        Ok(self)
+    }
+
+    /// The base URL for relay endpoints of a chain
+    pub fn chain_relay_url(&self, chain_id: impl IntoChainId) -> &Url {
+        self.chain_urls
+            .get(&chain_id.into_chain_id())
+            .unwrap_or(&self.url)
+    }
+
+    /// The base URL for fee oracle endpoints of a chain
+    pub fn chain_api_url(&self, chain_id: impl IntoChainId) -> &Url {
+        self.chain_urls
+            .get(&chain_id.into_chain_id())
+            .unwrap_or(&self.api_url)
+    }
+
+    /// Authenticate every request with a sponsor API key, sent in the
+    /// [`API_KEY_HEADER`] header
+    #[must_use]
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(ApiKey(api_key.into()));
+        self
+    }
+
+    /// The sponsor API key, if any
+    pub fn api_key(&self) -> Option<&str> {
+        self.api_key.as_ref().map(|key| key.0.as_str())
+    }
+
+    /// Identify an application in the `User-Agent` header, after the SDK
+    /// identifier, e.g. `gelato-sdk-rs/0.1.0 my-dapp/1.2.0`. This aids
+    /// debugging and rate-limit attribution by Gelato
+    #[must_use]
+    pub fn with_app_id(mut self, app_id: impl AsRef<str>) -> Self {
+        self.user_agent = format!("{SDK_USER_AGENT} {}", app_id.as_ref());
+        self
+    }
+
+    /// The `User-Agent` header sent with every request
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    fn authenticate(&self, req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        let id = correlation_id();
+        trace_record!("correlation_id", &id);
+        let req = req
+            .header(reqwest::header::USER_AGENT, &self.user_agent)
+            .header(CORRELATION_ID_HEADER, id);
+        match &self.api_key {
+            Some(key) => req.header(API_KEY_HEADER, &key.0),
+            None => req,
+        }
+    }
+
+    /// Start a GET request, with the client's user agent, authentication and
+    /// retry policy applied
+    pub(crate) fn get(&self, url: Url) -> GelatoRequest<'_> {
+        let builder = self.authenticate(self.client.get(url.clone()));
+        GelatoRequest::new(self, reqwest::Method::GET, &url, builder)
+    }
+
+    /// Start a POST request, with the client's user agent, authentication
+    /// and retry policy applied
+    pub(crate) fn post(&self, url: Url) -> GelatoRequest<'_> {
+        let builder = self.authenticate(self.client.post(url.clone()));
+        GelatoRequest::new(self, reqwest::Method::POST, &url, builder)
+    }
+
+    /// Retry transient request failures according to `policy`. Disabled by
+    /// default
+    #[must_use]
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Report every request to a metrics observer
+    #[must_use]
+    pub fn with_metrics(mut self, observer: impl MetricsObserver + 'static) -> Self {
+        self.metrics = Some(Arc::new(observer));
+        self
+    }
+
+    /// Pass every request through an interceptor, after those already
+    /// installed
+    #[must_use]
+    pub fn with_interceptor(mut self, interceptor: impl Interceptor + 'static) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
+    /// Set the timer used for delays between retries. Defaults to
+    /// [`FuturesTimer`], which works with any runtime
+    #[must_use]
+    pub fn with_timer(mut self, timer: impl Timer + 'static) -> Self {
+        self.timer = Arc::new(timer);
+        self
+    }
+
+    /// Set the validity of fee quotes returned by `get_fee_quote`. Defaults
+    /// to [`DEFAULT_QUOTE_TTL`]
+    #[must_use]
+    pub fn with_quote_ttl(mut self, ttl: Duration) -> Self {
+        self.quote_ttl = ttl;
+        self
+    }
+
+    /// Send a GET request to an arbitrary endpoint. An escape hatch for
+    /// endpoints without typed support. `path` is resolved against the relay
+    /// URL. Absolute URLs are used as-is
+    ///
+    /// # Errors
+    ///
+    /// If the path cannot be joined to the relay URL, the request fails, or
+    /// the response is not JSON
+    pub async fn raw_get(&self, path: &str) -> ClientResult<serde_json::Value> {
+        self.get_json(path, &()).await
+    }
+
+    /// Send a POST request with a JSON body to an arbitrary endpoint. An
+    /// escape hatch for endpoints without typed support. `path` is resolved
+    /// against the relay URL. Absolute URLs are used as-is
+    ///
+    /// # Errors
+    ///
+    /// If the path cannot be joined to the relay URL, the request fails, or
+    /// the response is not JSON
+    pub async fn raw_post<B>(&self, path: &str, body: &B) -> ClientResult<serde_json::Value>
+    where
+        B: serde::Serialize + ?Sized,
+    {
+        self.post_json(path, body).await
+    }
+
+    /// Send a GET request to an arbitrary endpoint, with `query` encoded as
+    /// the query string, and deserialize the JSON response. Like
+    /// [`Self::raw_get`], but typed. The client's authentication, retry
+    /// policy and logging apply
+    ///
+    /// # Errors
+    ///
+    /// If the path cannot be joined to the relay URL, the query cannot be
+    /// encoded, the request fails, or the response does not deserialize
+    pub async fn get_json<T, Q>(&self, path: &str, query: &Q) -> ClientResult<T>
+    where
+        T: serde::de::DeserializeOwned,
+        Q: serde::Serialize + ?Sized,
+    {
+        let mut url = self.url.join(path)?;
+        let query = serde_urlencoded::to_string(query)?;
+        if !query.is_empty() {
+            url.set_query(Some(&query));
+        }
+        json_get!(self, url, T)
+    }
+
+    /// Send a POST request with a JSON body to an arbitrary endpoint, and
+    /// deserialize the JSON response. Like [`Self::raw_post`], but typed. The
+    /// client's authentication, retry policy and logging apply
+    ///
+    /// # Errors
+    ///
+    /// If the path cannot be joined to the relay URL, the request fails, or
+    /// the response does not deserialize
+    pub async fn post_json<T, B>(&self, path: &str, body: &B) -> ClientResult<T>
+    where
+        T: serde::de::DeserializeOwned,
+        B: serde::Serialize + ?Sized,
+    {
+        json_post!(self, self.url.join(path)?, body)
+    }
+
+    /// Memoize fee estimates for `ttl`. Estimates are cached per chain,
+    /// payment token, gas bucket and priority, with cached estimates
+    /// requested for the gas limit rounded up to the bucket. Disabled by
+    /// default
+    #[must_use]
+    pub fn with_fee_cache(mut self, ttl: Duration) -> Self {
+        self.fee_cache = Some(TtlCache::new(ttl));
+        self
+    }
+
+    /// Set the gas granularity of the fee estimate cache. Defaults to
+    /// [`DEFAULT_FEE_GAS_BUCKET`]
+    #[must_use]
+    pub fn with_fee_gas_bucket(mut self, bucket: u64) -> Self {
+        self.fee_gas_bucket = bucket.max(1);
+        self
+    }
+
+    /// Before any submission, check the chain against the cached list of
+    /// supported chains, and reject unsupported chains with
+    /// [`ClientError::UnsupportedChain`]. Disabled by default
+    #[must_use]
+    pub fn with_chain_gate(mut self) -> Self {
+        self.chain_gate = true;
+        self
+    }
+
+    /// Set how long the supported-chain list is cached. Defaults to
+    /// [`DEFAULT_CHAIN_CACHE_TTL`]. A zero TTL disables caching
+    #[must_use]
+    pub fn with_chain_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.chain_cache = TtlCache::new(ttl);
+        self
+    }
+
+    /// Remember forward request and meta tx submissions under their
+    /// idempotency key for `ttl`. Resubmitting a remembered request returns
+    /// the original response without contacting the relay, and a duplicate
+    /// sent while the original is in flight waits for its outcome. If the
+    /// original fails, the duplicate is sent.
+    ///
+    /// Disabled by default, so that deliberate resubmissions of identical
+    /// requests reach the relay. A zero TTL disables deduplication
+    #[must_use]
+    pub fn with_idempotency_ttl(mut self, ttl: Duration) -> Self {
+        self.submitted = (!ttl.is_zero()).then(|| TtlCache::new(ttl));
+        self
+    }
+
+    /// Drop the cached supported-chain list, so the next lookup fetches it
+    /// from the relay. Affects all clones of this client
+    pub fn invalidate_chain_cache(&self) {
+        self.chain_cache.clear();
+    }
+
+    async fn check_chain(&self, chain_id: u64) -> ClientResult<()> {
+        if self.chain_gate && !self.get_gelato_relay_chains().await?.contains(&chain_id) {
+            return Err(ClientError::UnsupportedChain(chain_id));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn send_relay_transaction_url(&self, chain_id: u64) -> ClientResult<Url> {
+        Ok(self
+            .chain_relay_url(chain_id)
+            .join(&format!("relays/{chain_id}"))?)
+    }
+
+    /// Send a transaction over the relay
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "relays/{chain_id}",
+                chain_id = tracing::field::Empty,
+                correlation_id = tracing::field::Empty,
+                task_id = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn send_relay_transaction(
+        &self,
+        params: &rpc::RelayRequest,
+        chain_id: impl IntoChainId,
+    ) -> ClientResult<rpc::RelayResponse> {
+        let chain_id = chain_id.into_chain_id();
+        trace_record!("chain_id", chain_id);
+        self.check_chain(chain_id).await?;
+        record_task(json_post!(
+            self,
+            self.send_relay_transaction_url(chain_id)?,
+            params,
+        ))
+    }
+
+    pub(crate) fn relay_v2_url(&self, chain_id: u64, endpoint: &str) -> ClientResult<Url> {
+        Ok(self
+            .chain_relay_url(chain_id)
+            .join(&format!("relays/v2/{endpoint}"))?)
+    }
+
+    // POST a relay v2 request authenticated with the sponsor API key
+    async fn send_sponsored<T>(
+        &self,
+        chain_id: u64,
+        endpoint: &str,
+        request: &T,
+    ) -> ClientResult<rpc::RelayResponse>
+    where
+        T: serde::Serialize,
+    {
+        let sponsor_api_key = self.api_key().ok_or(ClientError::MissingApiKey)?;
+        let body = rpc::Sponsored {
+            request,
+            sponsor_api_key,
+        };
+        json_post!(self, self.relay_v2_url(chain_id, endpoint)?, &body)
+    }
+
+    /// Send a relay v2 sponsored call, paid from the 1Balance of the sponsor
+    /// owning the client's API key
+    ///
+    /// # Errors
+    ///
+    /// [`ClientError::MissingApiKey`] if the client has no API key
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "relays/v2/sponsored-call",
+                chain_id = req.chain_id,
+                correlation_id = tracing::field::Empty,
+                task_id = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn sponsored_call(
+        &self,
+        req: &rpc::SponsoredCallRequest,
+    ) -> ClientResult<rpc::RelayResponse> {
+        self.check_chain(req.chain_id).await?;
+        record_task(
+            self.send_sponsored(req.chain_id, "sponsored-call", req)
+                .await,
+        )
+    }
+
+    /// Send a signed relay v2 sponsored ERC-2771 call, paid from the
+    /// 1Balance of the sponsor owning the client's API key
+    ///
+    /// # Errors
+    ///
+    /// [`ClientError::MissingApiKey`] if the client has no API key
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "relays/v2/sponsored-call-erc2771",
+                chain_id = req.chain_id,
+                correlation_id = tracing::field::Empty,
+                task_id = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn sponsored_call_erc2771(
+        &self,
+        req: &rpc::SignedSponsoredCallErc2771,
+    ) -> ClientResult<rpc::RelayResponse> {
+        self.check_chain(req.chain_id).await?;
+        record_task(
+            self.send_sponsored(req.chain_id, "sponsored-call-erc2771", req)
+                .await,
+        )
+    }
+
+    /// Send a signed relay v2 sponsored ERC-2771 call using concurrent replay
+    /// protection, paid from the 1Balance of the sponsor owning the client's
+    /// API key
+    ///
+    /// # Errors
+    ///
+    /// [`ClientError::MissingApiKey`] if the client has no API key
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "relays/v2/sponsored-call-erc2771",
+                chain_id = req.chain_id,
+                correlation_id = tracing::field::Empty,
+                task_id = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn sponsored_call_concurrent_erc2771(
+        &self,
+        req: &rpc::SignedSponsoredCallConcurrentErc2771,
+    ) -> ClientResult<rpc::RelayResponse> {
+        self.check_chain(req.chain_id).await?;
+        let request = rpc::Concurrent {
+            request: req,
+            is_concurrent: true,
+        };
+        record_task(
+            self.send_sponsored(req.chain_id, "sponsored-call-erc2771", &request)
+                .await,
+        )
+    }
+
+    /// Send a relay v2 call with sync fee. The target pays the fee during
+    /// execution, so no API key is required
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "relays/v2/call-with-sync-fee",
+                chain_id = req.chain_id,
+                correlation_id = tracing::field::Empty,
+                task_id = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn call_with_sync_fee(
+        &self,
+        req: &rpc::CallWithSyncFeeRequest,
+    ) -> ClientResult<rpc::RelayResponse> {
+        self.check_chain(req.chain_id).await?;
+        record_task(json_post!(
+            self,
+            self.relay_v2_url(req.chain_id, "call-with-sync-fee")?,
+            req
+        ))
+    }
+
+    /// Send a signed relay v2 ERC-2771 call with sync fee. The target pays
+    /// the fee during execution, so no API key is required
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "relays/v2/call-with-sync-fee-erc2771",
+                chain_id = req.chain_id,
+                correlation_id = tracing::field::Empty,
+                task_id = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn call_with_sync_fee_erc2771(
+        &self,
+        req: &rpc::SignedCallWithSyncFeeErc2771,
+    ) -> ClientResult<rpc::RelayResponse> {
+        self.check_chain(req.chain_id).await?;
+        record_task(json_post!(
+            self,
+            self.relay_v2_url(req.chain_id, "call-with-sync-fee-erc2771")?,
+            req
+        ))
+    }
+
+    /// Send a signed relay v2 ERC-2771 call with sync fee using concurrent
+    /// replay protection. No API key is required
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "relays/v2/call-with-sync-fee-erc2771",
+                chain_id = req.chain_id,
+                correlation_id = tracing::field::Empty,
+                task_id = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn call_with_sync_fee_concurrent_erc2771(
+        &self,
+        req: &rpc::SignedCallWithSyncFeeConcurrentErc2771,
+    ) -> ClientResult<rpc::RelayResponse> {
+        self.check_chain(req.chain_id).await?;
+        let request = rpc::Concurrent {
+            request: req,
+            is_concurrent: true,
+        };
+        record_task(json_post!(
+            self,
+            self.relay_v2_url(req.chain_id, "call-with-sync-fee-erc2771")?,
+            &request
+        ))
+    }
+
+    pub(crate) fn send_forward_request_url(&self, chain_id: u64) -> Url {
+        self.chain_relay_url(chain_id)
+            .join("metabox-relays/")
+            .unwrap()
+            .join(&format!("{chain_id}"))
+            .unwrap()
+    }
+
+    /// Send a transaction forward call
+    ///
+    /// <https://docs.gelato.network/developer-products/gelato-relay-sdk/request-types#forwardcall>
+    ///
+    /// `ForwardCall` is designed to handle payments of type `Synchronous`, as
+    /// it requires no signatures.
+    ///
+    /// Because payment is of type `Synchronous`, the target contract MUST
+    /// pay for its gas in `params.fee_token` during call forwarding.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "metabox-relays/{chain_id}",
+                chain_id = params.chain_id,
+                correlation_id = tracing::field::Empty,
+                task_id = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn send_forward_call(
+        &self,
+        params: &rpc::ForwardCall,
+    ) -> ClientResult<rpc::RelayResponse> {
+        self.check_chain(params.chain_id).await?;
+        record_task(json_post!(
+            self,
+            self.send_forward_request_url(params.chain_id),
+            params
+        ))
+    }
+
+    /// Send a transaction forward request
+    ///
+    /// <https://docs.gelato.network/developer-products/gelato-relay-sdk/request-types#forwardrequest>
+    ///
+    /// ForwardRequest is designed to handle payments of type 1, 2 and 3, in
+    /// cases where all meta-transaction related logic (or other kinds of
+    /// replay protection mechanisms such as hash based commitments) is already
+    /// implemented inside target smart contract. The sponsor is still required
+    /// to EIP-712 sign this request, in order to ensure the integrity of
+    /// payments. Optionally, nonce may or may not be enforced, by setting
+    /// enforceSponsorNonce. Some dApps may not need to rely on a nonce for
+    /// ForwardRequest if they already implement strong forms of replay
+    /// protection.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "metabox-relays/{chain_id}",
+                chain_id = params.chain_id,
+                correlation_id = tracing::field::Empty,
+                task_id = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn send_forward_request(
+        &self,
+        params: &rpc::SignedForwardRequest,
+    ) -> ClientResult<rpc::RelayResponse> {
+        self.check_chain(params.chain_id).await?;
+        let key = idempotency_key(&**params)?;
+        record_task(
+            self.send_idempotent(self.send_forward_request_url(params.chain_id), key, params)
+                .await,
+        )
+    }
+
+    /// Gelato relay MetaTxRequest
+    ///
+    /// <https://docs.gelato.network/developer-products/gelato-relay-sdk/request-types#metatxrequest>
+    ///
+    /// MetaTxRequest is designed to handle payments of type AsyncGasTank,
+    /// SyncGasTank and SyncPullFee, in cases where the target contract does not
+    /// have any meta-transaction nor replay protection logic. In this case, the
+    /// appropriate Gelato Relay's smart contract already verifies user and sponsor
+    /// signatures. user is the EOA address that wants to interact with the dApp,
+    /// while sponsor is the account that pays fees.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "metabox-relays/{chain_id}",
+                chain_id = params.chain_id,
+                correlation_id = tracing::field::Empty,
+                task_id = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn send_meta_tx_request(
+        &self,
+        params: &rpc::SignedMetaTxRequest,
+    ) -> ClientResult<rpc::RelayResponse> {
+        self.check_chain(params.chain_id).await?;
+        let key = idempotency_key(&**params)?;
+        record_task(
+            self.send_idempotent(self.send_forward_request_url(params.chain_id), key, params)
+                .await,
+        )
+    }
+
+    // POST a submission with an idempotency key. If deduplication is
+    // enabled, the key is reserved before sending, so duplicates wait for the
+    // in-flight submission, and are answered with its response if accepted
+    async fn send_idempotent<T>(
+        &self,
+        url: Url,
+        key: H256,
+        params: &T,
+    ) -> ClientResult<rpc::RelayResponse>
+    where
+        T: serde::Serialize,
+    {
+        let submission = match &self.submitted {
+            Some(submitted) => submitted.get_or_insert_with(key, Default::default),
+            None => {
+                return json_post!(
+                    self,
+                    url,
+                    params,
+                    header = (IDEMPOTENCY_KEY_HEADER, format!("{key:?}"))
+                )
+            }
+        };
+
+        // held until the submission completes. on failure, the next waiter
+        // sends instead
+        let mut accepted = submission.lock().await;
+        if let Some(resp) = accepted.as_ref() {
+            trace_warn!(idempotency_key = ?key, "Skipping duplicate submission");
+            return Ok(resp.clone());
+        }
+        let resp: rpc::RelayResponse = json_post!(
+            self,
+            url,
+            params,
+            header = (IDEMPOTENCY_KEY_HEADER, format!("{key:?}"))
+        )?;
+        *accepted = Some(resp.clone());
+        Ok(resp)
+    }
+
+    /// Send a signed forward request or meta tx request
+    pub async fn send_signed_request(
+        &self,
+        params: &SignedRequest,
+    ) -> ClientResult<rpc::RelayResponse> {
+        match params {
+            SignedRequest::ForwardRequest(req) => self.send_forward_request(req).await,
+            SignedRequest::MetaTxRequest(req) => self.send_meta_tx_request(req).await,
+        }
+    }
+
+    /// Submit a batch of signed requests concurrently, with at most
+    /// `max_concurrent` requests in flight. Results are returned in the order
+    /// of `requests`, and one failed submission does not affect the others.
+    /// See [`DEFAULT_BATCH_CONCURRENCY`]
+    pub async fn send_many<'a, I>(
+        &self,
+        requests: I,
+        max_concurrent: usize,
+    ) -> Vec<ClientResult<rpc::RelayResponse>>
+    where
+        I: IntoIterator<Item = &'a SignedRequest>,
+    {
+        stream::iter(requests)
+            .map(|req| self.send_signed_request(req))
+            .buffered(max_concurrent.max(1))
+            .collect()
+            .await
+    }
+
+    /// Validate, verify the signatures of, and submit a signed request
+    /// previously exported as JSON
+    pub async fn submit_json(&self, json: serde_json::Value) -> ClientResult<rpc::RelayResponse> {
+        let request = offline::from_json(json)?;
+        self.send_signed_request(&request).await
+    }
+
+    /// Validate, verify the signatures of, and submit a signed request
+    /// previously exported to a JSON file
+    pub async fn submit_file(&self, path: impl AsRef<Path>) -> ClientResult<rpc::RelayResponse> {
+        let request = offline::read_json_file(path)?;
+        self.send_signed_request(&request).await
+    }
+
+    /// Check if a chain id is supported by Gelato API. Served from the
+    /// supported-chain cache
+    pub async fn is_chain_supported(&self, chain_id: impl IntoChainId) -> ClientResult<bool> {
+        let chain_id = chain_id.into_chain_id();
+        Ok(self.get_gelato_relay_chains().await?.contains(&chain_id))
+    }
+
+    pub(crate) fn relay_chains_url(&self) -> reqwest::Url {
+        self.url.join("relays/").unwrap()
+    }
+
+    /// Get a list of supported chains. Served from the supported-chain
+    /// cache, see [`Self::with_chain_cache_ttl`]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "relays/",
+                correlation_id = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn get_gelato_relay_chains(&self) -> ClientResult<Vec<u64>> {
+        if let Some(chains) = self.chain_cache.get(&()) {
+            return Ok(chains);
+        }
+        let chains = json_get!(self, self.relay_chains_url(), rpc::RelayChainsResponse)?.relays();
+        self.chain_cache.insert((), chains.clone());
+        Ok(chains)
+    }
+
+    /// Compare the local clock to the relay's clock, as reported by the HTTP
+    /// `Date` header of a cheap request. Skewed local clocks produce
+    /// deadlines that are already expired when the relay receives them
+    ///
+    /// # Errors
+    ///
+    /// If the request fails, or the response has no valid `Date` header
+    pub async fn check_clock_skew(&self) -> ClientResult<ClockSkew> {
+        let resp = self.get(self.relay_chains_url()).send().await?;
+        let local = crate::time::now();
+
+        resp.headers()
+            .get(reqwest::header::DATE)
+            .and_then(|date| date.to_str().ok())
+            .and_then(|date| ClockSkew::from_date_header(local, date))
+            .ok_or(ClientError::NoServerDate)
+    }
+
+    pub(crate) fn oracle_chains_url(&self) -> reqwest::Url {
+        self.api_url.join("oracles/").unwrap()
+    }
+
+    /// Get a list of chains with an active fee oracle
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "oracles/",
+                correlation_id = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn get_oracle_chains(&self) -> ClientResult<Vec<u64>> {
+        Ok(json_get!(self, self.oracle_chains_url(), rpc::OracleChainsResponse)?.oracles())
+    }
+
+    /// Get the chains supported by the relay, with whether each has a fee
+    /// oracle, and the contracts known to the SDK. Combines the relay and
+    /// oracle chain lists
+    pub async fn get_supported_networks(&self) -> ClientResult<Vec<NetworkInfo>> {
+        let (relay_chains, oracle_chains) = futures_util::future::try_join(
+            self.get_gelato_relay_chains(),
+            self.get_oracle_chains(),
+        )
+        .await?;
+        Ok(NetworkInfo::combine(&relay_chains, &oracle_chains))
+    }
+
+    pub(crate) fn payment_tokens_url(&self, chain_id: u64) -> ClientResult<Url> {
+        Ok(self
+            .chain_api_url(chain_id)
+            .join(&format!("oracles/{chain_id}/paymentTokens"))?)
+    }
+
+    /// Get the payment tokens accepted by the fee oracle on a chain. Fee
+    /// tokens outside this list are rejected by the relay
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "oracles/{chain_id}/paymentTokens",
+                chain_id = tracing::field::Empty,
+                correlation_id = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn get_payment_tokens(
+        &self,
+        chain_id: impl IntoChainId,
+    ) -> ClientResult<Vec<FeeToken>> {
+        let chain_id = chain_id.into_chain_id();
+        trace_record!("chain_id", chain_id);
+        let url = self.payment_tokens_url(chain_id)?;
+        Ok(json_get!(self, url, rpc::PaymentTokensResponse)?.payment_tokens())
+    }
+
+    /// True if the fee oracle accepts `token` as payment on a chain
+    pub async fn is_payment_token(
+        &self,
+        chain_id: impl IntoChainId,
+        token: impl Into<FeeToken>,
+    ) -> ClientResult<bool> {
+        let token = token.into();
+        Ok(self.get_payment_tokens(chain_id).await?.contains(&token))
+    }
+
+    pub(crate) fn estimated_fee_url(
+        &self,
+        chain_id: u64,
+        query: &rpc::EstimatedFeeRequest,
+    ) -> ClientResult<Url> {
+        let mut url = self
+            .chain_api_url(chain_id)
+            .join(&format!("oracles/{chain_id}/estimate"))?;
+        url.set_query(Some(&serde_urlencoded::to_string(query)?));
+        Ok(url)
+    }
+
+    /// Get the estimated fee for a specific amount of gas on a specific chain,
+    /// denominated in a specific payment token. Served from the fee cache,
+    /// if enabled
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "oracles/{chain_id}/estimate",
+                chain_id = tracing::field::Empty,
+                correlation_id = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn get_estimated_fee(
+        &self,
+        chain_id: impl IntoChainId,
+        payment_token: impl Into<FeeToken>,
+        gas_limit: U64,
+        is_high_priority: bool,
+    ) -> ClientResult<U256> {
+        let chain_id = chain_id.into_chain_id();
+        trace_record!("chain_id", chain_id);
+        let payment_token = payment_token.into();
+
+        let cache = match &self.fee_cache {
+            Some(cache) => cache,
+            None => {
+                return self
+                    .fetch_estimated_fee(chain_id, payment_token, gas_limit, is_high_priority)
+                    .await
+            }
+        };
+
+        let gas_limit = round_up_gas(gas_limit, self.fee_gas_bucket);
+        let key = (chain_id, payment_token, gas_limit, is_high_priority);
+        if let Some(fee) = cache.get(&key) {
+            return Ok(fee);
+        }
+
+        let fee = self
+            .fetch_estimated_fee(chain_id, payment_token, gas_limit, is_high_priority)
+            .await?;
+        cache.insert(key, fee);
+        Ok(fee)
+    }
+
+    /// Get the estimated fee for a specific amount of gas on a specific chain,
+    /// denominated in the chain's native token
+    pub async fn get_estimated_native_fee(
+        &self,
+        chain_id: impl IntoChainId,
+        gas_limit: U64,
+        is_high_priority: bool,
+    ) -> ClientResult<U256> {
+        self.get_estimated_fee(chain_id, FeeToken::default(), gas_limit, is_high_priority)
+            .await
+    }
+
+    async fn fetch_estimated_fee(
+        &self,
+        chain_id: u64,
+        payment_token: FeeToken,
+        gas_limit: U64,
+        is_high_priority: bool,
+    ) -> ClientResult<U256> {
+        let query = rpc::EstimatedFeeRequest {
+            payment_token,
+            gas_limit,
+            is_high_priority,
+        };
+        Ok(json_get!(
+            self,
+            self.estimated_fee_url(chain_id, &query)?,
+            rpc::EstimatedFeeResponse
+        )?
+        .estimated_fee())
+    }
+
+    /// Get a fee quote for a specific amount of gas on a specific chain,
+    /// denominated in a specific payment token. The quote expires after the
+    /// client's quote TTL
+    pub async fn get_fee_quote(
+        &self,
+        chain_id: impl IntoChainId,
+        payment_token: impl Into<FeeToken>,
+        gas_limit: U64,
+        is_high_priority: bool,
+    ) -> ClientResult<rpc::FeeQuote> {
+        let chain_id = chain_id.into_chain_id();
+        let payment_token = payment_token.into();
+        let fee = self
+            .get_estimated_fee(chain_id, payment_token, gas_limit, is_high_priority)
+            .await?;
+        Ok(rpc::FeeQuote {
+            chain_id,
+            payment_token,
+            gas_limit,
+            is_high_priority,
+            fee,
+            expires_at: crate::time::now() + self.quote_ttl,
+        })
+    }
+
+    fn check_quote(
+        quote: &rpc::FeeQuote,
+        chain_id: u64,
+        fee_token: FeeToken,
+        max_fee: U256,
+    ) -> ClientResult<()> {
+        if quote.is_expired() {
+            return Err(ClientError::FeeQuoteExpired);
+        }
+        if quote.chain_id != chain_id || quote.payment_token != fee_token || quote.fee != max_fee {
+            trace_warn!(
+                quoted_fee = %quote.fee,
+                max_fee = %max_fee,
+                "Submitting request that does not match its fee quote"
+            );
+        }
+        Ok(())
+    }
+
+    /// Send a transaction forward request priced off a fee quote. Refuses to
+    /// submit if the quote has expired
+    pub async fn send_forward_request_with_quote(
+        &self,
+        params: &rpc::SignedForwardRequest,
+        quote: &rpc::FeeQuote,
+    ) -> ClientResult<rpc::RelayResponse> {
+        Self::check_quote(quote, params.chain_id, params.fee_token, params.max_fee)?;
+        self.send_forward_request(params).await
+    }
+
+    /// Send a meta tx request priced off a fee quote. Refuses to submit if
+    /// the quote has expired
+    pub async fn send_meta_tx_request_with_quote(
+        &self,
+        params: &rpc::SignedMetaTxRequest,
+        quote: &rpc::FeeQuote,
+    ) -> ClientResult<rpc::RelayResponse> {
+        Self::check_quote(quote, params.chain_id, params.fee_token, params.max_fee)?;
+        self.send_meta_tx_request(params).await
+    }
+
+    pub(crate) fn get_task_status_url(&self, task_id: TaskId) -> Url {
+        self.api_url
+            .join("tasks/GelatoMetaBox/")
+            .unwrap()
+            .join(&format!("{task_id}/"))
+            .unwrap()
+    }
+
+    pub(crate) fn get_task_status_v2_url(&self, task_id: TaskId) -> Url {
+        self.url
+            .join("tasks/status/")
+            .unwrap()
+            .join(&format!("{task_id}"))
+            .unwrap()
+    }
+
+    /// Fetch all statuses the API reports for a task
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "tasks/GelatoMetaBox/{task_id}",
+                correlation_id = tracing::field::Empty,
+                task_id = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn get_task_statuses(
+        &self,
+        task_id: impl Into<TaskId>,
+    ) -> ClientResult<Vec<rpc::TransactionStatus>> {
+        let task_id = task_id.into();
+        trace_record!("task_id", task_id);
+        json_get!(
+            self,
+            self.get_task_status_url(task_id),
+            rpc::TaskStatusResponse,
+        )?
+        .into_statuses()
+    }
+
+    /// Fetch the status of a task. The first of the statuses reported by
+    /// the API
+    ///
+    /// # Errors
+    ///
+    /// [`ClientError::TaskNotFound`] if the API reports no status
+    pub async fn get_task_status(
+        &self,
+        task_id: impl Into<TaskId>,
+    ) -> ClientResult<rpc::TransactionStatus> {
+        let task_id = task_id.into();
+        self.get_task_statuses(task_id)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(ClientError::TaskNotFound(task_id))
+    }
+
+    /// Fetch the status of a task from the v2 `tasks/status/{taskId}`
+    /// endpoint, served from the relay URL
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                endpoint = "tasks/status/{task_id}",
+                correlation_id = tracing::field::Empty,
+                task_id = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn get_task_status_v2(
+        &self,
+        task_id: impl Into<TaskId>,
+    ) -> ClientResult<rpc::TaskStatusV2> {
+        let task_id = task_id.into();
+        trace_record!("task_id", task_id);
+        json_get!(
+            self,
+            self.get_task_status_v2_url(task_id),
+            rpc::TaskStatusV2Response,
+        )
+        .map(|resp| resp.task)
+    }
+
+    /// Create a future that will track the status of a task
+    pub fn track_task<P>(&self, task_id: impl Into<TaskId>, payload: P) -> GelatoTask<P> {
+        GelatoTask::new(task_id.into(), self, payload)
+    }
+
+    /// Dispatch a forward request. Get a future tracking its status
+    pub async fn forward_request(
+        &self,
+        params: &rpc::SignedForwardRequest,
+    ) -> ClientResult<GelatoTask<'_, rpc::SignedForwardRequest>> {
+        let resp = self.send_forward_request(params).await?;
+        Ok(self.track_task(resp.task_id(), params.clone()))
+    }
+
+    /// Dispatch a meta tx request. Get a future tracking its status
+    pub async fn meta_tx_request(
+        &self,
+
+        params: &rpc::SignedMetaTxRequest,
+    ) -> ClientResult<GelatoTask<'_, rpc::SignedMetaTxRequest>> {
+        let resp = self.send_meta_tx_request(params).await?;
+        Ok(self.track_task(resp.task_id(), params.clone()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_core::types::Signature;
+
+    use super::*;
+    use crate::PaymentType;
+
+    #[tokio::test]
+    async fn it_deduplicates_submissions() {
+        let req = rpc::ForwardRequest {
+            chain_id: 42,
+            target: Default::default(),
+            data: Default::default(),
+            fee_token: Default::default(),
+            payment_type: PaymentType::AsyncGasTank,
+            max_fee: 1000u64.into(),
+            gas: 200000u64.into(),
+            sponsor: Default::default(),
+            sponsor_chain_id: 42,
+            nonce: 0,
+            enforce_sponsor_nonce: false,
+            enforce_sponsor_nonce_ordering: false,
+        };
+        let fake_sig = Signature::try_from((0..65u8).collect::<Vec<_>>().as_ref()).unwrap();
+        let signed = req.add_signature(fake_sig);
+        let key = idempotency_key(&*signed).unwrap();
+        assert_eq!(key, H256::from(signed.encode_eip712().unwrap()));
+
+        // nothing listens on port 1, so only remembered submissions succeed
+        let client = GelatoClient::new("http://127.0.0.1:1/").unwrap();
+        assert!(client.submitted.is_none());
+        let client = client.with_idempotency_ttl(Duration::from_secs(60));
+        assert!(client.send_forward_request(&signed).await.is_err());
+
+        // the failed submission is not remembered
+        let submitted = client.submitted.as_ref().unwrap();
+        let submission = submitted.get(&key).unwrap();
+        assert!(submission.lock().await.is_none());
+
+        let task_id = TaskId::from(H256::repeat_byte(1));
+        *submission.lock().await = Some(task_id.into());
+        let resp = client.send_forward_request(&signed).await.unwrap();
+        assert_eq!(resp.task_id(), task_id);
+    }
+
+    #[test]
+    fn it_reads_error_responses() {
+        let err = read_response::<serde_json::Value>(
+            StatusCode::BAD_REQUEST,
+            r#"{"code": 400, "message": "Chain not supported"}"#,
+        )
+        .unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+        assert_eq!(err.api_error_kind(), Some(ApiErrorKind::UnsupportedChain));
+        assert!(matches!(
+            err,
+            ClientError::Http { status: StatusCode::BAD_REQUEST, ref body }
+                if body.contains("Chain not supported")
+        ));
+
+        let err = read_response::<serde_json::Value>(StatusCode::BAD_GATEWAY, "<html>oops</html>")
+            .unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::BAD_GATEWAY));
+        assert!(matches!(
+            err,
+            ClientError::Http { status: StatusCode::BAD_GATEWAY, ref body } if body == "<html>oops</html>"
+        ));
+    }
+
+    #[test]
+    fn it_rounds_gas_up_to_the_bucket() {
+        assert_eq!(round_up_gas(1.into(), 1000), 1000.into());
+        assert_eq!(round_up_gas(1000.into(), 1000), 1000.into());
+        assert_eq!(round_up_gas(1001.into(), 1000), 2000.into());
+        assert_eq!(round_up_gas(u64::MAX.into(), 1000), u64::MAX.into());
+        assert_eq!(round_up_gas(u64::MAX.into(), 1), u64::MAX.into());
+    }
+}
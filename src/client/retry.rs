@@ -0,0 +1,211 @@
+use std::time::Duration;
+
+use reqwest::{Method, StatusCode, Url};
+
+use super::{GelatoClient, RequestInfo, RequestOutcome};
+use crate::time::Instant;
+
+/// Retry policy for transient request failures: connection errors,
+/// timeouts, and HTTP 429, 502, 503 and 504 responses.
+///
+/// Delays grow exponentially from `initial_backoff` up to `max_backoff`. A
+/// `Retry-After` header in seconds overrides the computed delay, still capped
+/// at `max_backoff`.
+///
+/// Note that a submission whose response was lost may be retried after the
+/// relay accepted it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first
+    pub max_attempts: usize,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound on the delay between attempts
+    pub max_backoff: Duration,
+    /// Factor applied to the delay after each retry
+    pub multiplier: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retrying after `attempt` failed attempts
+    pub fn backoff(&self, attempt: usize) -> Duration {
+        let exponent = u32::try_from(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.multiplier
+            .checked_pow(exponent)
+            .and_then(|factor| self.initial_backoff.checked_mul(factor))
+            .map_or(self.max_backoff, |delay| delay.min(self.max_backoff))
+    }
+}
+
+fn is_transient_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+fn is_transient_error(e: &reqwest::Error) -> bool {
+    e.is_connect() || e.is_timeout()
+}
+
+fn retry_after(resp: &reqwest::Response) -> Option<Duration> {
+    resp.headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Send one attempt of a request, passing it through the client's
+/// interceptors
+async fn execute(
+    client: &GelatoClient,
+    builder: reqwest::RequestBuilder,
+) -> reqwest::Result<reqwest::Response> {
+    if client.interceptors.is_empty() {
+        return builder.send().await;
+    }
+
+    let mut request = builder.build()?;
+    for interceptor in client.interceptors.iter() {
+        interceptor.on_request(&mut request);
+    }
+    let result = client.client.execute(request).await;
+    for interceptor in client.interceptors.iter().rev() {
+        match &result {
+            Ok(resp) => interceptor.on_response(resp),
+            Err(e) => interceptor.on_error(e),
+        }
+    }
+    result
+}
+
+/// A request sent through a [`GelatoClient`], retried according to its
+/// retry policy and reported to its metrics observer
+#[derive(Debug)]
+pub(crate) struct GelatoRequest<'a> {
+    client: &'a GelatoClient,
+    builder: reqwest::RequestBuilder,
+    // only computed when a metrics observer is installed
+    info: Option<RequestInfo>,
+}
+
+impl<'a> GelatoRequest<'a> {
+    pub(crate) fn new(
+        client: &'a GelatoClient,
+        method: Method,
+        url: &Url,
+        builder: reqwest::RequestBuilder,
+    ) -> Self {
+        let info = client
+            .metrics
+            .as_ref()
+            .map(|_| RequestInfo::new(method, url));
+        Self {
+            client,
+            builder,
+            info,
+        }
+    }
+
+    /// Add a header
+    pub(crate) fn header(self, name: &'static str, value: impl AsRef<str>) -> Self {
+        Self {
+            builder: self.builder.header(name, value.as_ref()),
+            ..self
+        }
+    }
+
+    /// Set a JSON body
+    pub(crate) fn json<T: serde::Serialize + ?Sized>(self, json: &T) -> Self {
+        Self {
+            builder: self.builder.json(json),
+            ..self
+        }
+    }
+
+    /// Send the request, retrying transient failures
+    pub(crate) async fn send(mut self) -> reqwest::Result<reqwest::Response> {
+        let client = self.client;
+        let (observer, info) = match (&client.metrics, self.info.take()) {
+            (Some(observer), Some(info)) => (observer, info),
+            _ => return self.send_with_retries().await.0,
+        };
+
+        observer.request_started(&info);
+        let started_at = Instant::now();
+        let (result, attempts) = self.send_with_retries().await;
+        let outcome = RequestOutcome {
+            latency: started_at.elapsed(),
+            status: result.as_ref().ok().map(|resp| resp.status()),
+            attempts,
+        };
+        observer.request_finished(&info, &outcome);
+        result
+    }
+
+    // the result, and the number of attempts made
+    async fn send_with_retries(self) -> (reqwest::Result<reqwest::Response>, usize) {
+        let policy = match self.client.retry {
+            Some(policy) => policy,
+            None => return (execute(self.client, self.builder).await, 1),
+        };
+
+        let mut attempt = 1;
+        loop {
+            // streaming bodies can't be cloned, and so can't be retried
+            let builder = match self.builder.try_clone() {
+                Some(builder) if attempt < policy.max_attempts => builder,
+                _ => return (execute(self.client, self.builder).await, attempt),
+            };
+
+            let delay = match execute(self.client, builder).await {
+                Ok(resp) if is_transient_status(resp.status()) => {
+                    trace_warn!(status = %resp.status(), attempt, "Retrying request");
+                    retry_after(&resp).map(|delay| delay.min(policy.max_backoff))
+                }
+                Err(e) if is_transient_error(&e) => {
+                    trace_warn!(error = %e, attempt, "Retrying request");
+                    None
+                }
+                result => return (result, attempt),
+            };
+
+            self.client
+                .timer
+                .sleep(delay.unwrap_or_else(|| policy.backoff(attempt)))
+                .await;
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_backs_off_exponentially() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff(2), Duration::from_secs(1));
+        assert_eq!(policy.backoff(3), Duration::from_secs(2));
+        assert_eq!(policy.backoff(100), policy.max_backoff);
+    }
+}
@@ -0,0 +1,205 @@
+use std::{
+    fmt::Debug,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Default margin added to deadlines to tolerate skew between the local
+/// clock and the relay's clock
+pub const DEFAULT_SKEW_MARGIN: Duration = Duration::from_secs(30);
+
+/// A source of the current wall-clock time
+pub trait Clock: Debug + Send + Sync {
+    /// The current time
+    fn now(&self) -> SystemTime;
+}
+
+/// The system clock
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        crate::time::now()
+    }
+}
+
+/// A clock at a fixed offset from another clock. Useful for correcting a
+/// local clock known to be skewed relative to the relay
+#[derive(Debug, Clone)]
+pub struct OffsetClock<C> {
+    clock: C,
+    offset_secs: i64,
+}
+
+impl<C> OffsetClock<C> {
+    /// Instantiate a clock `offset_secs` ahead of `clock`. Negative values
+    /// put it behind, down to the unix epoch
+    pub fn new(clock: C, offset_secs: i64) -> Self {
+        Self { clock, offset_secs }
+    }
+}
+
+impl<C: Clock> Clock for OffsetClock<C> {
+    fn now(&self) -> SystemTime {
+        let now = self.clock.now();
+        let offset = Duration::from_secs(self.offset_secs.unsigned_abs());
+        if self.offset_secs >= 0 {
+            now.checked_add(offset).unwrap_or(now)
+        } else {
+            now.checked_sub(offset)
+                .filter(|time| *time >= UNIX_EPOCH)
+                .unwrap_or(UNIX_EPOCH)
+        }
+    }
+}
+
+/// The unix timestamp of a time, in seconds. Times before the epoch are 0
+pub(crate) fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// The unix timestamp `validity` from now, according to the system clock.
+/// `None` if the time can't be represented
+pub(crate) fn unix_secs_in(validity: Duration) -> Option<u64> {
+    crate::time::now().checked_add(validity).map(unix_secs)
+}
+
+/// Computes unix-timestamp deadlines from a configurable time source, padded
+/// by a skew margin so that small disagreements between the local clock and
+/// the relay's clock do not produce requests that are expired on arrival
+#[derive(Debug, Clone)]
+pub struct DeadlineClock {
+    clock: Arc<dyn Clock>,
+    skew_margin: Duration,
+}
+
+impl Default for DeadlineClock {
+    fn default() -> Self {
+        Self {
+            clock: Arc::new(SystemClock),
+            skew_margin: DEFAULT_SKEW_MARGIN,
+        }
+    }
+}
+
+impl DeadlineClock {
+    /// Instantiate a deadline clock with a specific time source and the
+    /// default skew margin
+    pub fn new(clock: impl Clock + 'static) -> Self {
+        Self {
+            clock: Arc::new(clock),
+            ..Default::default()
+        }
+    }
+
+    /// Set the skew margin. Defaults to [`DEFAULT_SKEW_MARGIN`]
+    #[must_use]
+    pub fn skew_margin(mut self, margin: Duration) -> Self {
+        self.skew_margin = margin;
+        self
+    }
+
+    /// The current unix timestamp according to the time source
+    pub fn now_unix(&self) -> u64 {
+        unix_secs(self.clock.now())
+    }
+
+    /// A deadline `validity` from now, extended by the skew margin.
+    /// Saturates at `u64::MAX`
+    pub fn deadline_after(&self, validity: Duration) -> u64 {
+        let margin = validity.saturating_add(self.skew_margin);
+        self.clock
+            .now()
+            .checked_add(margin)
+            .map_or(u64::MAX, unix_secs)
+    }
+
+    /// A deadline at a specific time, extended by the skew margin. Saturates
+    /// at `u64::MAX`
+    pub fn deadline_at(&self, time: SystemTime) -> u64 {
+        time.checked_add(self.skew_margin)
+            .map_or(u64::MAX, unix_secs)
+    }
+
+    /// True if the deadline has definitely passed, i.e. it is more than the
+    /// skew margin in the past. A deadline of 0 never expires
+    pub fn is_expired(&self, deadline: u64) -> bool {
+        deadline != 0 && deadline.saturating_add(self.skew_margin.as_secs()) < self.now_unix()
+    }
+}
+
+/// A comparison between the local clock and a server's clock, as reported by
+/// the HTTP `Date` header of a server response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSkew {
+    /// Local time when the response was received
+    pub local: SystemTime,
+    /// Server time as reported by the `Date` header
+    pub server: SystemTime,
+}
+
+impl ClockSkew {
+    /// Parse a `Date` header value and compare it to the local clock
+    pub fn from_date_header(local: SystemTime, date: &str) -> Option<Self> {
+        let server = httpdate::parse_http_date(date).ok()?;
+        Some(Self { local, server })
+    }
+
+    /// Seconds that the local clock is ahead of the server. Negative if the
+    /// local clock is behind. Note that `Date` headers have 1-second
+    /// resolution
+    pub fn offset_secs(&self) -> i64 {
+        unix_secs(self.local) as i64 - unix_secs(self.server) as i64
+    }
+
+    /// True if the skew is larger than `margin` in either direction
+    pub fn exceeds(&self, margin: Duration) -> bool {
+        self.offset_secs().unsigned_abs() > margin.as_secs()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    struct FixedClock(u64);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> SystemTime {
+            UNIX_EPOCH + Duration::from_secs(self.0)
+        }
+    }
+
+    #[test]
+    fn it_pads_deadlines() {
+        let clock = DeadlineClock::new(FixedClock(1_000)).skew_margin(Duration::from_secs(30));
+        assert_eq!(clock.deadline_after(Duration::from_secs(60)), 1_090);
+        assert!(!clock.is_expired(980));
+        assert!(clock.is_expired(960));
+        assert!(!clock.is_expired(0));
+    }
+
+    #[test]
+    fn it_saturates_at_the_edges() {
+        let behind = OffsetClock::new(FixedClock(10), -20);
+        assert_eq!(behind.now(), UNIX_EPOCH);
+        let ahead = OffsetClock::new(FixedClock(10), 20);
+        assert_eq!(unix_secs(ahead.now()), 30);
+
+        let clock = DeadlineClock::new(FixedClock(1_000)).skew_margin(Duration::MAX);
+        assert_eq!(clock.deadline_after(Duration::MAX), u64::MAX);
+        assert!(!clock.is_expired(u64::MAX));
+    }
+
+    #[test]
+    fn it_measures_skew() {
+        let local = UNIX_EPOCH + Duration::from_secs(784_111_837);
+        let skew = ClockSkew::from_date_header(local, "Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+        assert_eq!(skew.offset_secs(), 60);
+        assert!(skew.exceeds(Duration::from_secs(30)));
+    }
+}
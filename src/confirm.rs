@@ -0,0 +1,164 @@
+use ethers_core::types::{TransactionReceipt, H256};
+use ethers_providers::Middleware;
+
+use crate::{GelatoApi, GelatoTask, TaskError};
+
+/// Errors awaiting confirmation of an execution
+#[derive(Debug, thiserror::Error)]
+pub enum ConfirmationError {
+    /// Tracking the task failed
+    #[error(transparent)]
+    Task(#[from] TaskError),
+    /// Provider error while fetching the receipt or block number
+    #[error("Provider error: {0}")]
+    Provider(Box<dyn std::error::Error + Send + Sync>),
+    /// The execution did not reach the required confirmations within the
+    /// allowed number of checks, e.g. because it was reorged out for good
+    #[error("Execution {transaction_hash:?} not confirmed after {checks} checks")]
+    NotConfirmed {
+        /// Hash of the execution transaction
+        transaction_hash: H256,
+        /// Number of receipt checks made
+        checks: usize,
+    },
+}
+
+// true if a transaction included at `receipt_block` has `confirmations`
+// confirmations at `tip`. The including block counts as the first
+fn is_confirmed(receipt_block: u64, tip: u64, confirmations: usize) -> bool {
+    let confirmations = u64::try_from(confirmations).unwrap_or(u64::MAX);
+    tip.saturating_add(1).saturating_sub(receipt_block) >= confirmations
+}
+
+impl<'a, P, A> GelatoTask<'a, P, A>
+where
+    A: GelatoApi + ?Sized,
+{
+    /// Track the task to `ExecSuccess`, then wait until the execution
+    /// transaction has `confirmations` confirmations on `provider`, and
+    /// return its receipt.
+    ///
+    /// Gelato reports success as soon as the transaction is mined, so a
+    /// reorg may drop it. The receipt is re-fetched on every check, at the
+    /// task's maximum polling delay, so a transaction reorged out of the
+    /// chain is waited for until it is included again. Fails with
+    /// [`ConfirmationError::NotConfirmed`] after `max_checks` checks
+    pub async fn confirmations<M>(
+        mut self,
+        provider: &M,
+        confirmations: usize,
+        max_checks: usize,
+    ) -> Result<TransactionReceipt, ConfirmationError>
+    where
+        M: Middleware,
+        M::Error: 'static,
+    {
+        let execution = (&mut self).await?;
+        let provider_err = |e: M::Error| ConfirmationError::Provider(Box::new(e));
+
+        for check in 1..=max_checks {
+            let receipt = provider
+                .get_transaction_receipt(execution.transaction_hash)
+                .await
+                .map_err(provider_err)?;
+            match receipt {
+                Some(receipt) => {
+                    if let Some(receipt_block) = receipt.block_number {
+                        let tip = provider.get_block_number().await.map_err(provider_err)?;
+                        if is_confirmed(receipt_block.as_u64(), tip.as_u64(), confirmations) {
+                            return Ok(receipt);
+                        }
+                    }
+                }
+                None => {
+                    trace_warn!(
+                        transaction_hash = ?execution.transaction_hash,
+                        "Execution receipt not found, possibly reorged"
+                    );
+                }
+            }
+            if check < max_checks {
+                self.sleep(self.max_delay()).await;
+            }
+        }
+        Err(ConfirmationError::NotConfirmed {
+            transaction_hash: execution.transaction_hash,
+            checks: max_checks,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_counts_confirmations() {
+        assert!(is_confirmed(10, 10, 1));
+        assert!(!is_confirmed(10, 10, 2));
+        assert!(is_confirmed(10, 12, 3));
+        assert!(is_confirmed(10, 12, 0));
+        // tip behind the receipt, e.g. a lagging node
+        assert!(!is_confirmed(10, 9, 1));
+    }
+
+    mod mock {
+        use std::time::Duration;
+
+        use ethers_core::types::U64;
+        use ethers_providers::Provider;
+
+        use super::*;
+        use crate::{rpc::TaskState, testing::MockGelatoClient, TaskId};
+
+        fn receipt(block: u64) -> Option<TransactionReceipt> {
+            Some(TransactionReceipt {
+                block_number: Some(block.into()),
+                ..Default::default()
+            })
+        }
+
+        fn executed(mock: &MockGelatoClient) -> GelatoTask<'_, (), MockGelatoClient> {
+            let id = TaskId::from(H256::repeat_byte(1));
+            mock.push_statuses(id, [crate::testing::status(id, TaskState::ExecSuccess)]);
+            GelatoTask::new(id, mock, ()).polling_interval(Duration::from_millis(1))
+        }
+
+        #[tokio::test]
+        async fn it_waits_out_reorgs() {
+            let gelato = MockGelatoClient::new();
+            let (provider, mock) = Provider::mocked();
+            // the mock answers the most recently pushed response first
+            mock.push::<U64, _>(U64::from(12)).unwrap();
+            mock.push::<Option<TransactionReceipt>, _>(receipt(11))
+                .unwrap();
+            mock.push::<Option<TransactionReceipt>, _>(None).unwrap();
+            mock.push::<U64, _>(U64::from(10)).unwrap();
+            mock.push::<Option<TransactionReceipt>, _>(receipt(10))
+                .unwrap();
+
+            let confirmed = executed(&gelato)
+                .confirmations(&provider, 2, 3)
+                .await
+                .unwrap();
+            assert_eq!(confirmed.block_number, Some(11.into()));
+        }
+
+        #[tokio::test]
+        async fn it_gives_up_after_max_checks() {
+            let gelato = MockGelatoClient::new();
+            let (provider, mock) = Provider::mocked();
+            mock.push::<Option<TransactionReceipt>, _>(None).unwrap();
+            mock.push::<Option<TransactionReceipt>, _>(None).unwrap();
+
+            let err = executed(&gelato)
+                .confirmations(&provider, 1, 2)
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                ConfirmationError::NotConfirmed { checks: 2, .. }
+            ));
+        }
+    }
+}
@@ -0,0 +1,389 @@
+use ethers_core::types::Address;
+use futures_util::future;
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll, Waker},
+};
+
+use crate::{
+    offline::SignedRequest,
+    poller::{PollerHandle, TaskPoller},
+    rpc::Execution,
+    shutdown::{Shutdown, ShutdownSignal},
+    task::TaskError,
+    ClientError, ClientResult, GelatoClient, TaskId,
+};
+
+#[derive(Debug, Default)]
+struct InFlight {
+    per_sponsor: HashMap<Address, usize>,
+    per_chain: HashMap<u64, usize>,
+    waiters: HashMap<u64, Waker>,
+    next_waiter: u64,
+}
+
+impl InFlight {
+    fn decrement<K: Eq + std::hash::Hash>(counts: &mut HashMap<K, usize>, key: &K) {
+        if let Some(count) = counts.get_mut(key) {
+            *count -= 1;
+            if *count == 0 {
+                counts.remove(key);
+            }
+        }
+    }
+}
+
+/// In-flight task limits, shared between a [`Dispatcher`] and its clones
+#[derive(Debug, Clone, Default)]
+struct Limits {
+    per_sponsor: Option<usize>,
+    per_chain: Option<usize>,
+    in_flight: Arc<Mutex<InFlight>>,
+}
+
+impl Limits {
+    fn in_flight(&self) -> MutexGuard<'_, InFlight> {
+        self.in_flight.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn try_acquire(&self, sponsor: Address, chain_id: u64) -> bool {
+        let mut in_flight = self.in_flight();
+        let sponsor_count = in_flight.per_sponsor.get(&sponsor).copied().unwrap_or(0);
+        let chain_count = in_flight.per_chain.get(&chain_id).copied().unwrap_or(0);
+
+        if self.per_sponsor.is_some_and(|max| sponsor_count >= max)
+            || self.per_chain.is_some_and(|max| chain_count >= max)
+        {
+            return false;
+        }
+
+        *in_flight.per_sponsor.entry(sponsor).or_default() += 1;
+        *in_flight.per_chain.entry(chain_id).or_default() += 1;
+        true
+    }
+
+    fn release(&self, sponsor: Address, chain_id: u64) {
+        let mut in_flight = self.in_flight();
+        InFlight::decrement(&mut in_flight.per_sponsor, &sponsor);
+        InFlight::decrement(&mut in_flight.per_chain, &chain_id);
+        in_flight
+            .waiters
+            .drain()
+            .for_each(|(_, waker)| waker.wake());
+    }
+
+    /// Register the waker of an [`Acquire`], replacing its previous waker.
+    /// Returns the key of its slot
+    fn register(&self, waiter: Option<u64>, waker: &Waker) -> u64 {
+        let mut in_flight = self.in_flight();
+        let key = waiter.unwrap_or_else(|| {
+            in_flight.next_waiter = in_flight.next_waiter.wrapping_add(1);
+            in_flight.next_waiter
+        });
+        match in_flight.waiters.get(&key) {
+            Some(registered) if registered.will_wake(waker) => {}
+            _ => {
+                in_flight.waiters.insert(key, waker.clone());
+            }
+        }
+        key
+    }
+
+    fn unregister(&self, waiter: u64) {
+        self.in_flight().waiters.remove(&waiter);
+    }
+}
+
+/// A slot in the in-flight limits of a [`Dispatcher`]. Released on drop
+#[derive(Debug)]
+pub struct InFlightPermit {
+    limits: Limits,
+    sponsor: Address,
+    chain_id: u64,
+}
+
+impl Drop for InFlightPermit {
+    fn drop(&mut self) {
+        self.limits.release(self.sponsor, self.chain_id);
+    }
+}
+
+/// Future resolving to an [`InFlightPermit`] once the sponsor and chain are
+/// both below their limits
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Acquire {
+    limits: Limits,
+    sponsor: Address,
+    chain_id: u64,
+    waiter: Option<u64>,
+}
+
+impl Drop for Acquire {
+    fn drop(&mut self) {
+        if let Some(waiter) = self.waiter.take() {
+            self.limits.unregister(waiter);
+        }
+    }
+}
+
+impl Future for Acquire {
+    type Output = InFlightPermit;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if !self.limits.try_acquire(self.sponsor, self.chain_id) {
+            let waiter = self.limits.register(self.waiter, cx.waker());
+            self.waiter = Some(waiter);
+            // re-check in case a permit was released while registering
+            if !self.limits.try_acquire(self.sponsor, self.chain_id) {
+                return Poll::Pending;
+            }
+        }
+        if let Some(waiter) = self.waiter.take() {
+            self.limits.unregister(waiter);
+        }
+        Poll::Ready(InFlightPermit {
+            limits: self.limits.clone(),
+            sponsor: self.sponsor,
+            chain_id: self.chain_id,
+        })
+    }
+}
+
+/// Submits signed requests and tracks them on a shared [`TaskPoller`],
+/// limiting the number of simultaneously pending tasks per sponsor and per
+/// chain.
+///
+/// Gelato throttles sponsors, so unbounded parallelism converts into backend
+/// cancellations. Dispatches above a limit wait until a pending task of the
+/// same sponsor or chain resolves
+#[derive(Debug, Clone)]
+pub struct Dispatcher {
+    client: GelatoClient,
+    poller: TaskPoller,
+    limits: Limits,
+    shutdown: Option<Shutdown>,
+}
+
+impl Dispatcher {
+    /// Instantiate a dispatcher without limits. The poller must be run for
+    /// dispatched tasks to resolve
+    pub fn new(client: GelatoClient, poller: TaskPoller) -> Self {
+        Self {
+            client,
+            poller,
+            limits: Default::default(),
+            shutdown: None,
+        }
+    }
+
+    /// Stop dispatching when `shutdown` is triggered. Later dispatches, and
+    /// dispatches waiting for a slot, fail with [`ClientError::Shutdown`].
+    /// A dispatch that is submitting holds a [`ShutdownSignal`] until its
+    /// request is sent, so that shutdown drains it. Dispatched tasks are
+    /// stopped by the poller's own shutdown, see
+    /// [`TaskPoller::run_with_shutdown`]
+    #[must_use]
+    pub fn with_shutdown(mut self, shutdown: &Shutdown) -> Self {
+        self.shutdown = Some(shutdown.clone());
+        self
+    }
+
+    /// Limit the number of pending tasks per sponsor
+    #[must_use]
+    pub fn max_in_flight_per_sponsor(mut self, max: usize) -> Self {
+        self.limits.per_sponsor = Some(max.max(1));
+        self
+    }
+
+    /// Limit the number of pending tasks per chain
+    #[must_use]
+    pub fn max_in_flight_per_chain(mut self, max: usize) -> Self {
+        self.limits.per_chain = Some(max.max(1));
+        self
+    }
+
+    /// The poller tracking dispatched tasks
+    pub fn poller(&self) -> &TaskPoller {
+        &self.poller
+    }
+
+    /// The number of pending tasks of a sponsor
+    pub fn in_flight_for_sponsor(&self, sponsor: Address) -> usize {
+        self.limits
+            .in_flight()
+            .per_sponsor
+            .get(&sponsor)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// The number of pending tasks on a chain
+    pub fn in_flight_for_chain(&self, chain_id: u64) -> usize {
+        self.limits
+            .in_flight()
+            .per_chain
+            .get(&chain_id)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Wait for a slot for a sponsor and chain
+    pub fn acquire(&self, sponsor: Address, chain_id: u64) -> Acquire {
+        Acquire {
+            limits: self.limits.clone(),
+            sponsor,
+            chain_id,
+            waiter: None,
+        }
+    }
+
+    /// Wait for a slot, submit the request, and track it to completion. The
+    /// slot is held until the returned task resolves or is dropped
+    pub async fn dispatch(
+        &self,
+        request: impl Into<SignedRequest>,
+    ) -> ClientResult<DispatchedTask> {
+        let request = request.into();
+        let (permit, _signal) = self.admit(request.sponsor(), request.chain_id()).await?;
+        let resp = self.client.send_signed_request(&request).await?;
+        Ok(DispatchedTask {
+            handle: self.poller.track(resp.task_id()),
+            _permit: permit,
+        })
+    }
+
+    // wait for a slot, unless shutdown is triggered first. The signal, if
+    // any, keeps shutdown from draining until the dispatch is submitted
+    async fn admit(
+        &self,
+        sponsor: Address,
+        chain_id: u64,
+    ) -> ClientResult<(InFlightPermit, Option<ShutdownSignal>)> {
+        let signal = match &self.shutdown {
+            Some(shutdown) => shutdown.signal(),
+            None => return Ok((self.acquire(sponsor, chain_id).await, None)),
+        };
+        if signal.is_shutdown() {
+            return Err(ClientError::Shutdown);
+        }
+        let acquire = self.acquire(sponsor, chain_id);
+        let permit = match future::select(acquire, signal.triggered()).await {
+            future::Either::Left((permit, _)) => permit,
+            future::Either::Right(_) => return Err(ClientError::Shutdown),
+        };
+        Ok((permit, Some(signal)))
+    }
+}
+
+/// A task submitted by a [`Dispatcher`]. Resolves when the task reaches a
+/// terminal state, releasing its in-flight slot
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct DispatchedTask {
+    handle: PollerHandle,
+    _permit: InFlightPermit,
+}
+
+impl DispatchedTask {
+    /// The id of the dispatched task
+    pub fn task_id(&self) -> TaskId {
+        self.handle.task_id()
+    }
+}
+
+impl Future for DispatchedTask {
+    type Output = Result<Execution, TaskError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.handle).poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_util::FutureExt;
+
+    use super::*;
+
+    #[test]
+    fn it_limits_in_flight_per_sponsor() {
+        let client = GelatoClient::default();
+        let dispatcher =
+            Dispatcher::new(client.clone(), TaskPoller::new(client)).max_in_flight_per_sponsor(1);
+        let sponsor = Address::repeat_byte(1);
+
+        let permit = dispatcher.acquire(sponsor, 1).now_or_never().unwrap();
+        assert_eq!(dispatcher.in_flight_for_sponsor(sponsor), 1);
+        assert!(dispatcher.acquire(sponsor, 1).now_or_never().is_none());
+        assert!(dispatcher
+            .acquire(Address::repeat_byte(2), 1)
+            .now_or_never()
+            .is_some());
+
+        drop(permit);
+        assert_eq!(dispatcher.in_flight_for_chain(1), 0);
+        assert!(dispatcher.acquire(sponsor, 1).now_or_never().is_some());
+    }
+
+    #[test]
+    fn it_registers_each_waiter_once() {
+        let client = GelatoClient::default();
+        let dispatcher =
+            Dispatcher::new(client.clone(), TaskPoller::new(client)).max_in_flight_per_sponsor(1);
+        let sponsor = Address::repeat_byte(1);
+        let _permit = dispatcher.acquire(sponsor, 1).now_or_never().unwrap();
+
+        let waker = futures_util::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut acquire = dispatcher.acquire(sponsor, 1);
+        for _ in 0..3 {
+            assert!(Pin::new(&mut acquire).poll(&mut cx).is_pending());
+        }
+        assert_eq!(dispatcher.limits.in_flight().waiters.len(), 1);
+
+        drop(acquire);
+        assert!(dispatcher.limits.in_flight().waiters.is_empty());
+    }
+
+    #[test]
+    fn it_rejects_dispatches_on_shutdown() {
+        let client = GelatoClient::default();
+        let shutdown = Shutdown::new();
+        let dispatcher = Dispatcher::new(client.clone(), TaskPoller::new(client))
+            .max_in_flight_per_sponsor(1)
+            .with_shutdown(&shutdown);
+        let sponsor = Address::repeat_byte(1);
+
+        let (permit, signal) = dispatcher
+            .admit(sponsor, 1)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert!(signal.is_some());
+        assert_eq!(shutdown.active(), 1);
+
+        // a dispatch waiting for a slot is rejected once shutdown triggers
+        let mut waiting = Box::pin(dispatcher.admit(sponsor, 1));
+        assert!((&mut waiting).now_or_never().is_none());
+        let mut drained = shutdown.shutdown();
+        assert!(matches!(
+            waiting.now_or_never().unwrap(),
+            Err(ClientError::Shutdown)
+        ));
+
+        // the admitted dispatch holds up draining until it is done
+        assert!((&mut drained).now_or_never().is_none());
+        drop(signal);
+        assert!(drained.now_or_never().is_some());
+
+        drop(permit);
+        assert!(matches!(
+            dispatcher.admit(sponsor, 1).now_or_never().unwrap(),
+            Err(ClientError::Shutdown)
+        ));
+    }
+}
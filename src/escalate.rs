@@ -0,0 +1,324 @@
+use std::time::Duration;
+
+use ethers_core::types::U256;
+use futures_util::{
+    future::{self, Either},
+    stream, Stream, StreamExt,
+};
+
+use crate::{
+    rpc::{self, Execution, ForwardRequest, ForwardRequestError, SignedForwardRequest},
+    task::resolve,
+    ClientError, GelatoApi, GelatoTask, TaskError, TaskStatusStream,
+};
+
+/// Errors escalating the fee of a task
+#[derive(Debug, thiserror::Error)]
+pub enum EscalationError {
+    /// Tracking the task failed
+    #[error(transparent)]
+    Task(#[from] TaskError),
+    /// Resubmission failed
+    #[error(transparent)]
+    Client(#[from] ClientError),
+    /// Re-signing the bumped request failed
+    #[error(transparent)]
+    ForwardRequest(#[from] ForwardRequestError),
+    /// The request does not enforce the sponsor nonce, so a resubmission
+    /// could be executed alongside the original
+    #[error("Fee escalation requires an enforced sponsor nonce")]
+    NonceNotEnforced,
+}
+
+/// Policy for resubmitting a forward request with a bumped `max_fee` when
+/// the relay cancels it, or it stays `CheckPending` for too long. Similar to
+/// the gas escalator middleware of ethers.
+///
+/// Only requests that enforce the sponsor nonce are escalated, so that at
+/// most one submission is executed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EscalationPolicy {
+    /// Percentage added to `max_fee` on each resubmission
+    pub bump_percent: u32,
+    /// Upper bound on `max_fee`. Escalation stops once it is reached
+    pub max_fee_cap: Option<U256>,
+    /// How long a submission may stay `CheckPending` before it is
+    /// resubmitted
+    pub pending_timeout: Duration,
+    /// Maximum number of resubmissions
+    pub max_resubmissions: usize,
+}
+
+impl Default for EscalationPolicy {
+    fn default() -> Self {
+        Self {
+            bump_percent: 20,
+            max_fee_cap: None,
+            pending_timeout: Duration::from_secs(120),
+            max_resubmissions: 3,
+        }
+    }
+}
+
+impl EscalationPolicy {
+    /// The bumped `max_fee`, or `None` if the cap has been reached. Saturates
+    /// at `U256::MAX`
+    pub fn bump(&self, max_fee: U256) -> Option<U256> {
+        let percent = U256::from(self.bump_percent);
+        let increase = max_fee
+            .checked_mul(percent)
+            .map(|increase| increase / 100)
+            .unwrap_or_else(|| (max_fee / 100).saturating_mul(percent));
+        let bumped = max_fee.saturating_add(increase);
+        let bumped = match self.max_fee_cap {
+            Some(cap) => bumped.min(cap),
+            None => bumped,
+        };
+        (bumped > max_fee).then_some(bumped)
+    }
+}
+
+type Tagged = (usize, Result<rpc::TransactionStatus, TaskError>);
+
+// tag the statuses of a submission with its index
+fn tagged<'a, P, A>(
+    stream: TaskStatusStream<'a, P, A>,
+    index: usize,
+) -> impl Stream<Item = Tagged> + Unpin + 'a
+where
+    P: 'a,
+    A: GelatoApi + ?Sized,
+{
+    stream.map(move |status| (index, status))
+}
+
+impl<'a, A> GelatoTask<'a, SignedForwardRequest, A>
+where
+    A: GelatoApi + ?Sized,
+{
+    /// Track the task, resubmitting it with a bumped `max_fee` signed by
+    /// `sponsor` according to the policy.
+    ///
+    /// Every submission is tracked until one executes, as a submission that
+    /// was replaced for being stuck may still be executed. Resolves to the
+    /// first execution, or to the last error once no submission is pending.
+    ///
+    /// Errors with [`EscalationError::NonceNotEnforced`] if the request does
+    /// not enforce the sponsor nonce
+    pub async fn escalate<S>(
+        self,
+        sponsor: &S,
+        policy: EscalationPolicy,
+    ) -> Result<Execution, EscalationError>
+    where
+        S: ethers_signers::Signer,
+        S::Error: 'static,
+    {
+        if !self.payload().enforce_sponsor_nonce {
+            return Err(EscalationError::NonceNotEnforced);
+        }
+
+        // resubmissions are tracked with the settings of this task
+        let template = self.resubmitted(self.id(), ());
+        let mut latest = self.payload().clone();
+        let mut resubmissions = 0;
+        let mut last_error = None;
+        let mut stuck = Some(self.sleep(policy.pending_timeout));
+        let mut live = stream::select_all([tagged(self.into_stream(), 0)]);
+
+        loop {
+            let next = match stuck.as_mut() {
+                Some(sleep) => match future::select(live.next(), sleep).await {
+                    Either::Left((next, _)) => Some(next),
+                    Either::Right(_) => None,
+                },
+                None => Some(live.next().await),
+            };
+
+            let escalate = match next {
+                // the latest submission is stuck in `CheckPending`
+                None => {
+                    stuck = None;
+                    true
+                }
+                // every submission has resolved without executing
+                Some(None) => return Err(last_error.unwrap_or(TaskError::NotFound).into()),
+                Some(Some((index, status))) => {
+                    let is_latest = index == resubmissions;
+                    match status.map(|status| (resolve(status.clone()), status)) {
+                        Ok((Some(Ok(execution)), _)) => return Ok(execution),
+                        // the nonce was used, so no other submission can
+                        // execute
+                        Ok((Some(Err(e @ TaskError::Reverted { .. })), _)) => return Err(e.into()),
+                        Ok((Some(Err(e)), _)) | Err(e) => {
+                            let cancelled = matches!(e, TaskError::Cancelled { .. });
+                            last_error = Some(e);
+                            is_latest && cancelled
+                        }
+                        Ok((None, status)) => {
+                            if is_latest && status.task_state != rpc::TaskState::CheckPending {
+                                stuck = None;
+                            }
+                            false
+                        }
+                    }
+                }
+            };
+            if !escalate {
+                continue;
+            }
+
+            // out of escalations. keep tracking the pending submissions
+            let max_fee = match policy.bump(latest.max_fee) {
+                Some(max_fee) if resubmissions < policy.max_resubmissions => max_fee,
+                _ => continue,
+            };
+            trace_warn!(
+                max_fee = %max_fee,
+                resubmissions = resubmissions + 1,
+                "Resubmitting task with a bumped fee"
+            );
+
+            let mut bumped: ForwardRequest = (*latest).clone();
+            bumped.max_fee = max_fee;
+            let signed = bumped.sign(sponsor).await?;
+            let resp = template.client().send_forward_request(&signed).await?;
+            resubmissions += 1;
+            latest = signed.clone();
+
+            let task = template.resubmitted(resp.task_id(), signed);
+            stuck = Some(task.sleep(policy.pending_timeout));
+            live.push(tagged(task.into_stream(), resubmissions));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_bumps_fees_up_to_the_cap() {
+        let policy = EscalationPolicy {
+            max_fee_cap: Some(1100.into()),
+            ..Default::default()
+        };
+        assert_eq!(policy.bump(1000.into()), Some(1100.into()));
+        assert_eq!(policy.bump(1100.into()), None);
+        assert_eq!(EscalationPolicy::default().bump(0.into()), None);
+
+        // saturates instead of overflowing
+        assert_eq!(EscalationPolicy::default().bump(U256::MAX), None);
+        let bumped = EscalationPolicy::default().bump(U256::MAX / 2).unwrap();
+        assert!(bumped > U256::MAX / 2);
+    }
+
+    mod mock {
+        use ethers_core::types::H256;
+        use ethers_signers::{LocalWallet, Signer};
+
+        use super::*;
+        use crate::{
+            testing::{status, MockGelatoClient, Submission},
+            PaymentType, TaskId,
+        };
+
+        fn wallet() -> LocalWallet {
+            "9cb3a530d61728e337290409d967db069f5219279f89e5ddb5ae4af76a8da5f4"
+                .parse()
+                .unwrap()
+        }
+
+        async fn signed(wallet: &LocalWallet) -> SignedForwardRequest {
+            ForwardRequest {
+                chain_id: 42,
+                target: Default::default(),
+                data: Default::default(),
+                fee_token: Default::default(),
+                payment_type: PaymentType::AsyncGasTank,
+                max_fee: 1000.into(),
+                gas: 200000.into(),
+                sponsor: wallet.address(),
+                sponsor_chain_id: 42,
+                nonce: 0,
+                enforce_sponsor_nonce: true,
+                enforce_sponsor_nonce_ordering: false,
+            }
+            .sign(wallet)
+            .await
+            .unwrap()
+        }
+
+        #[tokio::test]
+        async fn it_resubmits_cancelled_requests() {
+            let wallet = wallet();
+            let first = TaskId::from(H256::repeat_byte(1));
+            let second = TaskId::from(H256::repeat_byte(2));
+            let mock = MockGelatoClient::new();
+            mock.push_response(second)
+                .push_statuses(first, [status(first, rpc::TaskState::Cancelled)])
+                .push_statuses(second, [status(second, rpc::TaskState::ExecSuccess)]);
+
+            GelatoTask::new(first, &mock, signed(&wallet).await)
+                .polling_interval(Duration::from_millis(1))
+                .escalate(&wallet, EscalationPolicy::default())
+                .await
+                .unwrap();
+
+            match &mock.submissions()[..] {
+                [Submission::ForwardRequest(resubmitted)] => {
+                    assert_eq!(resubmitted.max_fee, 1200.into());
+                    resubmitted.verify().unwrap();
+                }
+                submissions => panic!("unexpected submissions {:?}", submissions),
+            }
+        }
+
+        #[tokio::test]
+        async fn it_resolves_when_a_replaced_submission_executes() {
+            let wallet = wallet();
+            let first = TaskId::from(H256::repeat_byte(1));
+            let second = TaskId::from(H256::repeat_byte(2));
+            let mock = MockGelatoClient::new();
+            let pending = std::iter::repeat_with(|| status(first, rpc::TaskState::CheckPending));
+            mock.push_response(second)
+                .push_statuses(first, pending.take(50))
+                .push_statuses(first, [status(first, rpc::TaskState::ExecSuccess)])
+                .push_statuses(second, [status(second, rpc::TaskState::CheckPending)]);
+
+            let policy = EscalationPolicy {
+                pending_timeout: Duration::from_millis(5),
+                max_resubmissions: 1,
+                ..Default::default()
+            };
+            GelatoTask::new(first, &mock, signed(&wallet).await)
+                .polling_interval(Duration::from_millis(1))
+                .escalate(&wallet, policy)
+                .await
+                .unwrap();
+            assert_eq!(mock.submissions().len(), 1);
+        }
+
+        #[tokio::test]
+        async fn it_fails_once_every_submission_is_cancelled() {
+            let wallet = wallet();
+            let first = TaskId::from(H256::repeat_byte(1));
+            let mock = MockGelatoClient::new();
+            mock.push_statuses(first, [status(first, rpc::TaskState::Cancelled)]);
+
+            let policy = EscalationPolicy {
+                max_resubmissions: 0,
+                ..Default::default()
+            };
+            let err = GelatoTask::new(first, &mock, signed(&wallet).await)
+                .polling_interval(Duration::from_millis(1))
+                .escalate(&wallet, policy)
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err,
+                EscalationError::Task(TaskError::Cancelled { .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,209 @@
+#[cfg(feature = "providers")]
+use ethers_contract::{abigen, EthLogDecode};
+use ethers_core::types::{Address, U256};
+#[cfg(feature = "providers")]
+use ethers_core::{
+    abi::RawLog,
+    types::{Log, TransactionReceipt, H256},
+};
+#[cfg(feature = "providers")]
+use ethers_providers::Middleware;
+
+#[cfg(feature = "providers")]
+use crate::rpc::Execution;
+
+use crate::{FeeToken, TaskId};
+
+// Fee events of Gelato's relay contracts: `GelatoRelay` for sync fee calls,
+// `GelatoRelayForwarder` for forward requests and `GelatoMetaBox` for meta tx
+// requests
+#[cfg(feature = "providers")]
+abigen!(
+    RelayFeeEvents,
+    r#"[
+        event LogCallWithSyncFee(address indexed target, address feeToken, uint256 fee, bytes32 taskId)
+        event LogForwardRequestAsyncGasTankFee(address indexed sponsor, address indexed target, uint256 sponsorChainId, address feeToken, uint256 fee, bytes32 taskId)
+        event LogForwardRequestSyncGasTankFee(address indexed sponsor, address indexed target, address feeToken, uint256 fee, bytes32 taskId)
+        event LogMetaTxRequestAsyncGasTankFee(address indexed sponsor, address indexed user, address indexed target, uint256 sponsorChainId, address feeToken, uint256 fee, bytes32 taskId)
+        event LogMetaTxRequestSyncGasTankFee(address indexed sponsor, address indexed user, address indexed target, address feeToken, uint256 fee, bytes32 taskId)
+    ]"#
+);
+
+/// The fee actually charged for an execution, as reported by the relay
+/// contract's fee event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActualFee {
+    /// Contract that emitted the fee event
+    pub emitter: Address,
+    /// Contract the request called
+    pub target: Address,
+    /// Sponsor charged, if the fee was paid from a Gas Tank
+    pub sponsor: Option<Address>,
+    /// Token the fee was charged in
+    pub fee_token: FeeToken,
+    /// Fee charged
+    pub fee: U256,
+    /// Gelato task id, as reported by the event
+    pub task_id: TaskId,
+}
+
+/// The fee actually charged, next to the `max_fee` the sponsor signed off on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeReconciliation {
+    /// Maximum fee in the request
+    pub max_fee: U256,
+    /// Fee actually charged
+    pub actual: ActualFee,
+}
+
+impl FeeReconciliation {
+    /// Amount by which `max_fee` exceeded the fee actually charged
+    pub fn overquote(&self) -> U256 {
+        self.max_fee.saturating_sub(self.actual.fee)
+    }
+
+    /// Fee actually charged as basis points of `max_fee`
+    pub fn utilization_bps(&self) -> Option<U256> {
+        if self.max_fee.is_zero() {
+            return None;
+        }
+        Some(self.actual.fee * U256::from(10_000u64) / self.max_fee)
+    }
+}
+
+#[cfg(feature = "providers")]
+fn decode_fee_log(log: &Log) -> Option<ActualFee> {
+    let raw = RawLog {
+        topics: log.topics.clone(),
+        data: log.data.to_vec(),
+    };
+    let (target, sponsor, fee_token, fee, task_id) =
+        match RelayFeeEventsEvents::decode_log(&raw).ok()? {
+            RelayFeeEventsEvents::LogCallWithSyncFeeFilter(e) => {
+                (e.target, None, e.fee_token, e.fee, e.task_id)
+            }
+            RelayFeeEventsEvents::LogForwardRequestAsyncGasTankFeeFilter(e) => {
+                (e.target, Some(e.sponsor), e.fee_token, e.fee, e.task_id)
+            }
+            RelayFeeEventsEvents::LogForwardRequestSyncGasTankFeeFilter(e) => {
+                (e.target, Some(e.sponsor), e.fee_token, e.fee, e.task_id)
+            }
+            RelayFeeEventsEvents::LogMetaTxRequestAsyncGasTankFeeFilter(e) => {
+                (e.target, Some(e.sponsor), e.fee_token, e.fee, e.task_id)
+            }
+            RelayFeeEventsEvents::LogMetaTxRequestSyncGasTankFeeFilter(e) => {
+                (e.target, Some(e.sponsor), e.fee_token, e.fee, e.task_id)
+            }
+        };
+
+    Some(ActualFee {
+        emitter: log.address,
+        target,
+        sponsor,
+        fee_token: fee_token.into(),
+        fee,
+        task_id: H256::from(task_id).into(),
+    })
+}
+
+/// Find the relay fee event in an execution receipt
+#[cfg(feature = "providers")]
+pub fn actual_fee_from_receipt(receipt: &TransactionReceipt) -> Option<ActualFee> {
+    receipt.logs.iter().find_map(decode_fee_log)
+}
+
+/// Fetch the execution receipt and determine the fee actually charged.
+/// Returns `None` if the receipt is not yet available or contains no fee
+/// event
+#[cfg(feature = "providers")]
+pub async fn actual_fee<M: Middleware>(
+    provider: &M,
+    execution: &Execution,
+) -> Result<Option<ActualFee>, M::Error> {
+    let receipt = provider
+        .get_transaction_receipt(execution.transaction_hash)
+        .await?;
+    Ok(receipt.as_ref().and_then(actual_fee_from_receipt))
+}
+
+/// Fetch the execution receipt and reconcile the fee actually charged
+/// against the request's `max_fee`
+#[cfg(feature = "providers")]
+pub async fn reconcile<M: Middleware>(
+    provider: &M,
+    execution: &Execution,
+    max_fee: U256,
+) -> Result<Option<FeeReconciliation>, M::Error> {
+    Ok(actual_fee(provider, execution)
+        .await?
+        .map(|actual| FeeReconciliation { max_fee, actual }))
+}
+
+#[cfg(all(test, feature = "providers"))]
+mod test {
+    use ethers_core::{
+        abi::{encode, Token},
+        utils::keccak256,
+    };
+
+    use super::*;
+
+    #[test]
+    fn it_decodes_fee_logs() {
+        let sponsor = Address::repeat_byte(0x11);
+        let target = Address::repeat_byte(0x22);
+        let fee_token = Address::repeat_byte(0xee);
+        let task_id = H256::repeat_byte(0x01);
+
+        // laid out per the ABI: indexed fields in topics, the rest in data
+        let log = Log {
+            address: Address::repeat_byte(0x33),
+            topics: vec![
+                H256::from(keccak256(
+                    "LogForwardRequestSyncGasTankFee(address,address,address,uint256,bytes32)",
+                )),
+                H256::from(sponsor),
+                H256::from(target),
+            ],
+            data: encode(&[
+                Token::Address(fee_token),
+                Token::Uint(42.into()),
+                Token::FixedBytes(task_id.as_bytes().to_vec()),
+            ])
+            .into(),
+            ..Default::default()
+        };
+
+        let fee = decode_fee_log(&log).unwrap();
+        assert_eq!(fee.emitter, Address::repeat_byte(0x33));
+        assert_eq!(fee.sponsor, Some(sponsor));
+        assert_eq!(fee.target, target);
+        assert_eq!(*fee.fee_token, fee_token);
+        assert_eq!(fee.fee, 42.into());
+        assert_eq!(*fee.task_id, task_id);
+
+        let reconciled = FeeReconciliation {
+            max_fee: 100u64.into(),
+            actual: fee,
+        };
+        assert_eq!(reconciled.overquote(), 58.into());
+        assert_eq!(reconciled.utilization_bps(), Some(4_200.into()));
+    }
+
+    #[test]
+    fn it_ignores_logs_with_missing_topics() {
+        let log = Log {
+            topics: vec![H256::from(keccak256(
+                "LogCallWithSyncFee(address,address,uint256,bytes32)",
+            ))],
+            data: encode(&[
+                Token::Address(Address::zero()),
+                Token::Uint(42.into()),
+                Token::FixedBytes(vec![0; 32]),
+            ])
+            .into(),
+            ..Default::default()
+        };
+        assert_eq!(decode_fee_log(&log), None);
+    }
+}
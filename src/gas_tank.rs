@@ -0,0 +1,122 @@
+use ethers_contract::abigen;
+use ethers_core::{
+    abi::AbiEncode,
+    types::{transaction::eip2718::TypedTransaction, Address, TransactionRequest, U256},
+};
+
+use crate::{FeeToken, IntoChainId};
+
+abigen!(
+    GasTank,
+    r#"[
+        function depositNative(address sponsor) external payable
+        function depositToken(address sponsor, address token, uint256 amount) external
+    ]"#
+);
+
+abigen!(
+    Erc20,
+    r#"[
+        function approve(address spender, uint256 amount) external returns (bool)
+    ]"#
+);
+
+/// Builds transactions topping up a sponsor's Gas Tank balance.
+///
+/// The transactions are unsigned and have no sender, nonce or gas set, so
+/// they can be filled and sent by any middleware, e.g. after a balance alert.
+/// Gas Tank addresses are not known to the SDK, so must be supplied by the
+/// caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasTankTopUp {
+    chain_id: u64,
+    gas_tank: Address,
+    sponsor: Address,
+}
+
+impl GasTankTopUp {
+    /// Top up `sponsor`'s balance in the Gas Tank contract at `gas_tank` on
+    /// a chain
+    pub fn new(chain_id: impl IntoChainId, gas_tank: Address, sponsor: Address) -> Self {
+        Self {
+            chain_id: chain_id.into_chain_id(),
+            gas_tank,
+            sponsor,
+        }
+    }
+
+    fn tx(&self, to: Address, data: Vec<u8>) -> TransactionRequest {
+        TransactionRequest::new()
+            .chain_id(self.chain_id)
+            .to(to)
+            .data(data)
+    }
+
+    /// Deposit `amount` of the chain's native token
+    pub fn deposit_native(&self, amount: U256) -> TypedTransaction {
+        let call = DepositNativeCall {
+            sponsor: self.sponsor,
+        };
+        self.tx(self.gas_tank, call.encode()).value(amount).into()
+    }
+
+    /// Deposit `amount` of an ERC-20 token. The Gas Tank must first be
+    /// approved to spend it, see [`GasTankTopUp::approve_token`]
+    pub fn deposit_token(&self, token: Address, amount: U256) -> TypedTransaction {
+        let call = DepositTokenCall {
+            sponsor: self.sponsor,
+            token,
+            amount,
+        };
+        self.tx(self.gas_tank, call.encode()).into()
+    }
+
+    /// Approve the Gas Tank to spend `amount` of an ERC-20 token
+    pub fn approve_token(&self, token: Address, amount: U256) -> TypedTransaction {
+        let call = ApproveCall {
+            spender: self.gas_tank,
+            amount,
+        };
+        self.tx(token, call.encode()).into()
+    }
+
+    /// The transactions depositing `amount` of a fee token, in order. Native
+    /// deposits are a single transaction, token deposits are preceded by an
+    /// approval
+    pub fn deposit(&self, token: FeeToken, amount: U256) -> Vec<TypedTransaction> {
+        if token == FeeToken::default() {
+            vec![self.deposit_native(amount)]
+        } else {
+            vec![
+                self.approve_token(*token, amount),
+                self.deposit_token(*token, amount),
+            ]
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_core::types::NameOrAddress;
+
+    use super::*;
+
+    #[test]
+    fn it_builds_deposits() {
+        let top_up = GasTankTopUp::new(5, Address::repeat_byte(0x11), Address::repeat_byte(0x22));
+
+        let native = top_up.deposit(FeeToken::default(), 100u64.into());
+        assert_eq!(native.len(), 1);
+        assert_eq!(
+            native[0].to(),
+            Some(&NameOrAddress::Address(Address::repeat_byte(0x11)))
+        );
+        assert_eq!(native[0].value(), Some(&U256::from(100u64)));
+
+        let token = Address::repeat_byte(0x33);
+        let deposits = top_up.deposit(token.into(), 100u64.into());
+        assert_eq!(deposits.len(), 2);
+        assert_eq!(deposits[0].to(), Some(&NameOrAddress::Address(token)));
+        assert_eq!(deposits[1].data().map(|data| data.len()), Some(4 + 3 * 32));
+    }
+}
@@ -5,15 +5,31 @@
 #![forbid(unsafe_code)]
 #![forbid(where_clauses_object_safety)]
 
+/// macros for in-crate use
+#[macro_use]
+pub(crate) mod macros;
+
 /// Gelato Types
 pub mod types;
 pub use types::*;
 
+/// Named chains
+pub mod chain;
+pub use chain::{Chain, NetworkInfo};
+
+/// expiring in-memory caches
+pub(crate) mod cache;
 /// serialization convenience types
 pub(crate) mod ser;
+/// portable time sources
+pub(crate) mod time;
 /// lib utils
 pub(crate) mod utils;
-pub use utils::{get_forwarder, get_meta_box};
+pub use utils::{
+    get_forwarder, get_max_gas, get_meta_box, GELATO_RELAY_1BALANCE_CONCURRENT_ERC2771,
+    GELATO_RELAY_1BALANCE_ERC2771, GELATO_RELAY_CONCURRENT_ERC2771, GELATO_RELAY_ERC2771,
+    MAX_CALLDATA_SIZE,
+};
 
 mod client;
 pub use client::*;
@@ -25,12 +41,75 @@ pub mod rpc;
 pub mod builders;
 pub use builders::*;
 
+/// Time sources and deadline computation
+pub mod clock;
+
+/// Relay-context calldata suffix used by `callWithSyncFee` targets
+pub mod relay_context;
+
+/// Fee reconciliation from execution receipts
+pub mod fees;
+
+/// Reorg-safe confirmation of executions
+#[cfg(feature = "providers")]
+pub mod confirm;
+
+/// Gas Tank top-up transactions
+#[cfg(feature = "providers")]
+pub mod gas_tank;
+
+/// Submission with per-sponsor and per-chain in-flight limits
+pub mod dispatch;
+
+/// Sponsorship spend reports
+pub mod reports;
+
+/// Offline export of signed requests
+pub mod offline;
+
+/// Preflight checks before relaying on a chain
+pub mod preflight;
+
+/// Shared background polling of many tasks
+pub mod poller;
+
+/// Pre-submission simulation via Tenderly
+#[cfg(feature = "tenderly")]
+pub mod simulate;
+
+/// Serialized signing for signers that allow one operation at a time
+#[cfg(feature = "signers")]
+pub mod signing;
+
+/// Fee escalation for stuck forward requests
+#[cfg(feature = "signers")]
+pub mod escalate;
+
+/// Cross-SDK EIP-712 test vectors
+#[cfg(feature = "signers")]
+pub mod vectors;
+
+/// Blocking client for non-async code
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+/// Task status subscriptions over websockets
+#[cfg(feature = "ws")]
+pub mod ws;
+
+/// In-memory mock of the Gelato API for tests
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+/// Graceful shutdown for background components
+pub mod shutdown;
+
+/// Runtime-agnostic timers
+pub mod timer;
+
 /// Task status future
 pub mod task;
 pub use task::*;
 
 /// Re-export reqwest for convenience
 pub use reqwest;
-
-/// macros for in-crate use
-pub(crate) mod macros;
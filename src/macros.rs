@@ -1,35 +1,118 @@
-/// Make a POST request sending and expecting JSON.
-/// if JSON deser fails, emit a `WARN` level tracing event
-#[macro_export]
-macro_rules! json_post {
-    ($client:expr, $url:expr, $params:expr,) => {
-        json_post!($client, $url, $params)
+/// Emit a `WARN` level tracing event. No-op without the `tracing` feature
+#[cfg(feature = "tracing")]
+macro_rules! trace_warn {
+    ($($arg:tt)*) => { tracing::warn!($($arg)*) };
+}
+
+/// Emit a `WARN` level tracing event. No-op without the `tracing` feature
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_warn {
+    ($($arg:tt)*) => {};
+}
+
+/// Emit an `ERROR` level tracing event. No-op without the `tracing` feature
+#[cfg(feature = "tracing")]
+macro_rules! trace_error {
+    ($($arg:tt)*) => { tracing::error!($($arg)*) };
+}
+
+/// Emit an `ERROR` level tracing event. No-op without the `tracing` feature
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_error {
+    ($($arg:tt)*) => {};
+}
+
+/// Record a field on the current tracing span. No-op without the `tracing`
+/// feature
+#[cfg(feature = "tracing")]
+macro_rules! trace_record {
+    ($field:literal, $value:expr) => {
+        tracing::Span::current().record($field, &tracing::field::display($value));
     };
+}
 
-    ($client:expr, $url:expr, $params:expr) => {
-    {
-        let url = $url;
-        let resp = $client.post(url.clone()).json($params).send().await?;
+/// Record a field on the current tracing span. No-op without the `tracing`
+/// feature
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_record {
+    ($field:literal, $value:expr) => {
+        let _ = &$value;
+    };
+}
+
+/// Implement the public EIP-712 hash accessors of a request type, delegating
+/// to its [`Eip712`] impl
+///
+/// [`Eip712`]: ethers_core::types::transaction::eip712::Eip712
+macro_rules! eip712_accessors {
+    ($request:ty, $error:ty) => {
+        impl $request {
+            /// The EIP-712 digest of the request, i.e. the hash that is
+            /// signed. Precompute it to record or propose a request before
+            /// signing it
+            pub fn eip712_digest(&self) -> Result<ethers_core::types::H256, $error> {
+                ethers_core::types::transaction::eip712::Eip712::encode_eip712(self).map(Into::into)
+            }
+
+            /// The EIP-712 struct hash of the request, without its domain
+            pub fn struct_hash(&self) -> Result<ethers_core::types::H256, $error> {
+                ethers_core::types::transaction::eip712::Eip712::struct_hash(self).map(Into::into)
+            }
+
+            /// The EIP-712 domain separator of the request's verifying
+            /// contract on its chain
+            pub fn domain_separator(&self) -> Result<ethers_core::types::H256, $error> {
+                ethers_core::types::transaction::eip712::Eip712::domain(self)
+                    .map(|domain| domain.separator().into())
+            }
+        }
+    };
+}
+
+/// Make a POST request sending and expecting JSON, optionally with an extra
+/// header. if the status is not 2xx or JSON deser fails, emit a `WARN` level
+/// tracing event
+#[macro_export]
+macro_rules! json_post {
+    (@send $request:expr, $url:ident, $params:expr) => {{
+        let resp = $request.json($params).send().await?;
+        let status = resp.status();
         let text = resp.text().await?;
 
-        let result = serde_json::from_str(&text).map_err(Into::<$crate::client::ClientError>::into);
+        let result = $crate::client::read_response(status, &text);
 
         if result.is_err() {
-            tracing::warn!(
+            trace_warn!(
                 method = "POST",
-                url = %url,
+                url = %$url,
+                status = %status,
                 params = serde_json::to_string(&$params).unwrap().as_str(),
                 response = text.as_str(),
                 "Unexpected response from server"
             );
         }
         result
-    }
-}}
+    }};
+
+    ($client:expr, $url:expr, $params:expr,) => {
+        json_post!($client, $url, $params)
+    };
+
+    ($client:expr, $url:expr, $params:expr, header = ($name:expr, $value:expr)) => {{
+        let url = $url;
+        json_post!(@send $client.post(url.clone()).header($name, $value), url, $params)
+    }};
+
+    ($client:expr, $url:expr, $params:expr) => {{
+        let url = $url;
+        json_post!(@send $client.post(url.clone()), url, $params)
+    }};
+}
 
 #[macro_export]
 /// Make a GET request sending and expecting JSON.
-/// if JSON deser fails, emit a `WARN` level tracing event
+/// if the status is not 2xx or JSON deser fails, emit a `WARN` level tracing
+/// event
 macro_rules! json_get {
     ($client:expr, $url:expr, $expected:ty,) => {
         json_get!($client, $url, $expected)
@@ -48,14 +131,16 @@ macro_rules! json_get {
             req = req.json(&$body);
         }
         let resp = req.send().await?;
+        let status = resp.status();
         let text = resp.text().await?;
 
-        let result = serde_json::from_str::<$expected>(&text).map_err(Into::<$crate::client::ClientError>::into);
+        let result = $crate::client::read_response::<$expected>(status, &text);
 
         if result.is_err() {
-            tracing::warn!(
+            trace_warn!(
                 method = "GET",
                 url = %url,
+                status = %status,
                 response = text.as_str(),
                 "Unexpected response from server"
             );
@@ -64,7 +149,7 @@ macro_rules! json_get {
     }};
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "tracing"))]
 mod test {
     use std::str::FromStr;
 
@@ -73,7 +158,7 @@ mod test {
 
     use crate::ClientError;
 
-    struct MockClient<'a>(&'a str);
+    struct MockClient<'a>(&'a str, u16);
     impl<'a> MockClient<'a> {
         fn get(self, _: Url) -> Self {
             self
@@ -90,6 +175,9 @@ mod test {
         {
             Ok(self)
         }
+        fn status(&self) -> reqwest::StatusCode {
+            reqwest::StatusCode::from_u16(self.1).unwrap()
+        }
         async fn text(self) -> Result<String, ()> {
             Ok(self.0.to_owned())
         }
@@ -99,7 +187,7 @@ mod test {
     #[traced_test]
     async fn test_json_get_warn() -> Result<(), ()> {
         let url = reqwest::Url::from_str("http://example.com").unwrap();
-        json_get!(MockClient("hello world"), url.clone(), u64).unwrap_err();
+        json_get!(MockClient("hello world", 200), url.clone(), u64).unwrap_err();
         assert!(logs_contain("Unexpected response from server"));
         assert!(logs_contain("hello world"));
 
@@ -110,7 +198,7 @@ mod test {
     #[traced_test]
     async fn test_json_get_ok() -> Result<(), ()> {
         let url = reqwest::Url::from_str("http://example.com").unwrap();
-        let num = json_get!(MockClient("1312"), url.clone(), u64).unwrap();
+        let num = json_get!(MockClient("1312", 200), url.clone(), u64).unwrap();
         assert!(num == 1312);
         assert!(!logs_contain("Unexpected response from server"));
 
@@ -121,7 +209,8 @@ mod test {
     #[traced_test]
     async fn test_json_post_warn() -> Result<(), ()> {
         let url = reqwest::Url::from_str("http://example.com").unwrap();
-        let f: Result<u8, ClientError> = json_post!(MockClient("hello world"), url.clone(), &1312);
+        let f: Result<u8, ClientError> =
+            json_post!(MockClient("hello world", 200), url.clone(), &1312);
         assert!(f.is_err());
         assert!(logs_contain("Unexpected response from server"));
         assert!(logs_contain("hello world"));
@@ -133,10 +222,71 @@ mod test {
     #[traced_test]
     async fn test_json_post_ok() -> Result<(), ()> {
         let url = reqwest::Url::from_str("http://example.com").unwrap();
-        let num: u64 = json_post!(MockClient("1312"), url.clone(), &1312).unwrap();
+        let num: u64 = json_post!(MockClient("1312", 200), url.clone(), &1312).unwrap();
         assert!(num == 1312);
         assert!(!logs_contain("Unexpected response from server"));
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_json_get_reports_path() -> Result<(), ()> {
+        #[derive(Debug, serde::Deserialize)]
+        struct Outer {
+            #[allow(dead_code)]
+            data: Vec<u64>,
+        }
+
+        let url = reqwest::Url::from_str("http://example.com").unwrap();
+        let err = json_get!(MockClient(r#"{"data": [1, "two"]}"#, 200), url, Outer).unwrap_err();
+        match err {
+            ClientError::Deserialize { path, value, .. } => {
+                assert_eq!(path, "data[1]");
+                assert_eq!(value, Some(serde_json::json!("two")));
+            }
+            e => panic!("unexpected error {e:?}"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_json_get_api_error() -> Result<(), ()> {
+        let url = reqwest::Url::from_str("http://example.com").unwrap();
+        let body = r#"{"code": 400, "message": "Chain id 1234 is not supported"}"#;
+        let err = json_get!(MockClient(body, 200), url, Vec<u64>).unwrap_err();
+        assert_eq!(
+            err.api_error_kind(),
+            Some(crate::ApiErrorKind::UnsupportedChain)
+        );
+        match err {
+            ClientError::Api { code, message } => {
+                assert_eq!(code.as_deref(), Some("400"));
+                assert_eq!(message, "Chain id 1234 is not supported");
+            }
+            e => panic!("unexpected error {e:?}"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_json_get_http_error() -> Result<(), ()> {
+        let url = reqwest::Url::from_str("http://example.com").unwrap();
+        let err = json_get!(MockClient("bad gateway", 502), url.clone(), u64).unwrap_err();
+        assert_eq!(err.status(), Some(reqwest::StatusCode::BAD_GATEWAY));
+        match err {
+            ClientError::Http { body, .. } => assert_eq!(body, "bad gateway"),
+            e => panic!("unexpected error {e:?}"),
+        }
+
+        // error bodies are still classified
+        let body = r#"{"message": "Too many requests"}"#;
+        let err: ClientError = json_post!(MockClient(body, 429), url, &1312)
+            .map(|_: u64| ())
+            .unwrap_err();
+        assert_eq!(err.api_error_kind(), Some(crate::ApiErrorKind::RateLimited));
+
+        Ok(())
+    }
 }
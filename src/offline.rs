@@ -0,0 +1,215 @@
+use std::{fs, io, path::Path, time::UNIX_EPOCH};
+
+use ethers_core::types::{transaction::eip712::Eip712, Address, Signature, H256};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    rpc::{
+        ForwardRequest, ForwardRequestError, MetaTxRequest, MetaTxRequestError,
+        SignedForwardRequest, SignedMetaTxRequest,
+    },
+    ser::RsvSignature,
+};
+
+/// Version of the export file format
+pub const EXPORT_VERSION: u8 = 1;
+
+/// A fully built and signed request, ready for submission
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum SignedRequest {
+    /// A signed forward request
+    ForwardRequest(SignedForwardRequest),
+    /// A signed meta tx request
+    MetaTxRequest(SignedMetaTxRequest),
+}
+
+impl SignedRequest {
+    /// The chain id the request will be relayed on
+    pub fn chain_id(&self) -> u64 {
+        match self {
+            SignedRequest::ForwardRequest(req) => req.chain_id,
+            SignedRequest::MetaTxRequest(req) => req.chain_id,
+        }
+    }
+
+    /// The account paying for the request. For meta tx requests without a
+    /// sponsor, this is the user
+    pub fn sponsor(&self) -> Address {
+        match self {
+            SignedRequest::ForwardRequest(req) => req.sponsor,
+            SignedRequest::MetaTxRequest(req) => req.sponsor.unwrap_or(req.user),
+        }
+    }
+}
+
+impl From<SignedForwardRequest> for SignedRequest {
+    fn from(req: SignedForwardRequest) -> Self {
+        Self::ForwardRequest(req)
+    }
+}
+
+impl From<SignedMetaTxRequest> for SignedRequest {
+    fn from(req: SignedMetaTxRequest) -> Self {
+        Self::MetaTxRequest(req)
+    }
+}
+
+/// A signed request as written to an export file
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedRequest {
+    /// Export format version
+    pub version: u8,
+    /// Unix timestamp of the export
+    pub exported_at: u64,
+    /// The signed request
+    pub request: SignedRequest,
+}
+
+impl ExportedRequest {
+    /// Wrap a signed request for export
+    pub fn new(request: impl Into<SignedRequest>) -> Self {
+        Self {
+            version: EXPORT_VERSION,
+            exported_at: crate::time::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            request: request.into(),
+        }
+    }
+}
+
+/// Export a signed request as JSON, without submitting it
+pub fn to_json(request: impl Into<SignedRequest>) -> serde_json::Value {
+    serde_json::to_value(ExportedRequest::new(request)).expect("serialization is infallible")
+}
+
+/// Write a signed request to a JSON file, without submitting it. The file
+/// can be moved to a host with network access and submitted there
+pub fn write_json_file(
+    path: impl AsRef<Path>,
+    request: impl Into<SignedRequest>,
+) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(&ExportedRequest::new(request))?;
+    fs::write(path, json)
+}
+
+/// Errors importing a previously exported request
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    /// IO error reading the file
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    /// Malformed JSON
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+    /// Export file version not supported by this version of the SDK
+    #[error("Unsupported export version: {0}")]
+    UnsupportedVersion(Value),
+    /// Missing or unknown `typeId`
+    #[error("Unknown request type: {0:?}")]
+    UnknownType(Option<String>),
+    /// A required signature is missing
+    #[error("Missing signature: {0}")]
+    MissingSignature(&'static str),
+    /// Signature does not recover to the expected signer
+    #[error("{field} recovers to {actual:?}. Expected {expected:?}")]
+    WrongSigner {
+        /// Signature field
+        field: &'static str,
+        /// Address in the request
+        expected: Address,
+        /// Address recovered from the signature
+        actual: Address,
+    },
+    /// Signature could not be recovered
+    #[error("{0}")]
+    Signature(#[from] ethers_core::types::SignatureError),
+    /// Could not compute the forward request digest
+    #[error("{0}")]
+    ForwardRequest(#[from] ForwardRequestError),
+    /// Could not compute the meta tx request digest
+    #[error("{0}")]
+    MetaTxRequest(#[from] MetaTxRequestError),
+}
+
+fn signature(value: &Value, field: &'static str) -> Result<Option<Signature>, ImportError> {
+    match value.get(field) {
+        None | Some(Value::Null) => Ok(None),
+        Some(sig) => Ok(Some(
+            serde_json::from_value::<RsvSignature>(sig.clone())?.into(),
+        )),
+    }
+}
+
+fn verify(
+    field: &'static str,
+    signature: &Signature,
+    digest: [u8; 32],
+    expected: Address,
+) -> Result<(), ImportError> {
+    let actual = signature.recover(H256::from(digest))?;
+    if actual != expected {
+        return Err(ImportError::WrongSigner {
+            field,
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// Parse and verify a signed request exported with [`to_json`]. Bare signed
+/// requests (without the export envelope) are also accepted.
+///
+/// # Errors
+///
+/// If the JSON is malformed, or if any signature does not recover to the
+/// signer named in the request
+pub fn from_json(value: Value) -> Result<SignedRequest, ImportError> {
+    let value = match value.get("version") {
+        Some(version) if version.as_u64() != Some(EXPORT_VERSION.into()) => {
+            return Err(ImportError::UnsupportedVersion(version.clone()))
+        }
+        Some(_) => value.get("request").cloned().unwrap_or_default(),
+        None => value,
+    };
+
+    match value.get("typeId").and_then(Value::as_str) {
+        Some("ForwardRequest") => {
+            let req: ForwardRequest = serde_json::from_value(value.clone())?;
+            let sponsor_signature = signature(&value, "sponsorSignature")?
+                .ok_or(ImportError::MissingSignature("sponsorSignature"))?;
+            verify(
+                "sponsorSignature",
+                &sponsor_signature,
+                req.encode_eip712()?,
+                req.sponsor,
+            )?;
+            Ok(req.add_signature(sponsor_signature).into())
+        }
+        Some("MetaTxRequest") => {
+            let req: MetaTxRequest = serde_json::from_value(value.clone())?;
+            let digest = req.encode_eip712()?;
+            let user_signature = signature(&value, "userSignature")?
+                .ok_or(ImportError::MissingSignature("userSignature"))?;
+            verify("userSignature", &user_signature, digest, req.user)?;
+
+            let sponsor_signature = signature(&value, "sponsorSignature")?;
+            if let (Some(sig), Some(sponsor)) = (&sponsor_signature, req.sponsor) {
+                verify("sponsorSignature", sig, digest, sponsor)?;
+            }
+            Ok(req.add_signatures(user_signature, sponsor_signature).into())
+        }
+        other => Err(ImportError::UnknownType(other.map(ToOwned::to_owned))),
+    }
+}
+
+/// Read, parse and verify a signed request from a JSON file written by
+/// [`write_json_file`]
+pub fn read_json_file(path: impl AsRef<Path>) -> Result<SignedRequest, ImportError> {
+    from_json(serde_json::from_slice(&fs::read(path)?)?)
+}
@@ -0,0 +1,523 @@
+use futures_util::{future, stream, Stream, StreamExt};
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use crate::{
+    rpc::{self, Execution},
+    shutdown::ShutdownSignal,
+    task::{resolve, TaskError},
+    timer::{FuturesTimer, Timer},
+    GelatoApi, GelatoClient, TaskId,
+};
+
+const DEFAULT_RETRIES: usize = 5;
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(15);
+const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 16;
+
+// Terminal outcome of a tracked task. Stored instead of the task result, so
+// that every handle on the same task can build its own result
+#[derive(Debug, Clone)]
+enum Outcome {
+    Resolved(rpc::TransactionStatus),
+    TooManyRetries,
+    Shutdown,
+}
+
+#[derive(Debug, Default)]
+struct Tracked {
+    // number of live handles on this task
+    handles: usize,
+    // failed requests so far
+    failures: usize,
+    latest: Option<rpc::TransactionStatus>,
+    outcome: Option<Outcome>,
+    wakers: Vec<Waker>,
+}
+
+impl Tracked {
+    fn complete(&mut self, outcome: Outcome) {
+        self.outcome = Some(outcome);
+        std::mem::take(&mut self.wakers)
+            .into_iter()
+            .for_each(Waker::wake);
+    }
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    tasks: Mutex<HashMap<TaskId, Tracked>>,
+}
+
+impl Shared {
+    fn tasks(&self) -> MutexGuard<'_, HashMap<TaskId, Tracked>> {
+        self.tasks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A single background polling loop tracking many tasks.
+///
+/// Instead of one polling future per task, each tracked task id is fetched
+/// once per tick, no matter how many handles are waiting on it, and results
+/// are fanned out to lightweight [`PollerHandle`]s. Tasks whose handles have
+/// all been dropped are no longer polled.
+///
+/// The loop does nothing until [`TaskPoller::run`] is spawned on an executor.
+/// Failed requests are retried on the next tick, until the retry budget of
+/// the task is exhausted.
+#[derive(Debug, Clone)]
+pub struct TaskPoller {
+    client: GelatoClient,
+    shared: Arc<Shared>,
+    interval: Duration,
+    retries: usize,
+    max_concurrent_requests: usize,
+    timer: Arc<dyn Timer>,
+}
+
+impl TaskPoller {
+    /// Instantiate a poller using a client
+    pub fn new(client: GelatoClient) -> Self {
+        Self {
+            client,
+            shared: Default::default(),
+            interval: DEFAULT_INTERVAL,
+            retries: DEFAULT_RETRIES,
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+            timer: Arc::new(FuturesTimer),
+        }
+    }
+
+    /// Sets the polling interval (the time between ticks)
+    #[must_use]
+    pub fn polling_interval<T: Into<Duration>>(mut self, duration: T) -> Self {
+        self.interval = duration.into();
+        self
+    }
+
+    /// Set the number of failed requests tolerated per task before its
+    /// handles resolve to [`TaskError::TooManyRetries`]
+    #[must_use]
+    pub fn retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Set the maximum number of status requests in flight during a tick
+    #[must_use]
+    pub fn max_concurrent_requests(mut self, max: usize) -> Self {
+        self.max_concurrent_requests = max.max(1);
+        self
+    }
+
+    /// Set the timer used for delays between ticks. Defaults to
+    /// [`FuturesTimer`], which works with any runtime
+    #[must_use]
+    pub fn timer(mut self, timer: impl Timer + 'static) -> Self {
+        self.timer = Arc::new(timer);
+        self
+    }
+
+    /// Start tracking a task. Tracking the same task id multiple times
+    /// shares a single status request per tick
+    pub fn track(&self, task_id: TaskId) -> PollerHandle {
+        self.shared.tasks().entry(task_id).or_default().handles += 1;
+        PollerHandle {
+            task_id,
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// The number of distinct tasks currently tracked
+    pub fn tracked(&self) -> usize {
+        self.shared.tasks().len()
+    }
+
+    // ids of tasks that have not yet reached an outcome
+    fn pending(&self) -> Vec<TaskId> {
+        self.shared
+            .tasks()
+            .iter()
+            .filter(|(_, tracked)| tracked.outcome.is_none())
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    async fn tick(&self) {
+        let statuses: Vec<_> = stream::iter(self.pending())
+            .map(|task_id| async move { (task_id, self.client.get_task_status(task_id).await) })
+            .buffer_unordered(self.max_concurrent_requests)
+            .collect()
+            .await;
+
+        let mut tasks = self.shared.tasks();
+        for (task_id, status) in statuses {
+            // all handles were dropped while the request was in flight
+            let tracked = match tasks.get_mut(&task_id) {
+                Some(tracked) => tracked,
+                None => continue,
+            };
+
+            match status {
+                Ok(status) => {
+                    tracked.latest = Some(status.clone());
+                    if resolve(status.clone()).is_some() {
+                        tracked.complete(Outcome::Resolved(status));
+                    }
+                }
+                Err(e) => {
+                    trace_warn!(task_id = ?task_id, error = %e, "Error while polling task");
+                    tracked.failures += 1;
+                    if tracked.failures > self.retries {
+                        tracked.complete(Outcome::TooManyRetries);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Run the polling loop. Never resolves. Spawn it on an executor
+    pub async fn run(self) {
+        loop {
+            self.timer.sleep(self.interval).await;
+            self.tick().await;
+        }
+    }
+
+    /// Run the polling loop until shutdown is triggered. On shutdown, all
+    /// pending handles resolve to [`TaskError::Shutdown`] and the signal is
+    /// released
+    pub async fn run_with_shutdown(self, signal: ShutdownSignal) {
+        loop {
+            let sleep = self.timer.sleep(self.interval);
+            if let future::Either::Right(_) = future::select(sleep, signal.triggered()).await {
+                break;
+            }
+            self.tick().await;
+        }
+
+        self.shared
+            .tasks()
+            .values_mut()
+            .filter(|tracked| tracked.outcome.is_none())
+            .for_each(|tracked| tracked.complete(Outcome::Shutdown));
+    }
+}
+
+/// A handle on a task tracked by a [`TaskPoller`]. Resolves when the task
+/// reaches a terminal state
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct PollerHandle {
+    task_id: TaskId,
+    shared: Arc<Shared>,
+}
+
+impl PollerHandle {
+    /// The id of the tracked task
+    pub fn task_id(&self) -> TaskId {
+        self.task_id
+    }
+
+    /// The most recent status fetched by the poller, if any
+    pub fn latest(&self) -> Option<rpc::TransactionStatus> {
+        self.shared
+            .tasks()
+            .get(&self.task_id)
+            .and_then(|tracked| tracked.latest.clone())
+    }
+}
+
+impl Future for PollerHandle {
+    type Output = Result<Execution, TaskError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut tasks = self.shared.tasks();
+        let tracked = tasks
+            .get_mut(&self.task_id)
+            .expect("tracked while handle is live");
+
+        match tracked.outcome.clone() {
+            Some(Outcome::Resolved(status)) => {
+                Poll::Ready(resolve(status).expect("outcome is terminal"))
+            }
+            Some(Outcome::TooManyRetries) => Poll::Ready(Err(TaskError::TooManyRetries)),
+            Some(Outcome::Shutdown) => Poll::Ready(Err(TaskError::Shutdown {
+                task_id: self.task_id,
+            })),
+            None => {
+                if !tracked.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                    tracked.wakers.push(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for PollerHandle {
+    fn drop(&mut self) {
+        let mut tasks = self.shared.tasks();
+        if let Some(tracked) = tasks.get_mut(&self.task_id) {
+            tracked.handles -= 1;
+            if tracked.handles == 0 {
+                tasks.remove(&self.task_id);
+            }
+        }
+    }
+}
+
+/// A set of tasks polled together, yielding each outcome as it happens.
+///
+/// Like `FuturesUnordered` over [`GelatoTask`]s, but each tick requests the
+/// status of at most `requests_per_tick` tasks, in round-robin order, so that
+/// hundreds of tasks share one request budget. Unlike [`TaskPoller`], the set
+/// is driven by awaiting [`TaskSet::next`], and needs no background loop.
+///
+/// Awaiting [`TaskSet::next`] is cancel-safe. Responses to in-flight
+/// requests are discarded, and the tasks are polled again later.
+///
+/// [`GelatoTask`]: crate::GelatoTask
+#[derive(Debug)]
+pub struct TaskSet<'a, A: ?Sized = GelatoClient> {
+    client: &'a A,
+    // tasks pending or ready, i.e. with an outcome not yet yielded
+    members: HashSet<TaskId>,
+    pending: VecDeque<TaskId>,
+    failures: HashMap<TaskId, usize>,
+    ready: VecDeque<(TaskId, Result<Execution, TaskError>)>,
+    ticked: bool,
+    interval: Duration,
+    retries: usize,
+    requests_per_tick: usize,
+    max_concurrent_requests: usize,
+    timer: Arc<dyn Timer>,
+}
+
+impl<'a, A> TaskSet<'a, A>
+where
+    A: GelatoApi + ?Sized,
+{
+    /// Instantiate an empty set using a client
+    pub fn new(client: &'a A) -> Self {
+        Self {
+            client,
+            members: Default::default(),
+            pending: Default::default(),
+            failures: Default::default(),
+            ready: Default::default(),
+            ticked: false,
+            interval: DEFAULT_INTERVAL,
+            retries: DEFAULT_RETRIES,
+            requests_per_tick: usize::MAX,
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+            timer: Arc::new(FuturesTimer),
+        }
+    }
+
+    /// Sets the polling interval (the time between ticks)
+    #[must_use]
+    pub fn polling_interval<T: Into<Duration>>(mut self, duration: T) -> Self {
+        self.interval = duration.into();
+        self
+    }
+
+    /// Set the number of failed requests tolerated per task before it
+    /// resolves to [`TaskError::TooManyRetries`]
+    #[must_use]
+    pub fn retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Set the maximum number of status requests per tick. Defaults to
+    /// every pending task
+    #[must_use]
+    pub fn requests_per_tick(mut self, max: usize) -> Self {
+        self.requests_per_tick = max.max(1);
+        self
+    }
+
+    /// Set the maximum number of status requests in flight during a tick
+    #[must_use]
+    pub fn max_concurrent_requests(mut self, max: usize) -> Self {
+        self.max_concurrent_requests = max.max(1);
+        self
+    }
+
+    /// Set the timer used for delays between ticks. Defaults to
+    /// [`FuturesTimer`], which works with any runtime
+    #[must_use]
+    pub fn timer(mut self, timer: impl Timer + 'static) -> Self {
+        self.timer = Arc::new(timer);
+        self
+    }
+
+    /// Add a task to the set. Adding a task whose outcome has not been
+    /// yielded yet has no effect
+    pub fn push(&mut self, task_id: TaskId) {
+        if self.members.insert(task_id) {
+            self.pending.push_back(task_id);
+        }
+    }
+
+    /// The number of tasks with an outcome not yet yielded
+    pub fn len(&self) -> usize {
+        self.pending.len() + self.ready.len()
+    }
+
+    /// True if every outcome has been yielded
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The next task outcome. `None` once the set is empty. The first tick
+    /// happens immediately, later ticks one polling interval apart
+    pub async fn next(&mut self) -> Option<(TaskId, Result<Execution, TaskError>)> {
+        loop {
+            if let Some(outcome) = self.ready.pop_front() {
+                self.members.remove(&outcome.0);
+                return Some(outcome);
+            }
+            if self.pending.is_empty() {
+                return None;
+            }
+            if self.ticked {
+                self.timer.sleep(self.interval).await;
+            }
+            self.tick().await;
+        }
+    }
+
+    /// Yield outcomes as a stream, until the set is empty
+    pub fn into_stream(self) -> impl Stream<Item = (TaskId, Result<Execution, TaskError>)> + 'a {
+        stream::unfold(self, |mut set| async move {
+            set.next().await.map(|outcome| (outcome, set))
+        })
+    }
+
+    async fn tick(&mut self) {
+        let batch = self.requests_per_tick.min(self.pending.len());
+        let client = self.client;
+        let statuses: Vec<_> = stream::iter(self.pending.iter().take(batch).copied())
+            .map(|task_id| async move { (task_id, client.get_task_status(task_id).await) })
+            .buffer_unordered(self.max_concurrent_requests)
+            .collect()
+            .await;
+
+        // the next tick starts with the tasks not polled in this one
+        self.pending.rotate_left(batch);
+        self.ticked = true;
+        let mut resolved = HashSet::new();
+        for (task_id, status) in statuses {
+            let outcome = match status {
+                Ok(status) => match resolve(status) {
+                    Some(outcome) => outcome,
+                    None => continue,
+                },
+                Err(e) => {
+                    trace_warn!(task_id = ?task_id, error = %e, "Error while polling task");
+                    let failures = self.failures.entry(task_id).or_default();
+                    *failures += 1;
+                    if *failures <= self.retries {
+                        continue;
+                    }
+                    Err(TaskError::TooManyRetries)
+                }
+            };
+            resolved.insert(task_id);
+            self.failures.remove(&task_id);
+            self.ready.push_back((task_id, outcome));
+        }
+        if !resolved.is_empty() {
+            self.pending.retain(|id| !resolved.contains(id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_util::FutureExt;
+
+    use super::*;
+
+    #[test]
+    fn it_coalesces_handles() {
+        let poller = TaskPoller::new(GelatoClient::default());
+        let task_id = TaskId::from(ethers_core::types::H256::repeat_byte(1));
+
+        let first = poller.track(task_id);
+        let mut second = poller.track(task_id);
+        assert_eq!(poller.tracked(), 1);
+        assert!(first.latest().is_none());
+        assert!((&mut second).now_or_never().is_none());
+
+        drop(first);
+        assert_eq!(poller.tracked(), 1);
+        drop(second);
+        assert_eq!(poller.tracked(), 0);
+    }
+
+    #[tokio::test]
+    async fn it_yields_outcomes_as_they_happen() {
+        use crate::testing::{status, MockGelatoClient};
+        use rpc::TaskState;
+
+        let slow = TaskId::from(ethers_core::types::H256::repeat_byte(1));
+        let fast = TaskId::from(ethers_core::types::H256::repeat_byte(2));
+        let mock = MockGelatoClient::new();
+        mock.push_statuses(
+            slow,
+            [
+                status(slow, TaskState::CheckPending),
+                status(slow, TaskState::ExecSuccess),
+            ],
+        )
+        .push_statuses(fast, [status(fast, TaskState::ExecSuccess)]);
+
+        let mut set = TaskSet::new(&mock)
+            .polling_interval(Duration::from_millis(1))
+            .requests_per_tick(1);
+        set.push(slow);
+        set.push(fast);
+        set.push(fast);
+        assert_eq!(set.len(), 2);
+
+        let (first, outcome) = set.next().await.unwrap();
+        assert_eq!(first, fast);
+        assert!(outcome.is_ok());
+        assert_eq!(set.next().await.unwrap().0, slow);
+        assert!(set.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn it_yields_each_outcome_once() {
+        use crate::testing::{status, MockGelatoClient};
+        use rpc::TaskState;
+
+        let first = TaskId::from(ethers_core::types::H256::repeat_byte(1));
+        let second = TaskId::from(ethers_core::types::H256::repeat_byte(2));
+        let mock = MockGelatoClient::new();
+        mock.push_statuses(first, [status(first, TaskState::ExecSuccess)])
+            .push_statuses(second, [status(second, TaskState::ExecSuccess)]);
+
+        // a long interval, so the test only passes if the first tick is
+        // immediate
+        let mut set = TaskSet::new(&mock).polling_interval(Duration::from_secs(3600));
+        set.push(first);
+        set.push(second);
+        assert_eq!(set.next().await.unwrap().0, first);
+
+        // resolved but not yet yielded
+        set.push(second);
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.next().await.unwrap().0, second);
+        assert!(set.next().await.is_none());
+    }
+}
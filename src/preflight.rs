@@ -0,0 +1,185 @@
+use std::fmt;
+
+#[cfg(feature = "providers")]
+use ethers_core::types::Address;
+#[cfg(feature = "providers")]
+use ethers_providers::Middleware;
+
+use crate::{get_forwarder, get_meta_box, FeeToken, GelatoClient, IntoChainId};
+
+/// Outcome of a single preflight check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckOutcome {
+    /// The check passed
+    Pass,
+    /// The check failed, with a reason
+    Fail(String),
+    /// The check was not run, with a reason
+    Skipped(String),
+}
+
+/// A named preflight check and its outcome
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckResult {
+    /// What was checked
+    pub name: &'static str,
+    /// The outcome
+    pub outcome: CheckOutcome,
+}
+
+/// The results of a preflight run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreflightReport {
+    /// Chain id that was checked
+    pub chain_id: u64,
+    /// Individual check results, in the order they ran
+    pub checks: Vec<CheckResult>,
+}
+
+impl PreflightReport {
+    /// True if no check failed. Skipped checks do not count as failures
+    pub fn passed(&self) -> bool {
+        !self
+            .checks
+            .iter()
+            .any(|c| matches!(c.outcome, CheckOutcome::Fail(_)))
+    }
+
+    fn push(&mut self, name: &'static str, outcome: CheckOutcome) {
+        self.checks.push(CheckResult { name, outcome });
+    }
+}
+
+impl fmt::Display for PreflightReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Preflight report for chain {}", self.chain_id)?;
+        for check in &self.checks {
+            match &check.outcome {
+                CheckOutcome::Pass => writeln!(f, "  [PASS] {}", check.name)?,
+                CheckOutcome::Fail(reason) => writeln!(f, "  [FAIL] {}: {}", check.name, reason)?,
+                CheckOutcome::Skipped(reason) => {
+                    writeln!(f, "  [SKIP] {}: {}", check.name, reason)?
+                }
+            }
+        }
+        write!(f, "Result: {}", if self.passed() { "PASS" } else { "FAIL" })
+    }
+}
+
+/// Preflight checks to run before a deployment starts relaying on a chain
+#[derive(Debug, Clone)]
+pub struct Preflight<'a> {
+    client: &'a GelatoClient,
+    chain_id: u64,
+    fee_token: FeeToken,
+}
+
+impl<'a> Preflight<'a> {
+    /// Instantiate preflight checks for a chain, paying fees in the native
+    /// token
+    pub fn new(client: &'a GelatoClient, chain_id: impl IntoChainId) -> Self {
+        Self {
+            client,
+            chain_id: chain_id.into_chain_id(),
+            fee_token: Default::default(),
+        }
+    }
+
+    /// Set the fee token to check. Defaults to the native token
+    #[must_use]
+    pub fn fee_token(mut self, fee_token: impl Into<FeeToken>) -> Self {
+        self.fee_token = fee_token.into();
+        self
+    }
+
+    /// Run all checks that do not require a provider
+    pub async fn run(&self) -> PreflightReport {
+        let mut report = PreflightReport {
+            chain_id: self.chain_id,
+            checks: vec![],
+        };
+
+        match self.client.get_gelato_relay_chains().await {
+            Ok(chains) => {
+                report.push("API reachable", CheckOutcome::Pass);
+                report.push(
+                    "Chain supported by relay",
+                    if chains.contains(&self.chain_id) {
+                        CheckOutcome::Pass
+                    } else {
+                        CheckOutcome::Fail("not in the relay chain list".to_owned())
+                    },
+                );
+            }
+            Err(e) => {
+                report.push("API reachable", CheckOutcome::Fail(e.to_string()));
+                report.push(
+                    "Chain supported by relay",
+                    CheckOutcome::Skipped("API unreachable".to_owned()),
+                );
+            }
+        }
+
+        report.push(
+            "Forwarder known",
+            match get_forwarder(self.chain_id) {
+                Some(_) => CheckOutcome::Pass,
+                None => CheckOutcome::Fail("no forwarder address for chain".to_owned()),
+            },
+        );
+        report.push(
+            "MetaBox known",
+            match get_meta_box(self.chain_id) {
+                Some(_) => CheckOutcome::Pass,
+                None => CheckOutcome::Fail("no metabox address for chain".to_owned()),
+            },
+        );
+
+        report.push(
+            "Fee oracle available",
+            match self.client.get_oracle_chains().await {
+                Ok(oracles) if oracles.contains(&self.chain_id) => CheckOutcome::Pass,
+                Ok(_) => CheckOutcome::Fail("no oracle for chain".to_owned()),
+                Err(e) => CheckOutcome::Fail(e.to_string()),
+            },
+        );
+
+        report.push(
+            "Fee token whitelisted",
+            match self
+                .client
+                .is_payment_token(self.chain_id, self.fee_token)
+                .await
+            {
+                Ok(true) => CheckOutcome::Pass,
+                Ok(false) => CheckOutcome::Fail(format!(
+                    "{:?} is not a payment token on this chain",
+                    *self.fee_token
+                )),
+                Err(e) => CheckOutcome::Fail(e.to_string()),
+            },
+        );
+
+        report
+    }
+
+    /// Run all checks, additionally checking that the sponsor account holds
+    /// a native token balance
+    #[cfg(feature = "providers")]
+    pub async fn run_with_sponsor<M: Middleware>(
+        &self,
+        provider: &M,
+        sponsor: Address,
+    ) -> PreflightReport {
+        let mut report = self.run().await;
+        report.push(
+            "Sponsor balance",
+            match provider.get_balance(sponsor, None).await {
+                Ok(balance) if !balance.is_zero() => CheckOutcome::Pass,
+                Ok(_) => CheckOutcome::Fail(format!("{sponsor:?} has no balance")),
+                Err(e) => CheckOutcome::Fail(e.to_string()),
+            },
+        );
+        report
+    }
+}
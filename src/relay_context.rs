@@ -0,0 +1,78 @@
+use ethers_core::types::{Address, Bytes, U256};
+
+use crate::FeeToken;
+
+/// Length in bytes of the relay-context calldata suffix:
+/// `feeCollector (20) | feeToken (20) | fee (32)`
+pub const RELAY_CONTEXT_SUFFIX_LEN: usize = 72;
+
+/// Fee context appended to the calldata of `callWithSyncFee` calls. Target
+/// contracts inheriting Gelato's `GelatoRelayContext` read it from the end of
+/// `msg.data` to pay the relay
+///
+/// <https://docs.gelato.network/developer-services/relay/non-erc-2771/callwithsyncfee>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelayContext {
+    /// The address fees must be transferred to
+    pub fee_collector: Address,
+    /// The token fees are paid in
+    pub fee_token: FeeToken,
+    /// The fee amount
+    pub fee: U256,
+}
+
+impl RelayContext {
+    /// Encode the context as a calldata suffix, i.e.
+    /// `abi.encodePacked(feeCollector, feeToken, fee)`
+    pub fn encode(&self) -> [u8; RELAY_CONTEXT_SUFFIX_LEN] {
+        let mut suffix = [0u8; RELAY_CONTEXT_SUFFIX_LEN];
+        suffix[..20].copy_from_slice(self.fee_collector.as_bytes());
+        suffix[20..40].copy_from_slice(self.fee_token.as_bytes());
+        self.fee.to_big_endian(&mut suffix[40..]);
+        suffix
+    }
+
+    /// Append the context to calldata, as the relay does when forwarding the
+    /// call
+    pub fn append_to(&self, data: &[u8]) -> Bytes {
+        [data, &self.encode()].concat().into()
+    }
+
+    /// Split calldata into the original calldata and its relay context.
+    /// `None` if the calldata is too short to carry a context
+    pub fn strip(calldata: &[u8]) -> Option<(&[u8], Self)> {
+        let split = calldata.len().checked_sub(RELAY_CONTEXT_SUFFIX_LEN)?;
+        let (data, suffix) = calldata.split_at(split);
+        let context = Self {
+            fee_collector: Address::from_slice(&suffix[..20]),
+            fee_token: Address::from_slice(&suffix[20..40]).into(),
+            fee: U256::from_big_endian(&suffix[40..]),
+        };
+        Some((data, context))
+    }
+
+    /// Decode the relay context from the end of calldata
+    pub fn decode(calldata: &[u8]) -> Option<Self> {
+        Self::strip(calldata).map(|(_, context)| context)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_round_trips_the_suffix() {
+        let context = RelayContext {
+            fee_collector: Address::repeat_byte(0xfe),
+            fee_token: Default::default(),
+            fee: U256::from(1_000_000u64),
+        };
+        let data = [0xde, 0xad, 0xbe, 0xef];
+
+        let calldata = context.append_to(&data);
+        assert_eq!(calldata.len(), data.len() + RELAY_CONTEXT_SUFFIX_LEN);
+        assert_eq!(RelayContext::strip(&calldata), Some((&data[..], context)));
+        assert_eq!(RelayContext::decode(&data), None);
+    }
+}
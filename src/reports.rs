@@ -0,0 +1,211 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+use ethers_core::types::{Address, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::{fees::ActualFee, FeeToken, TaskId};
+
+/// The spend of a single sponsored execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpendRecord {
+    /// Chain the execution occurred on
+    pub chain_id: u64,
+    /// Token the fee was paid in
+    pub fee_token: FeeToken,
+    /// Fee charged
+    #[serde(with = "crate::ser::decimal_u256_ser")]
+    pub fee: U256,
+    /// Gelato task id
+    pub task_id: TaskId,
+    /// Unix timestamp of the execution
+    pub timestamp: u64,
+}
+
+impl SpendRecord {
+    /// Create a spend record from a reconciled fee
+    pub fn from_actual_fee(chain_id: u64, fee: &ActualFee, timestamp: u64) -> Self {
+        Self {
+            chain_id,
+            fee_token: fee.fee_token,
+            fee: fee.fee,
+            task_id: fee.task_id,
+            timestamp,
+        }
+    }
+}
+
+/// One row of a spend report. Fields that are not grouped on are `None`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportRow {
+    /// Chain id, if grouping by chain
+    pub chain_id: Option<u64>,
+    /// Fee token, if grouping by token
+    pub fee_token: Option<FeeToken>,
+    /// Window start as a unix timestamp, if grouping by time window
+    pub window_start: Option<u64>,
+    /// Number of executions in the group
+    pub executions: usize,
+    /// Total fees in the group
+    #[serde(with = "crate::ser::decimal_u256_ser")]
+    pub total_fee: U256,
+}
+
+// chain id, fee token, window start
+type GroupKey = (Option<u64>, Option<Address>, Option<u64>);
+
+/// Configuration for a spend report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReportBuilder {
+    by_chain: bool,
+    by_token: bool,
+    window: Option<u64>,
+    since: Option<u64>,
+    until: Option<u64>,
+}
+
+impl ReportBuilder {
+    /// Group rows by chain id
+    pub fn by_chain(mut self) -> Self {
+        self.by_chain = true;
+        self
+    }
+
+    /// Group rows by fee token
+    pub fn by_token(mut self) -> Self {
+        self.by_token = true;
+        self
+    }
+
+    /// Group rows into time windows of `secs` seconds, aligned to the unix
+    /// epoch. E.g. `86400` for daily rows
+    pub fn window(mut self, secs: u64) -> Self {
+        self.window = Some(secs.max(1));
+        self
+    }
+
+    /// Only include records at or after this unix timestamp
+    pub fn since(mut self, timestamp: u64) -> Self {
+        self.since = Some(timestamp);
+        self
+    }
+
+    /// Only include records before this unix timestamp
+    pub fn until(mut self, timestamp: u64) -> Self {
+        self.until = Some(timestamp);
+        self
+    }
+
+    /// Build a report from spend records
+    pub fn build<'a>(self, records: impl IntoIterator<Item = &'a SpendRecord>) -> SpendReport {
+        let mut groups: BTreeMap<GroupKey, (usize, U256)> = BTreeMap::new();
+
+        for record in records {
+            if self.since.is_some_and(|since| record.timestamp < since)
+                || self.until.is_some_and(|until| record.timestamp >= until)
+            {
+                continue;
+            }
+            let key = (
+                self.by_chain.then_some(record.chain_id),
+                self.by_token.then_some(*record.fee_token),
+                self.window.map(|w| record.timestamp - record.timestamp % w),
+            );
+            let entry = groups.entry(key).or_default();
+            entry.0 += 1;
+            entry.1 = entry.1.saturating_add(record.fee);
+        }
+
+        SpendReport {
+            rows: groups
+                .into_iter()
+                .map(
+                    |((chain_id, fee_token, window_start), (executions, total_fee))| ReportRow {
+                        chain_id,
+                        fee_token: fee_token.map(Into::into),
+                        window_start,
+                        executions,
+                        total_fee,
+                    },
+                )
+                .collect(),
+        }
+    }
+}
+
+/// Sponsorship spend, aggregated per chain, fee token and/or time window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SpendReport {
+    /// Report rows, ordered by chain, token, then window
+    pub rows: Vec<ReportRow>,
+}
+
+impl SpendReport {
+    /// Configure a new report
+    pub fn builder() -> ReportBuilder {
+        Default::default()
+    }
+
+    /// Export the report as JSON
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Export the report as CSV, with a header row. Empty cells indicate
+    /// fields that were not grouped on
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("chain_id,fee_token,window_start,executions,total_fee\n");
+        for row in &self.rows {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{}",
+                row.chain_id.map(|c| c.to_string()).unwrap_or_default(),
+                row.fee_token
+                    .map(|t| ethers_core::utils::to_checksum(&t, None))
+                    .unwrap_or_default(),
+                row.window_start.map(|w| w.to_string()).unwrap_or_default(),
+                row.executions,
+                row.total_fee,
+            );
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn record(chain_id: u64, fee: u64, timestamp: u64) -> SpendRecord {
+        SpendRecord {
+            chain_id,
+            fee_token: Default::default(),
+            fee: fee.into(),
+            task_id: Default::default(),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn it_groups_spend() {
+        let records = vec![record(1, 10, 0), record(1, 5, 90_000), record(137, 7, 10)];
+
+        let report = SpendReport::builder()
+            .by_chain()
+            .window(86_400)
+            .build(&records);
+        assert_eq!(report.rows.len(), 3);
+        assert_eq!(report.rows[0].chain_id, Some(1));
+        assert_eq!(report.rows[0].total_fee, 10.into());
+        assert_eq!(report.rows[1].window_start, Some(86_400));
+
+        let report = SpendReport::builder().by_token().until(50).build(&records);
+        assert_eq!(report.rows.len(), 1);
+        assert_eq!(report.rows[0].executions, 2);
+        assert_eq!(report.rows[0].total_fee, 17.into());
+        assert!(report
+            .to_csv()
+            .ends_with(",0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE,,2,17\n"));
+    }
+}
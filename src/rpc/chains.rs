@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::FeeToken;
+
 /// Response to Relay chains request. Contains a list of chain ids supported
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -10,10 +12,52 @@ pub(crate) struct RelayChainsResponse {
 
 impl RelayChainsResponse {
     pub(crate) fn relays_iter(&self) -> impl Iterator<Item = u64> + '_ {
-        self.relays.iter().map(|s| s.parse().unwrap())
+        self.relays.iter().filter_map(|s| s.parse().ok())
     }
 
     pub(crate) fn relays(&self) -> Vec<u64> {
         self.relays_iter().collect()
     }
 }
+
+/// Response to oracle chains request. Contains a list of chain ids with an
+/// active fee oracle
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OracleChainsResponse {
+    /// The chain ids with oracles
+    oracles: Vec<String>,
+}
+
+impl OracleChainsResponse {
+    pub(crate) fn oracles(&self) -> Vec<u64> {
+        self.oracles.iter().filter_map(|s| s.parse().ok()).collect()
+    }
+}
+
+/// Response to payment tokens request. Contains the tokens the fee oracle
+/// accepts on a chain
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PaymentTokensResponse {
+    /// The accepted payment tokens
+    payment_tokens: Vec<FeeToken>,
+}
+
+impl PaymentTokensResponse {
+    pub(crate) fn payment_tokens(self) -> Vec<FeeToken> {
+        self.payment_tokens
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_skips_malformed_chain_ids() {
+        let resp: RelayChainsResponse =
+            serde_json::from_str(r#"{"relays": ["1", "five", "137"]}"#).unwrap();
+        assert_eq!(resp.relays(), vec![1, 137]);
+    }
+}
@@ -1,18 +1,162 @@
+use ethers_core::types::transaction::eip712::EIP712Domain;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
 
-use ethers_core::types::H256;
+use crate::TaskId;
+
+/// The `eth_signTypedData_v4` payload of a request: its EIP-712 domain, the
+/// types parsed from its `type_string`, and the serialized request as the
+/// message. Fields of the type missing from the serialized request are
+/// encoded as zero, as in the request's struct hash
+pub(crate) fn typed_data_json<T: Serialize>(
+    type_string: &str,
+    domain: &EIP712Domain,
+    request: &T,
+) -> Value {
+    let (primary_type, fields) = type_string
+        .split_once('(')
+        .expect("valid EIP-712 type string");
+    let fields: Vec<(&str, &str)> = fields
+        .trim_end_matches(')')
+        .split(',')
+        .filter_map(|field| field.split_once(' '))
+        .collect();
+
+    let request = serde_json::to_value(request).unwrap_or_default();
+    let message: Map<String, Value> = fields
+        .iter()
+        .map(|(ty, name)| {
+            let value = match request.get(name) {
+                Some(value) if !value.is_null() => value.clone(),
+                _ => match *ty {
+                    "address" => json!(format!("{:?}", ethers_core::types::Address::zero())),
+                    "bool" => json!(false),
+                    "bytes32" => json!(format!("{:?}", ethers_core::types::H256::zero())),
+                    "bytes" => json!("0x"),
+                    _ => json!("0"),
+                },
+            };
+            (name.to_string(), value)
+        })
+        .collect();
+
+    let mut domain_type = vec![
+        json!({ "name": "name", "type": "string" }),
+        json!({ "name": "version", "type": "string" }),
+        json!({ "name": "chainId", "type": "uint256" }),
+        json!({ "name": "verifyingContract", "type": "address" }),
+    ];
+    let mut domain_json = json!({
+        "name": domain.name,
+        "version": domain.version,
+        "chainId": domain.chain_id.to_string(),
+        "verifyingContract": ethers_core::utils::to_checksum(&domain.verifying_contract, None),
+    });
+    if let Some(salt) = domain.salt {
+        domain_type.push(json!({ "name": "salt", "type": "bytes32" }));
+        domain_json["salt"] = json!(format!("0x{}", hex::encode(salt)));
+    }
+
+    let primary_fields: Vec<Value> = fields
+        .iter()
+        .map(|(ty, name)| json!({ "name": name, "type": ty }))
+        .collect();
+    let mut types = Map::new();
+    types.insert("EIP712Domain".to_owned(), Value::Array(domain_type));
+    types.insert(primary_type.to_owned(), Value::Array(primary_fields));
+
+    json!({
+        "types": types,
+        "primaryType": primary_type,
+        "domain": domain_json,
+        "message": message,
+    })
+}
+
+/// A relay v2 request body, authenticated with the sponsor's API key
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Sponsored<'a, T> {
+    #[serde(flatten)]
+    pub(crate) request: &'a T,
+    pub(crate) sponsor_api_key: &'a str,
+}
+
+/// A relay v2 ERC-2771 request body using concurrent replay protection
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Concurrent<'a, T> {
+    #[serde(flatten)]
+    pub(crate) request: &'a T,
+    pub(crate) is_concurrent: bool,
+}
 
 /// Response to relay request, contains an ID for the task
+///
+/// Deserialization tolerates unknown fields, task ids with or without a `0x`
+/// prefix, and responses wrapped in a `data` envelope
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", from = "RelayResponseRepr")]
+#[non_exhaustive]
 pub struct RelayResponse {
     /// The task ID
-    task_id: H256,
+    task_id: TaskId,
 }
 
 impl RelayResponse {
     /// The task ID
-    pub fn task_id(&self) -> H256 {
+    pub fn task_id(&self) -> TaskId {
         self.task_id
     }
 }
+
+impl From<TaskId> for RelayResponse {
+    fn from(task_id: TaskId) -> Self {
+        Self { task_id }
+    }
+}
+
+#[derive(Deserialize)]
+struct FlatRelayResponse {
+    #[serde(rename = "taskId", alias = "taskID", alias = "task_id")]
+    task_id: TaskId,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RelayResponseRepr {
+    Flat(FlatRelayResponse),
+    Wrapped { data: FlatRelayResponse },
+}
+
+impl From<RelayResponseRepr> for RelayResponse {
+    fn from(repr: RelayResponseRepr) -> Self {
+        let (RelayResponseRepr::Flat(resp) | RelayResponseRepr::Wrapped { data: resp }) = repr;
+        Self {
+            task_id: resp.task_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TASK_ID: &str = "ce52ae7a6a3032848d76b161ac4c131fa995dcc67e3be5392dfb8466275d6679";
+
+    #[test]
+    fn it_parses_response_variations() {
+        let expected: TaskId = TASK_ID.parse().unwrap();
+        let responses = [
+            serde_json::json!({ "taskId": format!("0x{TASK_ID}") }),
+            serde_json::json!({ "taskId": TASK_ID, "status": "ok" }),
+            serde_json::json!({ "data": { "taskId": format!("0x{TASK_ID}") } }),
+            serde_json::json!({ "taskID": TASK_ID }),
+        ];
+
+        for resp in responses {
+            let resp: RelayResponse = serde_json::from_value(resp).unwrap();
+            assert_eq!(resp.task_id(), expected);
+        }
+    }
+}
@@ -0,0 +1,666 @@
+use ethers_core::{
+    abi::{self, Token},
+    types::{
+        transaction::eip712::{EIP712Domain, Eip712},
+        Address, Bytes, Signature, H256,
+    },
+    utils::keccak256,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ser::RsvSignature,
+    utils::{
+        GELATO_RELAY_1BALANCE_CONCURRENT_ERC2771, GELATO_RELAY_1BALANCE_ERC2771,
+        GELATO_RELAY_CONCURRENT_ERC2771, GELATO_RELAY_ERC2771,
+    },
+    FeeToken,
+};
+
+const SPONSORED_CALL_ERC2771_TYPE: &str = "SponsoredCallERC2771(uint256 chainId,address target,bytes data,address user,uint256 userNonce,uint256 userDeadline)";
+
+const SPONSORED_CALL_CONCURRENT_ERC2771_TYPE: &str = "SponsoredCallConcurrentERC2771(uint256 chainId,address target,bytes data,address user,bytes32 userSalt,uint256 userDeadline)";
+
+const CALL_WITH_SYNC_FEE_ERC2771_TYPE: &str = "CallWithSyncFeeERC2771(uint256 chainId,address target,bytes data,address user,uint256 userNonce,uint256 userDeadline)";
+
+const CALL_WITH_SYNC_FEE_CONCURRENT_ERC2771_TYPE: &str = "CallWithSyncFeeConcurrentERC2771(uint256 chainId,address target,bytes data,address user,bytes32 userSalt,uint256 userDeadline)";
+
+/// A random 32-byte salt for concurrent ERC-2771 requests
+pub fn random_salt() -> H256 {
+    H256::from(rand::random::<[u8; 32]>())
+}
+
+/// A deterministic salt derived from a seed, e.g. an application-level
+/// request id. The same seed always produces the same salt, so resubmitting
+/// a request with the same seed is rejected as a replay
+pub fn salt_from_seed(seed: impl AsRef<[u8]>) -> H256 {
+    keccak256(seed).into()
+}
+
+/// ERC-2771 request errors
+#[derive(Debug, thiserror::Error)]
+pub enum Erc2771Error {
+    /// Wrong Signer
+    #[error(
+        "Wrong signer. Expected {expected:?}. Attempted to sign with key belonging to: {actual:?}"
+    )]
+    WrongSigner {
+        /// User in the struct
+        expected: Address,
+        /// Address belonging to the signer
+        actual: Address,
+    },
+    /// Signer errored
+    #[error("{0}")]
+    SignerError(Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+/// Gelato relay sponsored ERC-2771 call
+///
+/// <https://docs.gelato.network/developer-services/relay/erc-2771-recommended/sponsoredcallerc2771>
+///
+/// Fees are paid from the sponsor's 1Balance, identified by the client's
+/// API key. The user signs the request, and the relay's ERC-2771 contract
+/// checks the signature, the user's sequential nonce and the deadline before
+/// calling `target` with `user` appended to the calldata, so ERC-2771
+/// targets see it as `_msgSender()`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SponsoredCallErc2771 {
+    /// Chain id
+    pub chain_id: u64,
+    /// Address of dApp's smart contract to call.
+    #[serde(serialize_with = "crate::ser::serialize_checksum_addr")]
+    pub target: Address,
+    /// Payload for `target`.
+    pub data: Bytes,
+    /// EOA of dapp's user
+    #[serde(serialize_with = "crate::ser::serialize_checksum_addr")]
+    pub user: Address,
+    /// The user's nonce in the relay contract
+    pub user_nonce: u64,
+    /// Unix timestamp after which the request may not be executed
+    pub user_deadline: u64,
+}
+
+impl Eip712 for SponsoredCallErc2771 {
+    type Error = Erc2771Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(EIP712Domain {
+            name: "GelatoRelay1BalanceERC2771".to_owned(),
+            version: "1".to_owned(),
+            chain_id: self.chain_id.into(),
+            verifying_contract: *GELATO_RELAY_1BALANCE_ERC2771,
+            salt: None,
+        })
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(SPONSORED_CALL_ERC2771_TYPE))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let encoded_request = abi::encode(&[
+            Token::FixedBytes(Self::type_hash()?.to_vec()),
+            Token::Uint(self.chain_id.into()),
+            Token::Address(self.target),
+            Token::FixedBytes(keccak256(&self.data).to_vec()),
+            Token::Address(self.user),
+            Token::Uint(self.user_nonce.into()),
+            Token::Uint(self.user_deadline.into()),
+        ]);
+        Ok(keccak256(encoded_request))
+    }
+}
+
+eip712_accessors!(SponsoredCallErc2771, Erc2771Error);
+
+impl SponsoredCallErc2771 {
+    /// Fill the request with the user signature
+    #[cfg(feature = "signers")]
+    pub(crate) fn add_signature(self, user_signature: Signature) -> SignedSponsoredCallErc2771 {
+        SignedSponsoredCallErc2771 {
+            req: self,
+            user_signature: user_signature.into(),
+        }
+    }
+
+    /// Sign the request with the specified signer
+    ///
+    /// Errors if the signer does not match the user in the struct
+    #[cfg(feature = "signers")]
+    pub async fn sign<S>(self, signer: &S) -> Result<SignedSponsoredCallErc2771, Erc2771Error>
+    where
+        S: ethers_signers::Signer,
+        S::Error: 'static,
+    {
+        let signature = sign_as_user(&self, self.user, signer).await?;
+        Ok(self.add_signature(signature))
+    }
+}
+
+/// Signed Gelato relay sponsored ERC-2771 call
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedSponsoredCallErc2771 {
+    /// Request Details
+    #[serde(flatten)]
+    req: SponsoredCallErc2771,
+
+    /// EIP-712 signature over the request
+    user_signature: RsvSignature,
+}
+
+impl SignedSponsoredCallErc2771 {
+    /// Get the attached user signature
+    pub fn user_signature(&self) -> Signature {
+        *self.user_signature
+    }
+}
+
+impl std::ops::Deref for SignedSponsoredCallErc2771 {
+    type Target = SponsoredCallErc2771;
+
+    fn deref(&self) -> &Self::Target {
+        &self.req
+    }
+}
+
+// sign a request on behalf of `user`, checking the signer is the user
+#[cfg(feature = "signers")]
+async fn sign_as_user<T, S>(
+    payload: &T,
+    user: Address,
+    signer: &S,
+) -> Result<Signature, Erc2771Error>
+where
+    T: Eip712 + Send + Sync,
+    S: ethers_signers::Signer,
+    S::Error: 'static,
+{
+    let signer_addr = signer.address();
+    if signer_addr != user {
+        return Err(Erc2771Error::WrongSigner {
+            expected: user,
+            actual: signer_addr,
+        });
+    }
+
+    signer
+        .sign_typed_data(payload)
+        .await
+        .map_err(|e| Erc2771Error::SignerError(Box::new(e)))
+}
+
+/// Gelato relay sponsored ERC-2771 call with concurrent replay protection
+///
+/// <https://docs.gelato.network/developer-services/relay/erc-2771-recommended/sponsoredcallerc2771>
+///
+/// Instead of a sequential user nonce, replay protection relies on a unique
+/// `user_salt` and a `user_deadline`, so a user may have many requests in
+/// flight at once, executed in any order. The relay appends `user` to the
+/// calldata, so ERC-2771 targets see it as `_msgSender()`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SponsoredCallConcurrentErc2771 {
+    /// Chain id
+    pub chain_id: u64,
+    /// Address of dApp's smart contract to call.
+    #[serde(serialize_with = "crate::ser::serialize_checksum_addr")]
+    pub target: Address,
+    /// Payload for `target`.
+    pub data: Bytes,
+    /// EOA of dapp's user
+    #[serde(serialize_with = "crate::ser::serialize_checksum_addr")]
+    pub user: Address,
+    /// Unique salt. Requests with a salt already used by the user are
+    /// rejected
+    pub user_salt: H256,
+    /// Unix timestamp after which the request may not be executed
+    pub user_deadline: u64,
+}
+
+impl Eip712 for SponsoredCallConcurrentErc2771 {
+    type Error = Erc2771Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(EIP712Domain {
+            name: "GelatoRelay1BalanceConcurrentERC2771".to_owned(),
+            version: "1".to_owned(),
+            chain_id: self.chain_id.into(),
+            verifying_contract: *GELATO_RELAY_1BALANCE_CONCURRENT_ERC2771,
+            salt: None,
+        })
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(SPONSORED_CALL_CONCURRENT_ERC2771_TYPE))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let encoded_request = abi::encode(&[
+            Token::FixedBytes(Self::type_hash()?.to_vec()),
+            Token::Uint(self.chain_id.into()),
+            Token::Address(self.target),
+            Token::FixedBytes(keccak256(&self.data).to_vec()),
+            Token::Address(self.user),
+            Token::FixedBytes(self.user_salt.as_bytes().to_vec()),
+            Token::Uint(self.user_deadline.into()),
+        ]);
+        Ok(keccak256(encoded_request))
+    }
+}
+
+eip712_accessors!(SponsoredCallConcurrentErc2771, Erc2771Error);
+
+impl SponsoredCallConcurrentErc2771 {
+    /// Fill the request with the user signature
+    #[cfg(feature = "signers")]
+    pub(crate) fn add_signature(
+        self,
+        user_signature: Signature,
+    ) -> SignedSponsoredCallConcurrentErc2771 {
+        SignedSponsoredCallConcurrentErc2771 {
+            req: self,
+            user_signature: user_signature.into(),
+        }
+    }
+
+    /// Sign the request with the specified signer
+    ///
+    /// Errors if the signer does not match the user in the struct
+    #[cfg(feature = "signers")]
+    pub async fn sign<S>(
+        self,
+        signer: &S,
+    ) -> Result<SignedSponsoredCallConcurrentErc2771, Erc2771Error>
+    where
+        S: ethers_signers::Signer,
+        S::Error: 'static,
+    {
+        let signature = sign_as_user(&self, self.user, signer).await?;
+        Ok(self.add_signature(signature))
+    }
+}
+
+/// Signed Gelato relay sponsored ERC-2771 call with concurrent replay
+/// protection
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedSponsoredCallConcurrentErc2771 {
+    /// Request Details
+    #[serde(flatten)]
+    req: SponsoredCallConcurrentErc2771,
+
+    /// EIP-712 signature over the request
+    user_signature: RsvSignature,
+}
+
+impl SignedSponsoredCallConcurrentErc2771 {
+    /// Get the attached user signature
+    pub fn user_signature(&self) -> Signature {
+        *self.user_signature
+    }
+}
+
+impl std::ops::Deref for SignedSponsoredCallConcurrentErc2771 {
+    type Target = SponsoredCallConcurrentErc2771;
+
+    fn deref(&self) -> &Self::Target {
+        &self.req
+    }
+}
+
+/// Gelato relay ERC-2771 call with sync fee
+///
+/// <https://docs.gelato.network/developer-services/relay/erc-2771-recommended/callwithsyncfeeerc2771>
+///
+/// The target pays the relay synchronously, during execution, in
+/// `fee_token`. The user signs the request over the `GelatoRelayERC2771`
+/// domain. `fee_token` and `is_relay_context` are not signed. The relay
+/// appends `user` to the calldata, after the relay context if any, so
+/// ERC-2771 targets see it as `_msgSender()`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CallWithSyncFeeErc2771 {
+    /// Chain id
+    pub chain_id: u64,
+    /// Address of dApp's smart contract to call.
+    #[serde(serialize_with = "crate::ser::serialize_checksum_addr")]
+    pub target: Address,
+    /// Payload for `target`.
+    pub data: Bytes,
+    /// EOA of dapp's user
+    #[serde(serialize_with = "crate::ser::serialize_checksum_addr")]
+    pub user: Address,
+    /// The user's nonce in the relay contract
+    pub user_nonce: u64,
+    /// Unix timestamp after which the request may not be executed
+    pub user_deadline: u64,
+    /// Token the target pays the fee in
+    pub fee_token: FeeToken,
+    /// Whether the relay appends the relay context to the calldata
+    pub is_relay_context: bool,
+}
+
+impl Eip712 for CallWithSyncFeeErc2771 {
+    type Error = Erc2771Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(EIP712Domain {
+            name: "GelatoRelayERC2771".to_owned(),
+            version: "1".to_owned(),
+            chain_id: self.chain_id.into(),
+            verifying_contract: *GELATO_RELAY_ERC2771,
+            salt: None,
+        })
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(CALL_WITH_SYNC_FEE_ERC2771_TYPE))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let encoded_request = abi::encode(&[
+            Token::FixedBytes(Self::type_hash()?.to_vec()),
+            Token::Uint(self.chain_id.into()),
+            Token::Address(self.target),
+            Token::FixedBytes(keccak256(&self.data).to_vec()),
+            Token::Address(self.user),
+            Token::Uint(self.user_nonce.into()),
+            Token::Uint(self.user_deadline.into()),
+        ]);
+        Ok(keccak256(encoded_request))
+    }
+}
+
+eip712_accessors!(CallWithSyncFeeErc2771, Erc2771Error);
+
+impl CallWithSyncFeeErc2771 {
+    /// Fill the request with the user signature
+    #[cfg(feature = "signers")]
+    pub(crate) fn add_signature(self, user_signature: Signature) -> SignedCallWithSyncFeeErc2771 {
+        SignedCallWithSyncFeeErc2771 {
+            req: self,
+            user_signature: user_signature.into(),
+        }
+    }
+
+    /// Sign the request with the specified signer
+    ///
+    /// Errors if the signer does not match the user in the struct
+    #[cfg(feature = "signers")]
+    pub async fn sign<S>(self, signer: &S) -> Result<SignedCallWithSyncFeeErc2771, Erc2771Error>
+    where
+        S: ethers_signers::Signer,
+        S::Error: 'static,
+    {
+        let signature = sign_as_user(&self, self.user, signer).await?;
+        Ok(self.add_signature(signature))
+    }
+}
+
+/// Signed Gelato relay ERC-2771 call with sync fee
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedCallWithSyncFeeErc2771 {
+    /// Request Details
+    #[serde(flatten)]
+    req: CallWithSyncFeeErc2771,
+
+    /// EIP-712 signature over the request
+    user_signature: RsvSignature,
+}
+
+impl SignedCallWithSyncFeeErc2771 {
+    /// Get the attached user signature
+    pub fn user_signature(&self) -> Signature {
+        *self.user_signature
+    }
+}
+
+impl std::ops::Deref for SignedCallWithSyncFeeErc2771 {
+    type Target = CallWithSyncFeeErc2771;
+
+    fn deref(&self) -> &Self::Target {
+        &self.req
+    }
+}
+
+/// Gelato relay ERC-2771 call with sync fee and concurrent replay protection
+///
+/// <https://docs.gelato.network/developer-services/relay/erc-2771-recommended/callwithsyncfeeerc2771>
+///
+/// Like [`CallWithSyncFeeErc2771`], signed over the
+/// `GelatoRelayConcurrentERC2771` domain, with a unique `user_salt` in place
+/// of the sequential user nonce
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CallWithSyncFeeConcurrentErc2771 {
+    /// Chain id
+    pub chain_id: u64,
+    /// Address of dApp's smart contract to call.
+    #[serde(serialize_with = "crate::ser::serialize_checksum_addr")]
+    pub target: Address,
+    /// Payload for `target`.
+    pub data: Bytes,
+    /// EOA of dapp's user
+    #[serde(serialize_with = "crate::ser::serialize_checksum_addr")]
+    pub user: Address,
+    /// Unique salt. Requests with a salt already used by the user are
+    /// rejected
+    pub user_salt: H256,
+    /// Unix timestamp after which the request may not be executed
+    pub user_deadline: u64,
+    /// Token the target pays the fee in
+    pub fee_token: FeeToken,
+    /// Whether the relay appends the relay context to the calldata
+    pub is_relay_context: bool,
+}
+
+impl Eip712 for CallWithSyncFeeConcurrentErc2771 {
+    type Error = Erc2771Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(EIP712Domain {
+            name: "GelatoRelayConcurrentERC2771".to_owned(),
+            version: "1".to_owned(),
+            chain_id: self.chain_id.into(),
+            verifying_contract: *GELATO_RELAY_CONCURRENT_ERC2771,
+            salt: None,
+        })
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(CALL_WITH_SYNC_FEE_CONCURRENT_ERC2771_TYPE))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let encoded_request = abi::encode(&[
+            Token::FixedBytes(Self::type_hash()?.to_vec()),
+            Token::Uint(self.chain_id.into()),
+            Token::Address(self.target),
+            Token::FixedBytes(keccak256(&self.data).to_vec()),
+            Token::Address(self.user),
+            Token::FixedBytes(self.user_salt.as_bytes().to_vec()),
+            Token::Uint(self.user_deadline.into()),
+        ]);
+        Ok(keccak256(encoded_request))
+    }
+}
+
+eip712_accessors!(CallWithSyncFeeConcurrentErc2771, Erc2771Error);
+
+impl CallWithSyncFeeConcurrentErc2771 {
+    /// Fill the request with the user signature
+    #[cfg(feature = "signers")]
+    pub(crate) fn add_signature(
+        self,
+        user_signature: Signature,
+    ) -> SignedCallWithSyncFeeConcurrentErc2771 {
+        SignedCallWithSyncFeeConcurrentErc2771 {
+            req: self,
+            user_signature: user_signature.into(),
+        }
+    }
+
+    /// Sign the request with the specified signer
+    ///
+    /// Errors if the signer does not match the user in the struct
+    #[cfg(feature = "signers")]
+    pub async fn sign<S>(
+        self,
+        signer: &S,
+    ) -> Result<SignedCallWithSyncFeeConcurrentErc2771, Erc2771Error>
+    where
+        S: ethers_signers::Signer,
+        S::Error: 'static,
+    {
+        let signature = sign_as_user(&self, self.user, signer).await?;
+        Ok(self.add_signature(signature))
+    }
+}
+
+/// Signed Gelato relay ERC-2771 call with sync fee and concurrent replay
+/// protection
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedCallWithSyncFeeConcurrentErc2771 {
+    /// Request Details
+    #[serde(flatten)]
+    req: CallWithSyncFeeConcurrentErc2771,
+
+    /// EIP-712 signature over the request
+    user_signature: RsvSignature,
+}
+
+impl SignedCallWithSyncFeeConcurrentErc2771 {
+    /// Get the attached user signature
+    pub fn user_signature(&self) -> Signature {
+        *self.user_signature
+    }
+}
+
+impl std::ops::Deref for SignedCallWithSyncFeeConcurrentErc2771 {
+    type Target = CallWithSyncFeeConcurrentErc2771;
+
+    fn deref(&self) -> &Self::Target {
+        &self.req
+    }
+}
+
+#[cfg(all(test, feature = "signers"))]
+mod test {
+    use ethers::signers::LocalWallet;
+    use ethers_signers::Signer;
+
+    use super::*;
+
+    #[test]
+    fn it_derives_salts() {
+        assert_eq!(salt_from_seed("a"), salt_from_seed("a"));
+        assert_ne!(salt_from_seed("a"), salt_from_seed("b"));
+        assert_ne!(random_salt(), random_salt());
+    }
+
+    #[tokio::test]
+    async fn it_signs_sequential_calls() {
+        let wallet: LocalWallet = "11".repeat(32).parse().unwrap();
+        let req = SponsoredCallErc2771 {
+            chain_id: 5,
+            target: Address::repeat_byte(0x22),
+            data: vec![1, 2, 3].into(),
+            user: wallet.address(),
+            user_nonce: 3,
+            user_deadline: 1_700_000_000,
+        };
+        let digest = H256::from(req.encode_eip712().unwrap());
+
+        let signed = req.sign(&wallet).await.unwrap();
+        assert_eq!(
+            signed.user_signature().recover(digest).unwrap(),
+            wallet.address()
+        );
+        assert_eq!(
+            serde_json::to_value(&signed).unwrap()["userNonce"],
+            serde_json::json!(3)
+        );
+    }
+
+    #[tokio::test]
+    async fn it_signs_concurrent_calls() {
+        let wallet: LocalWallet = "11".repeat(32).parse().unwrap();
+        let req = SponsoredCallConcurrentErc2771 {
+            chain_id: 5,
+            target: Address::repeat_byte(0x22),
+            data: vec![1, 2, 3].into(),
+            user: wallet.address(),
+            user_salt: random_salt(),
+            user_deadline: 1_700_000_000,
+        };
+        let digest = H256::from(req.encode_eip712().unwrap());
+
+        let signed = req.clone().sign(&wallet).await.unwrap();
+        assert_eq!(
+            signed.user_signature().recover(digest).unwrap(),
+            wallet.address()
+        );
+
+        let other: LocalWallet = "22".repeat(32).parse().unwrap();
+        assert!(matches!(
+            req.sign(&other).await,
+            Err(Erc2771Error::WrongSigner { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_signs_sync_fee_calls() {
+        let wallet: LocalWallet = "11".repeat(32).parse().unwrap();
+        let req = CallWithSyncFeeErc2771 {
+            chain_id: 5,
+            target: Address::repeat_byte(0x22),
+            data: vec![1, 2, 3].into(),
+            user: wallet.address(),
+            user_nonce: 3,
+            user_deadline: 1_700_000_000,
+            fee_token: FeeToken::default(),
+            is_relay_context: true,
+        };
+        assert_eq!(req.domain().unwrap().name, "GelatoRelayERC2771");
+        let digest = H256::from(req.encode_eip712().unwrap());
+
+        // the fee token and relay context flag are not signed
+        let mut unsigned_fields = req.clone();
+        unsigned_fields.is_relay_context = false;
+        assert_eq!(H256::from(unsigned_fields.encode_eip712().unwrap()), digest);
+
+        let signed = req.sign(&wallet).await.unwrap();
+        assert_eq!(
+            signed.user_signature().recover(digest).unwrap(),
+            wallet.address()
+        );
+        assert_eq!(
+            serde_json::to_value(&signed).unwrap()["isRelayContext"],
+            serde_json::json!(true)
+        );
+
+        let req = CallWithSyncFeeConcurrentErc2771 {
+            chain_id: 5,
+            target: Address::repeat_byte(0x22),
+            data: vec![1, 2, 3].into(),
+            user: wallet.address(),
+            user_salt: random_salt(),
+            user_deadline: 1_700_000_000,
+            fee_token: FeeToken::default(),
+            is_relay_context: true,
+        };
+        assert_eq!(req.domain().unwrap().name, "GelatoRelayConcurrentERC2771");
+        let digest = H256::from(req.encode_eip712().unwrap());
+        let signed = req.sign(&wallet).await.unwrap();
+        assert_eq!(
+            signed.user_signature().recover(digest).unwrap(),
+            wallet.address()
+        );
+    }
+}
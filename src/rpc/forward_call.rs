@@ -1,4 +1,6 @@
-use ethers_core::types::{Address, Bytes, U64};
+use ethers_core::types::{
+    transaction::eip2718::TypedTransaction, Address, Bytes, NameOrAddress, TransactionRequest, U64,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::FeeToken;
@@ -28,3 +30,73 @@ pub struct ForwardCall {
     #[serde(with = "crate::ser::decimal_u64_ser")]
     pub gas: U64,
 }
+
+impl ForwardCall {
+    /// Derive a forward call from an ethers transaction, paying fees in
+    /// `fee_token`. The chain id defaults to 1 (ethereum) if the transaction
+    /// does not specify one.
+    ///
+    /// Returns `None` if the transaction has no `to` address, has no gas limit
+    /// or one above `u64::MAX`, or carries a non-zero value, which forward
+    /// calls can't send
+    pub fn from_typed_transaction(
+        tx: &TypedTransaction,
+        fee_token: impl Into<FeeToken>,
+    ) -> Option<Self> {
+        let target = match tx.to()? {
+            NameOrAddress::Address(target) => *target,
+            NameOrAddress::Name(_) => return None,
+        };
+        if tx.value().map_or(false, |value| !value.is_zero()) {
+            return None;
+        }
+        Some(Self {
+            chain_id: tx.chain_id().map(|id| id.as_u64()).unwrap_or(1),
+            target,
+            data: tx.data().cloned().unwrap_or_default(),
+            fee_token: fee_token.into(),
+            gas: u64::try_from(*tx.gas()?).ok()?.into(),
+        })
+    }
+
+    /// Derive a forward call from an ethers transaction request, paying fees
+    /// in `fee_token`. The chain id defaults to 1 (ethereum) if the
+    /// transaction does not specify one.
+    ///
+    /// Returns `None` if the transaction has no `to` address, has no gas limit
+    /// or one above `u64::MAX`, or carries a non-zero value, which forward
+    /// calls can't send
+    pub fn from_transaction_request(
+        tx: &TransactionRequest,
+        fee_token: impl Into<FeeToken>,
+    ) -> Option<Self> {
+        Self::from_typed_transaction(&tx.clone().into(), fee_token)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_core::types::U256;
+
+    use super::*;
+    use crate::rpc::RelayRequest;
+
+    #[test]
+    fn it_converts_transactions() {
+        let tx = TransactionRequest::new()
+            .to(Address::repeat_byte(0x22))
+            .data(vec![1, 2, 3])
+            .gas(100_000u64);
+        let call = ForwardCall::from_transaction_request(&tx, Address::repeat_byte(0x33)).unwrap();
+        assert_eq!(call.target, Address::repeat_byte(0x22));
+        assert_eq!(call.gas, 100_000u64.into());
+
+        let too_much_gas = tx.clone().gas(U256::from(u64::MAX) + 1);
+        assert!(ForwardCall::from_transaction_request(&too_much_gas, Address::zero()).is_none());
+
+        let with_value = tx.value(1u64);
+        assert!(ForwardCall::from_transaction_request(&with_value, Address::zero()).is_none());
+        let relay = RelayRequest::from_transaction_request(&with_value, Address::zero(), 1u64);
+        assert!(relay.is_none());
+    }
+}
@@ -2,14 +2,18 @@ use ethers_core::{
     abi::{self, Token},
     types::{
         transaction::eip712::{EIP712Domain, Eip712},
-        Address, Bytes, Signature, U64,
+        Address, Bytes, Signature, SignatureError, H256, U256,
     },
     utils::keccak256,
 };
 
 use serde::{Deserialize, Serialize};
 
-use crate::{ser::RsvSignature, utils::get_forwarder, FeeToken, PaymentType};
+use crate::{
+    ser::{ForwardRequestTypeId, RsvSignature},
+    utils::get_forwarder,
+    FeeToken, PaymentType,
+};
 
 const FORWARD_REQUEST_TYPE: &str = "ForwardRequest(uint256 chainId,address target,bytes data,address feeToken,uint256 paymentType,uint256 maxFee,uint256 gas,address sponsor,uint256 sponsorChainId,uint256 nonce,bool enforceSponsorNonce,bool enforceSponsorNonceOrdering)";
 
@@ -42,11 +46,11 @@ pub struct ForwardRequest {
     /// Type identifier for Gelato's payment. Can be 1, 2 or 3.
     pub payment_type: PaymentType,
     /// Maximum fee sponsor is willing to pay Gelato Executors
-    #[serde(with = "crate::ser::decimal_u64_ser")]
-    pub max_fee: U64,
+    #[serde(with = "crate::ser::decimal_u256_ser")]
+    pub max_fee: U256,
     /// Gas limit
-    #[serde(with = "crate::ser::decimal_u64_ser")]
-    pub gas: U64,
+    #[serde(with = "crate::ser::decimal_u256_ser")]
+    pub gas: U256,
     /// EOA address that pays Gelato Executors.
     #[serde(serialize_with = "crate::ser::serialize_checksum_addr")]
     pub sponsor: Address,
@@ -86,6 +90,9 @@ pub enum ForwardRequestError {
     /// InappropriatePaymentType
     #[error("Payment type Synchronous may not be used with this request")]
     InappropriatePaymentType,
+    /// Signature could not be recovered
+    #[error("{0}")]
+    Signature(#[from] SignatureError),
 }
 
 impl Eip712 for ForwardRequest {
@@ -116,8 +123,8 @@ impl Eip712 for ForwardRequest {
             Token::FixedBytes(keccak256(&self.data).to_vec()),
             Token::Address(*self.fee_token),
             Token::Uint((self.payment_type as u8).into()),
-            Token::Uint(self.max_fee.as_u64().into()),
-            Token::Uint(self.gas.as_u64().into()),
+            Token::Uint(self.max_fee),
+            Token::Uint(self.gas),
             Token::Address(self.sponsor),
             Token::Uint(self.sponsor_chain_id.into()),
             Token::Uint(self.nonce.into()),
@@ -129,11 +136,24 @@ impl Eip712 for ForwardRequest {
     }
 }
 
+eip712_accessors!(ForwardRequest, ForwardRequestError);
+
 impl ForwardRequest {
+    /// The full EIP-712 typed data of the request (domain, types and
+    /// message), as accepted by `eth_signTypedData_v4`. Lets browser wallets
+    /// and external signing services produce the sponsor signature
+    pub fn typed_data_json(&self) -> Result<serde_json::Value, ForwardRequestError> {
+        Ok(crate::rpc::typed_data_json(
+            FORWARD_REQUEST_TYPE,
+            &self.domain()?,
+            self,
+        ))
+    }
+
     /// Fill ForwardRequest with sponsor signature and return full request struct
-    fn add_signature(self, sponsor_signature: Signature) -> SignedForwardRequest {
+    pub(crate) fn add_signature(self, sponsor_signature: Signature) -> SignedForwardRequest {
         SignedForwardRequest {
-            type_id: "ForwardRequest",
+            type_id: ForwardRequestTypeId,
             req: self,
             sponsor_signature: sponsor_signature.into(),
         }
@@ -142,6 +162,7 @@ impl ForwardRequest {
     /// Sign the request with the specified signer
     ///
     /// Errors if the signer does not match the sponsor in the struct
+    #[cfg(feature = "signers")]
     pub async fn sign<S>(self, signer: &S) -> Result<SignedForwardRequest, ForwardRequestError>
     where
         S: ethers_signers::Signer,
@@ -169,6 +190,7 @@ impl ForwardRequest {
     /// Sponsor the request with the specified signer
     ///
     /// Overwrites the existing sponsor
+    #[cfg(feature = "signers")]
     pub async fn sponsor<S>(
         mut self,
         sponsor: &S,
@@ -200,7 +222,7 @@ impl ForwardRequest {
 #[serde(rename_all = "camelCase")]
 pub struct SignedForwardRequest {
     /// must be exactly "ForwardRequest"
-    type_id: &'static str,
+    type_id: ForwardRequestTypeId,
 
     /// Forward Request Details
     #[serde(flatten)]
@@ -211,13 +233,51 @@ pub struct SignedForwardRequest {
 }
 
 impl SignedForwardRequest {
+    /// Attach a sponsor signature produced elsewhere, e.g. by a hardware
+    /// wallet or a remote signing service, over the request's
+    /// [`eip712_digest`]. The signature is not checked, see
+    /// [`Self::new_verified`]
+    ///
+    /// [`eip712_digest`]: ForwardRequest::eip712_digest
+    pub fn new(req: ForwardRequest, sponsor_signature: Signature) -> Self {
+        req.add_signature(sponsor_signature)
+    }
+
+    /// Attach a sponsor signature produced elsewhere, and [`verify`] it
+    ///
+    /// [`verify`]: Self::verify
+    pub fn new_verified(
+        req: ForwardRequest,
+        sponsor_signature: Signature,
+    ) -> Result<Self, ForwardRequestError> {
+        let signed = Self::new(req, sponsor_signature);
+        signed.verify()?;
+        Ok(signed)
+    }
+
     /// Get the attached sponsor signature
     pub fn sponsor_signature(&self) -> Signature {
         *self.sponsor_signature
     }
 
+    /// Recover the signer of the sponsor signature from the EIP-712 digest,
+    /// and check that it is the `sponsor`. Use this to validate requests
+    /// submitted by third parties before forwarding them
+    pub fn verify(&self) -> Result<(), ForwardRequestError> {
+        let digest = self.req.encode_eip712()?;
+        let actual = self.sponsor_signature.recover(H256::from(digest))?;
+        if actual != self.sponsor {
+            return Err(ForwardRequestError::WrongSigner {
+                expected: self.sponsor,
+                actual,
+            });
+        }
+        Ok(())
+    }
+
     /// Re-sponsor this request. Get a new signed version with the sponsor set
     /// to the identity of the new signer
+    #[cfg(feature = "signers")]
     pub async fn responsor<S>(&self, signer: &S) -> Result<Self, ForwardRequestError>
     where
         S: ethers_signers::Signer,
@@ -235,7 +295,7 @@ impl std::ops::Deref for SignedForwardRequest {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "signers"))]
 mod test {
     use super::*;
     use ethers::signers::LocalWallet;
@@ -302,4 +362,84 @@ mod test {
             serde_json::Value::String(SPONSOR_SIGNATURE.to_owned()),
         );
     }
+
+    #[test]
+    fn it_exposes_hash_accessors() {
+        assert_eq!(
+            REQUEST.eip712_digest().unwrap(),
+            H256::from(REQUEST.encode_eip712().unwrap())
+        );
+        assert_eq!(
+            REQUEST.struct_hash().unwrap(),
+            H256::from(Eip712::struct_hash(&*REQUEST).unwrap())
+        );
+        assert_eq!(
+            format!("{:?}", REQUEST.domain_separator().unwrap()),
+            DOMAIN_SEPARATOR
+        );
+    }
+
+    #[test]
+    fn it_round_trips_signed_requests() {
+        let fake_sig = (0..65u8).collect::<Vec<_>>();
+        let fake_sig = Signature::try_from(fake_sig.as_ref()).unwrap();
+        let signed = REQUEST.clone().add_signature(fake_sig);
+
+        let mut json = serde_json::to_value(&signed).unwrap();
+        let parsed: SignedForwardRequest = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(parsed, signed);
+
+        json["typeId"] = serde_json::json!("MetaTxRequest");
+        assert!(serde_json::from_value::<SignedForwardRequest>(json).is_err());
+    }
+
+    #[tokio::test]
+    async fn it_verifies_signatures() {
+        let sponsor: LocalWallet = DUMMY_SPONSOR_KEY.parse().unwrap();
+        let signed = REQUEST.clone().sign(&sponsor).await.unwrap();
+        signed.verify().unwrap();
+
+        let mut tampered = signed.clone();
+        tampered.req.max_fee += U256::one();
+        assert!(matches!(
+            tampered.verify(),
+            Err(ForwardRequestError::WrongSigner { .. })
+        ));
+    }
+
+    #[test]
+    fn it_attaches_external_signatures() {
+        let signature: Signature = SPONSOR_SIGNATURE.parse().unwrap();
+        let signed = SignedForwardRequest::new_verified(REQUEST.clone(), signature).unwrap();
+        assert_eq!(signed.sponsor_signature(), signature);
+
+        let mut other = REQUEST.clone();
+        other.nonce += 1;
+        assert!(SignedForwardRequest::new_verified(other.clone(), signature).is_err());
+        assert_eq!(
+            SignedForwardRequest::new(other, signature).sponsor_signature(),
+            signature
+        );
+    }
+
+    #[test]
+    fn it_exports_typed_data() {
+        let typed_data = REQUEST.typed_data_json().unwrap();
+        assert_eq!(typed_data["primaryType"], "ForwardRequest");
+        assert_eq!(typed_data["domain"]["name"], "GelatoRelayForwarder");
+        assert_eq!(typed_data["domain"]["chainId"], "42");
+        assert_eq!(
+            typed_data["types"]["ForwardRequest"]
+                .as_array()
+                .unwrap()
+                .len(),
+            12
+        );
+        assert_eq!(
+            typed_data["types"]["ForwardRequest"][5],
+            serde_json::json!({ "name": "maxFee", "type": "uint256" })
+        );
+        assert_eq!(typed_data["message"]["maxFee"], "10000000000000000000");
+        assert_eq!(typed_data["message"]["enforceSponsorNonce"], false);
+    }
 }
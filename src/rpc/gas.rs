@@ -1,6 +1,8 @@
+use std::time::{Duration, SystemTime};
+
 use serde::{Deserialize, Serialize};
 
-use ethers_core::types::U64;
+use ethers_core::types::{U256, U64};
 
 use crate::FeeToken;
 
@@ -21,13 +23,77 @@ pub struct EstimatedFeeRequest {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct EstimatedFeeResponse {
-    /// The oracle-recommended fee, as a decimal string
-    estimated_fee: String,
+    /// The oracle-recommended fee, as a decimal string. Fees in 18-decimal
+    /// tokens may exceed u64
+    #[serde(with = "crate::ser::decimal_u256_ser")]
+    estimated_fee: U256,
 }
 
 impl EstimatedFeeResponse {
     /// Return the estimated fee as a number
-    pub(crate) fn estimated_fee(&self) -> U64 {
-        U64::from_dec_str(&self.estimated_fee).unwrap()
+    pub(crate) fn estimated_fee(&self) -> U256 {
+        self.estimated_fee
+    }
+}
+
+/// An oracle fee estimate, locked in until an expiry. Submitting requests
+/// priced off an expired quote risks paying stale oracle prices, or having
+/// the request rejected as underpriced
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeQuote {
+    /// Chain id the quote is valid on
+    pub chain_id: u64,
+    /// Payment token the fee is denominated in
+    pub payment_token: FeeToken,
+    /// Gas limit the fee was estimated for
+    pub gas_limit: U64,
+    /// Whether the quote is for high priority execution
+    pub is_high_priority: bool,
+    /// The quoted fee
+    pub fee: U256,
+    /// Time after which the quote should not be used
+    pub expires_at: SystemTime,
+}
+
+impl FeeQuote {
+    /// True if the quote has expired
+    pub fn is_expired(&self) -> bool {
+        crate::time::now() > self.expires_at
+    }
+
+    /// Time remaining until expiry. `None` if expired
+    pub fn time_remaining(&self) -> Option<Duration> {
+        self.expires_at.duration_since(crate::time::now()).ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_encodes_estimate_queries() {
+        let query = EstimatedFeeRequest {
+            payment_token: Default::default(),
+            gas_limit: 100_000.into(),
+            is_high_priority: false,
+        };
+        assert_eq!(
+            serde_urlencoded::to_string(&query).unwrap(),
+            "paymentToken=0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE&gasLimit=100000&isHighPriority=false"
+        );
+    }
+
+    #[test]
+    fn it_parses_fees_above_u64() {
+        let resp: EstimatedFeeResponse =
+            serde_json::from_str(r#"{"estimatedFee":"100000000000000000000"}"#).unwrap();
+        assert_eq!(
+            resp.estimated_fee(),
+            U256::from_dec_str("100000000000000000000").unwrap()
+        );
+        assert!(
+            serde_json::from_str::<EstimatedFeeResponse>(r#"{"estimatedFee":"0x1g"}"#).is_err()
+        );
     }
 }
@@ -2,15 +2,20 @@ use ethers_core::{
     abi::{self, Token},
     types::{
         transaction::eip712::{EIP712Domain, Eip712},
-        Address, Bytes, Signature, U64,
+        Address, Bytes, Signature, SignatureError, H256, U256,
     },
     utils::keccak256,
 };
 
+#[cfg(feature = "signers")]
 use ethers_signers::Signer;
 use serde::{Deserialize, Serialize};
 
-use crate::{ser::RsvSignature, utils::get_meta_box, FeeToken, PaymentType};
+use crate::{
+    ser::{MetaTxRequestTypeId, RsvSignature},
+    utils::get_meta_box,
+    Deadline, FeeToken, PaymentType,
+};
 
 const META_TX_TYPE: &str = "MetaTxRequest(uint256 chainId,address target,bytes data,address feeToken,uint256 paymentType,uint256 maxFee,uint256 gas,address user,address sponsor,uint256 sponsorChainId,uint256 nonce,uint256 deadline)";
 
@@ -39,11 +44,11 @@ pub struct MetaTxRequest {
     /// Type identifier for Gelato's payment. Can be 1, 2 or 3.
     pub payment_type: PaymentType, // 1 = gas tank
     /// Maximum fee sponsor is willing to pay Gelato Executors
-    #[serde(with = "crate::ser::decimal_u64_ser")]
-    pub max_fee: U64,
+    #[serde(with = "crate::ser::decimal_u256_ser")]
+    pub max_fee: U256,
     /// Gas limit
-    #[serde(with = "crate::ser::decimal_u64_ser")]
-    pub gas: U64,
+    #[serde(with = "crate::ser::decimal_u256_ser")]
+    pub gas: U256,
     /// EOA of dapp's user
     #[serde(serialize_with = "crate::ser::serialize_checksum_addr")]
     pub user: Address,
@@ -58,9 +63,10 @@ pub struct MetaTxRequest {
     /// Smart contract nonce for sponsor to sign.
     pub nonce: usize,
     /// Deadline for executing this MetaTxRequest. If set to 0, no deadline is
-    /// enforced
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub deadline: Option<u64>,
+    /// enforced. May be omitted, or serialized as an explicit 0, see
+    /// [`Deadline`]
+    #[serde(default, skip_serializing_if = "Deadline::is_omitted")]
+    pub deadline: Deadline,
 }
 
 /// MetaTxRequest error
@@ -90,6 +96,9 @@ pub enum MetaTxRequestError {
         "Attempted to add a sponsor signature to a user-signed meta-tx request with no sponsor set"
     )]
     NoSponsor,
+    /// Signature could not be recovered
+    #[error("{0}")]
+    Signature(#[from] SignatureError),
 }
 
 impl Eip712 for MetaTxRequest {
@@ -120,28 +129,42 @@ impl Eip712 for MetaTxRequest {
             Token::FixedBytes(keccak256(&self.data).to_vec()),
             Token::Address(*self.fee_token),
             Token::Uint((self.payment_type as u8).into()),
-            Token::Uint(self.max_fee.as_u64().into()),
-            Token::Uint(self.gas.as_u64().into()),
+            Token::Uint(self.max_fee),
+            Token::Uint(self.gas),
             Token::Address(self.user),
             Token::Address(self.sponsor.unwrap_or_default()),
             Token::Uint(self.sponsor_chain_id.unwrap_or_default().into()),
             Token::Uint(self.nonce.into()),
-            Token::Uint(self.deadline.unwrap_or_default().into()),
+            Token::Uint(self.deadline.timestamp().into()),
         ]);
         Ok(keccak256(encoded_request))
     }
 }
 
+eip712_accessors!(MetaTxRequest, MetaTxRequestError);
+
 impl MetaTxRequest {
+    /// The full EIP-712 typed data of the request (domain, types and
+    /// message), as accepted by `eth_signTypedData_v4`. Lets browser wallets
+    /// and external signing services produce the user and sponsor signatures.
+    /// An unset sponsor, sponsor chain id or deadline is encoded as zero
+    pub fn typed_data_json(&self) -> Result<serde_json::Value, MetaTxRequestError> {
+        Ok(crate::rpc::typed_data_json(
+            META_TX_TYPE,
+            &self.domain()?,
+            self,
+        ))
+    }
+
     /// Fill MetaTxRequest with user & sponsor signatures and return signed
     /// request struct
-    fn add_signatures(
+    pub(crate) fn add_signatures(
         self,
         user_signature: Signature,
         sponsor_signature: Option<Signature>,
     ) -> SignedMetaTxRequest {
         SignedMetaTxRequest {
-            type_id: "MetaTxRequest",
+            type_id: MetaTxRequestTypeId,
             req: self,
             user_signature: user_signature.into(),
             sponsor_signature: sponsor_signature.map(Into::into),
@@ -163,6 +186,7 @@ impl MetaTxRequest {
     /// Sign the request with the specified signer
     ///
     /// Errors if the signer does not match the user in the struct
+    #[cfg(feature = "signers")]
     pub async fn user_sign<S>(&self, signer: &S) -> Result<Signature, MetaTxRequestError>
     where
         S: ethers_signers::Signer,
@@ -188,6 +212,7 @@ impl MetaTxRequest {
     ///
     /// If this is called after `user_sign`, the tx may need to be re-signed by
     /// the user
+    #[cfg(feature = "signers")]
     pub async fn sponsor_sign<S>(&mut self, sponsor: &S) -> Result<Signature, MetaTxRequestError>
     where
         S: ethers_signers::Signer,
@@ -212,6 +237,7 @@ impl MetaTxRequest {
     }
 
     /// Sign the requeste with no sponsor
+    #[cfg(feature = "signers")]
     pub async fn sign<S>(self, user: &S) -> Result<SignedMetaTxRequest, MetaTxRequestError>
     where
         S: Signer,
@@ -223,6 +249,7 @@ impl MetaTxRequest {
     }
 
     /// Sign the tx request with a user and with a sponsor
+    #[cfg(feature = "signers")]
     pub async fn sign_with_sponsor<S, T>(
         mut self,
         user: &S,
@@ -254,8 +281,8 @@ impl MetaTxRequest {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct SignedMetaTxRequest {
-    /// Just guessing here :)
-    type_id: &'static str,
+    /// must be exactly "MetaTxRequest"
+    type_id: MetaTxRequestTypeId,
 
     /// Metatx request
     #[serde(flatten)]
@@ -270,6 +297,34 @@ pub struct SignedMetaTxRequest {
 }
 
 impl SignedMetaTxRequest {
+    /// Attach user and sponsor signatures produced elsewhere, e.g. by a
+    /// hardware wallet or a remote signing service, over the request's
+    /// [`eip712_digest`]. The signatures are not checked, see
+    /// [`Self::new_verified`]
+    ///
+    /// [`eip712_digest`]: MetaTxRequest::eip712_digest
+    pub fn new(
+        req: MetaTxRequest,
+        user_signature: Signature,
+        sponsor_signature: Option<Signature>,
+    ) -> Self {
+        req.add_signatures(user_signature, sponsor_signature)
+    }
+
+    /// Attach user and sponsor signatures produced elsewhere, and [`verify`]
+    /// them
+    ///
+    /// [`verify`]: Self::verify
+    pub fn new_verified(
+        req: MetaTxRequest,
+        user_signature: Signature,
+        sponsor_signature: Option<Signature>,
+    ) -> Result<Self, MetaTxRequestError> {
+        let signed = Self::new(req, user_signature, sponsor_signature);
+        signed.verify()?;
+        Ok(signed)
+    }
+
     /// Get the attached sponsor signature (if any)
     pub fn sponsor_signature(&self) -> Option<Signature> {
         self.sponsor_signature.map(Into::into)
@@ -280,12 +335,35 @@ impl SignedMetaTxRequest {
         *self.user_signature
     }
 
+    /// Recover the signers of the attached signatures from the EIP-712
+    /// digest, and check that they are the `user` and, if a sponsor
+    /// signature is attached, the `sponsor`. Use this to validate requests
+    /// submitted by third parties before forwarding them
+    pub fn verify(&self) -> Result<(), MetaTxRequestError> {
+        let digest = H256::from(self.req.encode_eip712()?);
+        let check = |signature: &Signature, expected: Address| -> Result<(), MetaTxRequestError> {
+            let actual = signature.recover(digest)?;
+            if actual != expected {
+                return Err(MetaTxRequestError::WrongSigner { expected, actual });
+            }
+            Ok(())
+        };
+
+        check(&*self.user_signature, self.user)?;
+        if let Some(sponsor_signature) = &self.sponsor_signature {
+            let sponsor = self.sponsor.ok_or(MetaTxRequestError::NoSponsor)?;
+            check(&**sponsor_signature, sponsor)?;
+        }
+        Ok(())
+    }
+
     /// Sponsor the request with the specified signer
     ///
     /// Overwrites sponsor if sponsor is None
     ///
     /// If this is called after `user_sign`, the tx may need to be re-signed by
     /// the user
+    #[cfg(feature = "signers")]
     pub async fn append_sponsor_sig<S>(&mut self, sponsor: &S) -> Result<(), MetaTxRequestError>
     where
         S: ethers_signers::Signer,
@@ -316,3 +394,49 @@ impl std::ops::Deref for SignedMetaTxRequest {
         &self.req
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn request(deadline: Deadline) -> MetaTxRequest {
+        MetaTxRequest {
+            chain_id: 5,
+            target: Address::repeat_byte(1),
+            data: Bytes::default(),
+            fee_token: FeeToken::default(),
+            payment_type: PaymentType::AsyncGasTank,
+            max_fee: 1u64.into(),
+            gas: 21_000u64.into(),
+            user: Address::repeat_byte(2),
+            sponsor: None,
+            sponsor_chain_id: None,
+            nonce: 0,
+            deadline,
+        }
+    }
+
+    #[test]
+    fn it_distinguishes_omitted_and_zero_deadlines() {
+        let omitted = serde_json::to_value(request(Deadline::Omitted)).unwrap();
+        assert!(omitted.get("deadline").is_none());
+        let zero = serde_json::to_value(request(Deadline::ZERO)).unwrap();
+        assert_eq!(zero["deadline"], serde_json::json!(0));
+
+        for deadline in [
+            Deadline::Omitted,
+            Deadline::ZERO,
+            Deadline::At(1_700_000_000),
+        ] {
+            let req = request(deadline);
+            let round_tripped: MetaTxRequest =
+                serde_json::from_value(serde_json::to_value(&req).unwrap()).unwrap();
+            assert_eq!(round_tripped, req);
+        }
+
+        assert_eq!(
+            request(Deadline::Omitted).struct_hash().unwrap(),
+            request(Deadline::ZERO).struct_hash().unwrap()
+        );
+    }
+}
@@ -7,6 +7,9 @@ pub(crate) mod chains;
 // no types intended for external use
 pub(crate) use chains::*;
 
+pub(crate) mod erc2771;
+pub use erc2771::*;
+
 pub(crate) mod forward_call;
 pub use forward_call::*;
 
@@ -22,5 +25,11 @@ pub use meta_tx::*;
 pub(crate) mod relay;
 pub use relay::*;
 
+pub(crate) mod sponsored_call;
+pub use sponsored_call::*;
+
 pub(crate) mod status;
 pub use status::*;
+
+pub(crate) mod sync_fee;
+pub use sync_fee::*;
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use ethers_core::types::{Address, Bytes, U64};
+use ethers_core::types::{
+    transaction::eip2718::TypedTransaction, Address, Bytes, NameOrAddress, TransactionRequest, U64,
+};
 
 use crate::FeeToken;
 
@@ -19,3 +21,43 @@ pub struct RelayRequest {
     #[serde(with = "crate::ser::decimal_u64_ser")]
     pub relayer_fee: U64,
 }
+
+impl RelayRequest {
+    /// Derive a relay request from an ethers transaction, paying
+    /// `relayer_fee` in `token`.
+    ///
+    /// Returns `None` if the transaction has no `to` address, or carries a
+    /// non-zero value, which relay requests can't send
+    pub fn from_typed_transaction(
+        tx: &TypedTransaction,
+        token: impl Into<FeeToken>,
+        relayer_fee: impl Into<U64>,
+    ) -> Option<Self> {
+        let dest = match tx.to()? {
+            NameOrAddress::Address(dest) => *dest,
+            NameOrAddress::Name(_) => return None,
+        };
+        if tx.value().map_or(false, |value| !value.is_zero()) {
+            return None;
+        }
+        Some(Self {
+            dest,
+            data: tx.data().cloned().unwrap_or_default(),
+            token: token.into(),
+            relayer_fee: relayer_fee.into(),
+        })
+    }
+
+    /// Derive a relay request from an ethers transaction request, paying
+    /// `relayer_fee` in `token`.
+    ///
+    /// Returns `None` if the transaction has no `to` address, or carries a
+    /// non-zero value, which relay requests can't send
+    pub fn from_transaction_request(
+        tx: &TransactionRequest,
+        token: impl Into<FeeToken>,
+        relayer_fee: impl Into<U64>,
+    ) -> Option<Self> {
+        Self::from_typed_transaction(&tx.clone().into(), token, relayer_fee)
+    }
+}
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use ethers_core::types::{Address, Bytes, U64};
+
+use crate::IntoChainId;
+
+/// Gelato relay v2 sponsored call
+///
+/// <https://docs.gelato.network/developer-services/relay/non-erc-2771/sponsoredcall>
+///
+/// Fees are paid from the sponsor's 1Balance, identified by the client's
+/// API key. The target sees the relay as `msg.sender`, so must handle its
+/// own authentication and replay protection
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SponsoredCallRequest {
+    /// Chain id
+    pub chain_id: u64,
+    /// Address of dApp's smart contract to call.
+    #[serde(serialize_with = "crate::ser::serialize_checksum_addr")]
+    pub target: Address,
+    /// Payload for `target`.
+    pub data: Bytes,
+    /// Gas limit. Estimated by the relay if not set
+    #[serde(
+        default,
+        with = "crate::ser::optional_decimal_u64_ser",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub gas_limit: Option<U64>,
+    /// Number of times the relay retries execution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+}
+
+impl SponsoredCallRequest {
+    /// Instantiate a sponsored call with default options
+    pub fn new(chain_id: impl IntoChainId, target: Address, data: impl Into<Bytes>) -> Self {
+        Self {
+            chain_id: chain_id.into_chain_id(),
+            target,
+            data: data.into(),
+            gas_limit: None,
+            retries: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rpc::Sponsored;
+
+    #[test]
+    fn it_serializes_sponsored_calls() {
+        let mut request = SponsoredCallRequest::new(
+            5,
+            "0x61bBe925A5D646cE074369A6335e5095Ea7abB7A"
+                .parse()
+                .unwrap(),
+            vec![0xab],
+        );
+        request.gas_limit = Some(100_000u64.into());
+
+        let body = Sponsored {
+            request: &request,
+            sponsor_api_key: "key",
+        };
+        assert_eq!(
+            serde_json::to_value(&body).unwrap(),
+            serde_json::json!({
+                "chainId": 5,
+                "target": "0x61bBe925A5D646cE074369A6335e5095Ea7abB7A",
+                "data": "0xab",
+                "gasLimit": "100000",
+                "sponsorApiKey": "key",
+            })
+        );
+    }
+}
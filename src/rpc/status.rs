@@ -1,6 +1,19 @@
 use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use ethers_core::types::{Address, Bytes, H256, U256};
+use serde_json::{Map, Value};
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+use crate::{
+    rpc::{CallWithSyncFeeRequest, ForwardRequest, MetaTxRequest},
+    TaskId,
+};
 
 /// Response to the GetTaskStatus api call. Contains an array of task statuses
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -11,11 +24,80 @@ pub enum TaskStatusResponse {
         /// Status data
         data: Vec<TransactionStatus>,
     },
-    /// Response with messages
-    Error {
-        /// error message
-        message: String,
-    },
+    /// Response with an error
+    Error(TaskStatusError),
+}
+
+impl TaskStatusResponse {
+    /// The statuses, or the API error
+    pub(crate) fn into_statuses(self) -> crate::ClientResult<Vec<TransactionStatus>> {
+        match self {
+            Self::Data { data } => Ok(data),
+            Self::Error(error) => Err(crate::ClientError::TaskStatus(error)),
+        }
+    }
+}
+
+/// An error returned by the status endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskStatusError {
+    /// Error code, if the API provided one
+    #[serde(
+        default,
+        deserialize_with = "crate::ser::optional_code_de::deserialize",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub code: Option<String>,
+    /// Error message
+    pub message: String,
+    /// Errors concerning individual tasks, if the API provided any
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<TaskErrorDetail>,
+}
+
+impl fmt::Display for TaskStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Gelato status error")?;
+        if let Some(code) = &self.code {
+            write!(f, " {code}")?;
+        }
+        write!(f, ": {}", self.message)?;
+        for error in &self.errors {
+            write!(f, "; {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// An error concerning one task, reported by the status endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskErrorDetail {
+    /// The task, if the API identified it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<TaskId>,
+    /// Error code, if the API provided one
+    #[serde(
+        default,
+        deserialize_with = "crate::ser::optional_code_de::deserialize",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub code: Option<String>,
+    /// Error message
+    pub message: String,
+}
+
+impl fmt::Display for TaskErrorDetail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(task_id) = &self.task_id {
+            write!(f, "{task_id} ")?;
+        }
+        if let Some(code) = &self.code {
+            write!(f, "{code} ")?;
+        }
+        f.write_str(&self.message)
+    }
 }
 
 /// A TransactionStatus object
@@ -27,7 +109,7 @@ pub struct TransactionStatus {
     /// Chain name
     pub chain: String,
     /// Task id
-    pub task_id: H256,
+    pub task_id: TaskId,
     /// Task state
     pub task_state: TaskState,
     /// Created at date/time string
@@ -43,6 +125,211 @@ pub struct TransactionStatus {
     pub last_execution: String, // date
 }
 
+impl TransactionStatus {
+    /// The most recent check, if the last check is not a bare timestamp
+    pub fn check(&self) -> Option<&Check> {
+        match &self.last_check {
+            Some(CheckOrDate::Check(check)) => Some(check),
+            _ => None,
+        }
+    }
+
+    /// Time elapsed between task creation and `now`. `None` if the creation
+    /// time can't be parsed
+    pub fn age_at(&self, now: SystemTime) -> Option<Duration> {
+        now.duration_since(parse_timestamp(&self.created_at)?).ok()
+    }
+
+    /// A compact, human-readable line describing the task's state, age,
+    /// transaction hash and revert reason, with the age measured at `now`
+    pub fn summary_at(&self, now: SystemTime) -> String {
+        let mut line = format!("{} {}", self.task_id, self.task_state);
+        if let Some(age) = self.age_at(now) {
+            line.push_str(&format!(" age={}", format_age(age)));
+        }
+        if let Some(execution) = &self.execution {
+            line.push_str(&format!(
+                " tx={:?} block={}",
+                execution.transaction_hash, execution.block_number
+            ));
+        }
+        if let Some(check) = self.check() {
+            if let Some(detail) = check.detail() {
+                line.push_str(&format!(" reason={detail:?}"));
+            }
+        }
+        line
+    }
+
+    /// A compact, human-readable line describing the task's state, age,
+    /// transaction hash and revert reason
+    pub fn summary(&self) -> String {
+        self.summary_at(crate::time::now())
+    }
+
+    /// Task creation time. `None` if it can't be parsed
+    #[cfg(feature = "chrono")]
+    pub fn created_at_utc(&self) -> Option<DateTime<Utc>> {
+        parse_utc(&self.created_at)
+    }
+
+    /// Last execution time. `None` if it can't be parsed
+    #[cfg(feature = "chrono")]
+    pub fn last_execution_utc(&self) -> Option<DateTime<Utc>> {
+        parse_utc(&self.last_execution)
+    }
+
+    /// Time of the last check. `None` if there is no last check or its time
+    /// can't be parsed
+    #[cfg(feature = "chrono")]
+    pub fn last_check_utc(&self) -> Option<DateTime<Utc>> {
+        self.last_check.as_ref()?.date_utc()
+    }
+}
+
+// Timestamps are kept as strings in the response types, so that an
+// unexpected format doesn't fail deserialization of the whole status
+#[cfg(feature = "chrono")]
+fn parse_utc(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+// Parse the relay's `YYYY-MM-DDTHH:MM:SS[.fff]Z` timestamps
+fn parse_timestamp(timestamp: &str) -> Option<SystemTime> {
+    let (date, time) = timestamp.split_once('T')?;
+    let time = time.strip_suffix('Z')?;
+
+    let mut date = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut time = time.splitn(3, ':').map(str::parse::<i64>);
+    let (hour, minute, second) = (time.next()?.ok()?, time.next()?.ok()?, time.next()?.ok()?);
+
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // days since the unix epoch of a proleptic gregorian date
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let seconds = days * 86_400 + hour * 3_600 + minute * 60 + second;
+    let millis = format!("{fraction:0<3}").get(..3)?.parse::<u64>().ok()?;
+    Some(
+        UNIX_EPOCH
+            + Duration::from_secs(u64::try_from(seconds).ok()?)
+            + Duration::from_millis(millis),
+    )
+}
+
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3_599 => format!("{}m{}s", secs / 60, secs % 60),
+        3_600..=86_399 => format!("{}h{}m", secs / 3_600, secs % 3_600 / 60),
+        _ => format!("{}d{}h", secs / 86_400, secs % 86_400 / 3_600),
+    }
+}
+
+/// Response to the v2 task status api call
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct TaskStatusV2Response {
+    pub(crate) task: TaskStatusV2,
+}
+
+/// A task status, as reported by the v2 `tasks/status/{taskId}` endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskStatusV2 {
+    /// Chain id
+    pub chain_id: u64,
+    /// Task id
+    pub task_id: TaskId,
+    /// Task state
+    pub task_state: TaskState,
+    /// Creation date/time string
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creation_date: Option<String>,
+    /// Date/time string of the last check
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_check_date: Option<String>,
+    /// Message of the last check, e.g. a revert reason
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_check_message: Option<String>,
+    /// Execution transaction hash, once executed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<H256>,
+    /// Execution block number, once executed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    /// Execution date/time string, once executed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_date: Option<String>,
+}
+
+impl From<TaskStatusV2> for TransactionStatus {
+    /// The v1 shape of the status, so that both endpoints resolve the same
+    /// way. The execution is present once the task has a transaction hash
+    fn from(status: TaskStatusV2) -> Self {
+        let execution = status.transaction_hash.map(|transaction_hash| Execution {
+            status: status.task_state.to_string(),
+            transaction_hash,
+            block_number: status.block_number.unwrap_or_default() as usize,
+            created_at: status.execution_date.clone().unwrap_or_default(),
+        });
+        TransactionStatus {
+            service: "relay".to_owned(),
+            chain: status.chain_id.to_string(),
+            task_id: status.task_id,
+            task_state: status.task_state.clone(),
+            created_at: status.creation_date.unwrap_or_default(),
+            last_check: Some(CheckOrDate::Check(Box::new(Check {
+                created_at: status.last_check_date,
+                task_state: status.task_state,
+                message: status.last_check_message,
+                payload: None,
+                reason: None,
+            }))),
+            execution,
+            last_execution: status.execution_date.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<TransactionStatus> for TaskStatusV2 {
+    /// The v2 shape of the status. The chain id is 0 unless the v1 chain is
+    /// numeric
+    fn from(status: TransactionStatus) -> Self {
+        let last_check = status.check();
+        let last_check_date = last_check
+            .and_then(|check| check.created_at.clone())
+            .or_else(|| match &status.last_check {
+                Some(CheckOrDate::Date(date)) => Some(date.clone()),
+                _ => None,
+            });
+        let last_check_message = last_check.and_then(|check| check.detail().map(str::to_owned));
+        TaskStatusV2 {
+            chain_id: status.chain.parse().unwrap_or_default(),
+            task_id: status.task_id,
+            task_state: status.task_state,
+            creation_date: Some(status.created_at),
+            last_check_date,
+            last_check_message,
+            transaction_hash: status.execution.as_ref().map(|e| e.transaction_hash),
+            block_number: status.execution.as_ref().map(|e| e.block_number as u64),
+            execution_date: status.execution.map(|e| e.created_at),
+        }
+    }
+}
+
 /// Execution details
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -58,6 +345,14 @@ pub struct Execution {
     pub created_at: String,
 }
 
+impl Execution {
+    /// Creation time. `None` if it can't be parsed
+    #[cfg(feature = "chrono")]
+    pub fn created_at_utc(&self) -> Option<DateTime<Utc>> {
+        parse_utc(&self.created_at)
+    }
+}
+
 /// Either check details, or a date/time string
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(untagged, rename_all = "camelCase")]
@@ -68,6 +363,18 @@ pub enum CheckOrDate {
     Check(Box<Check>),
 }
 
+impl CheckOrDate {
+    /// The date, or the creation time of the check. `None` if it is missing
+    /// or can't be parsed
+    #[cfg(feature = "chrono")]
+    pub fn date_utc(&self) -> Option<DateTime<Utc>> {
+        match self {
+            CheckOrDate::Date(date) => parse_utc(date),
+            CheckOrDate::Check(check) => check.created_at_utc(),
+        }
+    }
+}
+
 /// Check info for a
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -84,43 +391,177 @@ pub struct Check {
     /// Message string
     pub message: Option<String>,
     /// Initial request details
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "lenient_payload",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub payload: Option<Payload>,
     /// Reason for status (if any). This often has a solidity revert message
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub reason: Option<String>,
 }
 
-/// Transaction payload information
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+impl Check {
+    /// The revert reason, or the check message if there is no reason
+    pub fn detail(&self) -> Option<&str> {
+        self.reason.as_deref().or(self.message.as_deref())
+    }
+
+    /// Creation time. `None` if it is missing or can't be parsed
+    #[cfg(feature = "chrono")]
+    pub fn created_at_utc(&self) -> Option<DateTime<Utc>> {
+        parse_utc(self.created_at.as_deref()?)
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.task_state)?;
+        if let Some(detail) = self.detail() {
+            write!(f, ": {detail}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Transaction payload information. The shape varies by request kind, so
+/// every field is optional, and fields not modelled here are kept in `extra`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct Payload {
     /// Transaction target
-    #[serde(serialize_with = "crate::ser::serialize_checksum_addr")]
-    pub to: Address,
+    #[serde(
+        default,
+        serialize_with = "crate::ser::serialize_optional_checksum_addr",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub to: Option<Address>,
     /// Transaction input data
-    pub data: Bytes,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Bytes>,
     /// Fee data
-    pub fee_data: FeeData,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_data: Option<FeeData>,
+    /// Other fields, e.g. the fields of the original forward or meta tx
+    /// request
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-/// eip1559 fee data
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+impl Payload {
+    /// The `typeId` of the original request, e.g. `"ForwardRequest"`, if
+    /// present
+    pub fn type_id(&self) -> Option<&str> {
+        self.extra.get("typeId")?.as_str()
+    }
+
+    /// The transaction target: `to`, or the `target` of the original request
+    pub fn target(&self) -> Option<Address> {
+        self.to
+            .or_else(|| self.extra.get("target")?.as_str()?.parse().ok())
+    }
+
+    /// The original request, decoded by its `typeId`. Payloads without a
+    /// `typeId` are decoded as sync fee calls. `None` if the fields don't
+    /// match the request kind
+    pub fn request(&self) -> Option<PayloadRequest> {
+        // `to` and `data` are modelled by the payload, and named `target`
+        // and `data` by the requests
+        let mut fields = self.extra.clone();
+        if let Some(data) = &self.data {
+            fields.insert("data".to_owned(), serde_json::to_value(data).ok()?);
+        }
+        if let Some(to) = self.to {
+            fields
+                .entry("target")
+                .or_insert(serde_json::to_value(to).ok()?);
+        }
+        let fields = Value::Object(fields);
+
+        match self.type_id() {
+            Some("ForwardRequest") => serde_json::from_value(fields)
+                .ok()
+                .map(PayloadRequest::ForwardRequest),
+            Some("MetaTxRequest") => serde_json::from_value(fields)
+                .ok()
+                .map(PayloadRequest::MetaTxRequest),
+            Some("CallWithSyncFee") | None => serde_json::from_value(fields)
+                .ok()
+                .map(PayloadRequest::CallWithSyncFee),
+            Some(_) => None,
+        }
+    }
+}
+
+/// The original request of a [`Payload`], by request kind
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PayloadRequest {
+    /// A forward request
+    ForwardRequest(ForwardRequest),
+    /// A meta tx request
+    MetaTxRequest(MetaTxRequest),
+    /// A call with sync fee
+    CallWithSyncFee(CallWithSyncFeeRequest),
+}
+
+// A payload that doesn't match [`Payload`] is kept as `extra` if it is an
+// object, and dropped otherwise, so that it never fails the whole status
+fn lenient_payload<'de, D>(deserializer: D) -> Result<Option<Payload>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => None,
+        Some(value) => match serde_json::from_value(value.clone()) {
+            Ok(payload) => Some(payload),
+            Err(_) => match value {
+                Value::Object(extra) => Some(Payload {
+                    extra,
+                    ..Default::default()
+                }),
+                _ => None,
+            },
+        },
+    })
+}
+
+/// Transaction fee data. Legacy chains report only a gas price, and
+/// fields not modelled here are kept in `extra`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct FeeData {
     /// Gas Price
-    #[serde(with = "crate::ser::json_u256_ser")]
-    pub gas_price: U256,
+    #[serde(
+        default,
+        with = "crate::ser::optional_json_u256_ser",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub gas_price: Option<U256>,
     /// Max fee per gas
-    #[serde(with = "crate::ser::json_u256_ser")]
-    pub max_fee_per_gas: U256,
+    #[serde(
+        default,
+        with = "crate::ser::optional_json_u256_ser",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_fee_per_gas: Option<U256>,
     /// Max priority fee per gas
-    #[serde(with = "crate::ser::json_u256_ser")]
-    pub max_priority_fee_per_gas: U256,
+    #[serde(
+        default,
+        with = "crate::ser::optional_json_u256_ser",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_priority_fee_per_gas: Option<U256>,
+    /// Other fields
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
-/// Task states
+/// Task states. States added to the API after this release deserialize as
+/// [`TaskState::Unknown`], and are treated as pending
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(from = "String", into = "String")]
+#[non_exhaustive]
 pub enum TaskState {
     /// CheckPending
     CheckPending,
@@ -138,4 +579,332 @@ pub enum TaskState {
     Cancelled,
     /// NotFound
     NotFound,
+    /// A state unknown to this version of the SDK
+    Unknown(String),
+}
+
+impl From<String> for TaskState {
+    fn from(state: String) -> Self {
+        match state.as_str() {
+            "CheckPending" => Self::CheckPending,
+            "ExecPending" => Self::ExecPending,
+            "ExecSuccess" => Self::ExecSuccess,
+            "ExecReverted" => Self::ExecReverted,
+            "WaitingForConfirmation" => Self::WaitingForConfirmation,
+            "Blacklisted" => Self::Blacklisted,
+            "Cancelled" => Self::Cancelled,
+            "NotFound" => Self::NotFound,
+            _ => Self::Unknown(state),
+        }
+    }
+}
+
+impl From<TaskState> for String {
+    fn from(state: TaskState) -> Self {
+        match state {
+            TaskState::Unknown(state) => state,
+            state => state.as_str().to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for TaskState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TaskState {
+    /// The state as reported by the API
+    pub fn as_str(&self) -> &str {
+        match self {
+            TaskState::CheckPending => "CheckPending",
+            TaskState::ExecPending => "ExecPending",
+            TaskState::ExecSuccess => "ExecSuccess",
+            TaskState::ExecReverted => "ExecReverted",
+            TaskState::WaitingForConfirmation => "WaitingForConfirmation",
+            TaskState::Blacklisted => "Blacklisted",
+            TaskState::Cancelled => "Cancelled",
+            TaskState::NotFound => "NotFound",
+            TaskState::Unknown(state) => state,
+        }
+    }
+
+    /// True if the task will not change state again
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            TaskState::ExecSuccess
+                | TaskState::ExecReverted
+                | TaskState::Blacklisted
+                | TaskState::Cancelled
+                | TaskState::NotFound
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_returns_all_statuses() {
+        let resp: TaskStatusResponse =
+            serde_json::from_value(serde_json::json!({ "data": [] })).unwrap();
+        assert!(resp.into_statuses().unwrap().is_empty());
+
+        let resp: TaskStatusResponse =
+            serde_json::from_value(serde_json::json!({ "message": "Status not found" })).unwrap();
+        assert!(matches!(
+            resp.into_statuses(),
+            Err(crate::ClientError::TaskStatus(_))
+        ));
+    }
+
+    #[test]
+    fn it_converts_v2_statuses() {
+        let resp: TaskStatusV2Response = serde_json::from_value(serde_json::json!({
+            "task": {
+                "chainId": 5,
+                "taskId": "0xce52ae7a6a3032848d76b161ac4c131fa995dcc67e3be5392dfb8466275d6679",
+                "taskState": "ExecSuccess",
+                "creationDate": "2022-10-10T10:10:10.000Z",
+                "transactionHash": "0x0101010101010101010101010101010101010101010101010101010101010101",
+                "blockNumber": 100,
+                "executionDate": "2022-10-10T10:10:30.000Z"
+            }
+        }))
+        .unwrap();
+        let status = TransactionStatus::from(resp.task.clone());
+        assert_eq!(status.task_state, TaskState::ExecSuccess);
+        assert_eq!(status.check().unwrap().task_state, TaskState::ExecSuccess);
+        let execution = status.execution.clone().unwrap();
+        assert_eq!(execution.transaction_hash, H256::repeat_byte(1));
+        assert_eq!(execution.block_number, 100);
+        assert_eq!(TaskStatusV2::from(status), resp.task);
+    }
+
+    #[test]
+    fn it_decodes_payloads_tolerantly() {
+        let check: Check = serde_json::from_value(serde_json::json!({
+            "taskState": "ExecPending",
+            "message": null,
+            "payload": {
+                "to": "0x61bBe925A5D646cE074369A6335e5095Ea7abB7A",
+                "data": "0x",
+                "feeData": {
+                    "gasPrice": { "hex": "0x01", "type": "BigNumber" },
+                    "lastBaseFeePerGas": null
+                },
+                "typeId": "ForwardRequest"
+            }
+        }))
+        .unwrap();
+        let payload = check.payload.unwrap();
+        let fee_data = payload.fee_data.as_ref().unwrap();
+        assert_eq!(fee_data.gas_price, Some(1u64.into()));
+        assert_eq!(fee_data.max_fee_per_gas, None);
+        assert!(fee_data.extra.contains_key("lastBaseFeePerGas"));
+        assert_eq!(payload.type_id(), Some("ForwardRequest"));
+
+        let check: Check = serde_json::from_value(serde_json::json!({
+            "taskState": "ExecPending",
+            "message": null,
+            "payload": {
+                "target": "0x61bBe925A5D646cE074369A6335e5095Ea7abB7A",
+                "feeData": "unexpected"
+            }
+        }))
+        .unwrap();
+        let payload = check.payload.unwrap();
+        assert!(payload.fee_data.is_none());
+        assert_eq!(
+            payload.target(),
+            Some(
+                "0x61bBe925A5D646cE074369A6335e5095Ea7abB7A"
+                    .parse()
+                    .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn it_decodes_forward_request_payloads() {
+        let payload: Payload = serde_json::from_value(serde_json::json!({
+            "typeId": "ForwardRequest",
+            "chainId": 5,
+            "target": "0x61bBe925A5D646cE074369A6335e5095Ea7abB7A",
+            "data": "0x01",
+            "feeToken": "0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE",
+            "paymentType": 1,
+            "maxFee": "1000",
+            "gas": "200000",
+            "sponsor": "0x4e4f0d95bc1a4275b748a2220e1e0e4e674fc8c4",
+            "sponsorChainId": 5,
+            "nonce": 3,
+            "enforceSponsorNonce": true,
+            "enforceSponsorNonceOrdering": false
+        }))
+        .unwrap();
+        match payload.request() {
+            Some(PayloadRequest::ForwardRequest(req)) => {
+                assert_eq!(req.chain_id, 5);
+                assert_eq!(req.data, Bytes::from(vec![1]));
+                assert_eq!(req.max_fee, 1000.into());
+                assert_eq!(req.nonce, 3);
+            }
+            other => panic!("unexpected request {other:?}"),
+        }
+    }
+
+    #[test]
+    fn it_decodes_meta_tx_request_payloads() {
+        let payload: Payload = serde_json::from_value(serde_json::json!({
+            "typeId": "MetaTxRequest",
+            "chainId": 5,
+            "to": "0x61bBe925A5D646cE074369A6335e5095Ea7abB7A",
+            "data": "0x",
+            "feeToken": "0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE",
+            "paymentType": 1,
+            "maxFee": "1000",
+            "gas": "200000",
+            "user": "0x4e4f0d95bc1a4275b748a2220e1e0e4e674fc8c4",
+            "nonce": 0,
+            "deadline": 0
+        }))
+        .unwrap();
+        match payload.request() {
+            Some(PayloadRequest::MetaTxRequest(req)) => {
+                assert_eq!(req.target, payload.to.unwrap());
+                assert_eq!(req.sponsor, None);
+            }
+            other => panic!("unexpected request {other:?}"),
+        }
+    }
+
+    #[test]
+    fn it_decodes_sync_fee_payloads() {
+        let payload: Payload = serde_json::from_value(serde_json::json!({
+            "chainId": 5,
+            "target": "0x61bBe925A5D646cE074369A6335e5095Ea7abB7A",
+            "data": "0x",
+            "feeToken": "0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE",
+            "isRelayContext": true
+        }))
+        .unwrap();
+        match payload.request() {
+            Some(PayloadRequest::CallWithSyncFee(req)) => {
+                assert!(req.is_relay_context);
+                assert_eq!(req.gas_limit, None);
+            }
+            other => panic!("unexpected request {other:?}"),
+        }
+
+        // fields of another request kind
+        let payload: Payload = serde_json::from_value(serde_json::json!({
+            "typeId": "ForwardRequest",
+            "target": "0x61bBe925A5D646cE074369A6335e5095Ea7abB7A"
+        }))
+        .unwrap();
+        assert_eq!(payload.request(), None);
+    }
+
+    #[test]
+    fn it_parses_status_errors() {
+        let task_id = TaskId::from(H256::repeat_byte(1));
+        let resp: TaskStatusResponse = serde_json::from_value(serde_json::json!({
+            "code": 400,
+            "message": "Invalid request",
+            "errors": [{ "taskId": task_id, "code": "E_TASK", "message": "Malformed task id" }]
+        }))
+        .unwrap();
+
+        let error = match resp.into_statuses() {
+            Err(crate::ClientError::TaskStatus(error)) => error,
+            r => panic!("unexpected result {r:?}"),
+        };
+        assert_eq!(error.code.as_deref(), Some("400"));
+        assert_eq!(error.message, "Invalid request");
+        assert_eq!(
+            error.errors,
+            vec![TaskErrorDetail {
+                task_id: Some(task_id),
+                code: Some("E_TASK".to_owned()),
+                message: "Malformed task id".to_owned(),
+            }]
+        );
+        assert_eq!(
+            error.to_string(),
+            format!("Gelato status error 400: Invalid request; {task_id} E_TASK Malformed task id")
+        );
+    }
+
+    #[test]
+    fn it_tolerates_unknown_states() {
+        let state: TaskState = serde_json::from_value(serde_json::json!("ExecSuccess")).unwrap();
+        assert_eq!(state, TaskState::ExecSuccess);
+
+        let state: TaskState = serde_json::from_value(serde_json::json!("Refunded")).unwrap();
+        assert_eq!(state, TaskState::Unknown("Refunded".to_owned()));
+        assert!(!state.is_terminal());
+        assert_eq!(state.to_string(), "Refunded");
+        assert_eq!(
+            serde_json::to_value(&state).unwrap(),
+            serde_json::json!("Refunded")
+        );
+    }
+
+    #[test]
+    fn it_summarizes_statuses() {
+        let status: TransactionStatus = serde_json::from_value(serde_json::json!({
+            "service": "relay",
+            "chain": "goerli",
+            "taskId": "0x0101010101010101010101010101010101010101010101010101010101010101",
+            "taskState": "ExecReverted",
+            "created_at": "2022-06-02T12:00:00.000Z",
+            "lastCheck": {
+                "taskState": "ExecReverted",
+                "message": "Execution reverted",
+                "reason": "nope"
+            },
+            "execution": {
+                "status": "reverted",
+                "transactionHash": "0x0202020202020202020202020202020202020202020202020202020202020202",
+                "blockNumber": 7,
+                "created_at": "2022-06-02T12:01:00.000Z"
+            },
+            "lastExecution": "2022-06-02T12:01:00.000Z"
+        }))
+        .unwrap();
+
+        let now = parse_timestamp("2022-06-02T12:02:05.500Z").unwrap();
+        assert_eq!(
+            status.summary_at(now),
+            format!(
+                "{} ExecReverted age=2m5s tx={:?} block=7 reason=\"nope\"",
+                status.task_id,
+                H256::repeat_byte(2)
+            )
+        );
+        assert_eq!(status.check().unwrap().to_string(), "ExecReverted: nope");
+    }
+
+    #[test]
+    fn it_parses_timestamps() {
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some(UNIX_EPOCH));
+        assert_eq!(
+            parse_timestamp("2022-06-02T12:00:00.250Z"),
+            Some(UNIX_EPOCH + Duration::from_millis(1_654_171_200_250))
+        );
+        assert_eq!(parse_timestamp("yesterday"), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn it_parses_chrono_timestamps() {
+        let check = CheckOrDate::Date("2022-06-02T12:00:00.250Z".to_owned());
+        let time = check.date_utc().unwrap();
+        assert_eq!(time.timestamp_millis(), 1_654_171_200_250);
+        assert_eq!(parse_utc("yesterday"), None);
+    }
 }
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+use ethers_core::types::{Address, Bytes, U64};
+
+use crate::{FeeToken, IntoChainId};
+
+/// Gelato relay v2 call with sync fee
+///
+/// <https://docs.gelato.network/developer-services/relay/non-erc-2771/callwithsyncfee>
+///
+/// The target pays the relay synchronously, during execution, in
+/// `fee_token`. With `is_relay_context` set, the relay appends the fee
+/// collector, fee token and fee to the calldata, see
+/// [`RelayContext`](crate::relay_context::RelayContext)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CallWithSyncFeeRequest {
+    /// Chain id
+    pub chain_id: u64,
+    /// Address of dApp's smart contract to call.
+    #[serde(serialize_with = "crate::ser::serialize_checksum_addr")]
+    pub target: Address,
+    /// Payload for `target`.
+    pub data: Bytes,
+    /// Token the target pays the fee in
+    pub fee_token: FeeToken,
+    /// Whether the relay appends the relay context to the calldata
+    pub is_relay_context: bool,
+    /// Gas limit. Estimated by the relay if not set
+    #[serde(
+        default,
+        with = "crate::ser::optional_decimal_u64_ser",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub gas_limit: Option<U64>,
+    /// Number of times the relay retries execution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+}
+
+impl CallWithSyncFeeRequest {
+    /// Instantiate a call paying its fee in `fee_token`, with the relay
+    /// context appended to the calldata
+    pub fn new(
+        chain_id: impl IntoChainId,
+        target: Address,
+        data: impl Into<Bytes>,
+        fee_token: impl Into<FeeToken>,
+    ) -> Self {
+        Self {
+            chain_id: chain_id.into_chain_id(),
+            target,
+            data: data.into(),
+            fee_token: fee_token.into(),
+            is_relay_context: true,
+            gas_limit: None,
+            retries: None,
+        }
+    }
+}
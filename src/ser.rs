@@ -60,6 +60,19 @@ where
     serializer.serialize_str(&ethers_core::utils::to_checksum(val, None))
 }
 
+pub(crate) fn serialize_optional_checksum_addr<S>(
+    val: &Option<H160>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match val {
+        Some(val) => serialize_checksum_addr(val, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
 pub(crate) mod decimal_u64_ser {
     use ethers_core::types::U64;
     use serde::{Deserialize, Deserializer, Serializer};
@@ -80,6 +93,112 @@ pub(crate) mod decimal_u64_ser {
     }
 }
 
+pub(crate) mod optional_decimal_u64_ser {
+    use ethers_core::types::U64;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S>(val: &Option<U64>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => super::decimal_u64_ser::serialize(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<U64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| U64::from_dec_str(&s).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+pub(crate) mod optional_code_de {
+    use serde::{Deserialize, Deserializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Code {
+        Number(i64),
+        String(String),
+    }
+
+    /// Deserialize an optional error code given as a number or a string
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(
+            Option::<Code>::deserialize(deserializer)?.map(|code| match code {
+                Code::Number(code) => code.to_string(),
+                Code::String(code) => code,
+            }),
+        )
+    }
+}
+
+pub(crate) mod decimal_u256_ser {
+    use ethers_core::types::U256;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S>(val: &U256, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&val.to_string())
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<U256, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        U256::from_dec_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+pub(crate) mod optional_decimal_u256_ser {
+    use ethers_core::types::U256;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    // numbers, decimal strings, or 0x-prefixed hex strings
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AnyU256 {
+        Number(u64),
+        String(String),
+    }
+
+    pub(crate) fn serialize<S>(val: &Option<U256>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => super::decimal_u256_ser::serialize(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<U256>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<AnyU256>::deserialize(deserializer)?
+            .map(|val| match val {
+                AnyU256::Number(val) => Ok(val.into()),
+                AnyU256::String(s) => match s.strip_prefix("0x") {
+                    Some(hex) => U256::from_str_radix(hex, 16).map_err(D::Error::custom),
+                    None => U256::from_dec_str(&s).map_err(D::Error::custom),
+                },
+            })
+            .transpose()
+    }
+}
+
 pub(crate) mod json_u256_ser {
     use ethers_core::types::U256;
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -110,10 +229,87 @@ pub(crate) mod json_u256_ser {
     }
 }
 
+pub(crate) mod optional_json_u256_ser {
+    use ethers_core::types::U256;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    // ethers BigNumber objects, or plain hex strings
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AnyU256 {
+        BigNumber { hex: U256 },
+        Plain(U256),
+    }
+
+    pub(crate) fn serialize<S>(val: &Option<U256>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => super::json_u256_ser::serialize(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<U256>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(
+            Option::<AnyU256>::deserialize(deserializer)?.map(|val| match val {
+                AnyU256::BigNumber { hex } => hex,
+                AnyU256::Plain(val) => val,
+            }),
+        )
+    }
+}
+
+/// Declare a zero-sized `typeId` marker, serialized as `$literal`.
+/// Deserialization errors on any other value
+macro_rules! type_id {
+    ($name:ident, $literal:literal) => {
+        #[doc = concat!("The `", $literal, "` type id")]
+        #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+        pub(crate) struct $name;
+
+        impl $name {
+            pub(crate) const LITERAL: &'static str = $literal;
+        }
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_str(Self::LITERAL)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let s = String::deserialize(deserializer)?;
+                if s != Self::LITERAL {
+                    return Err(serde::de::Error::invalid_value(
+                        serde::de::Unexpected::Str(&s),
+                        &Self::LITERAL,
+                    ));
+                }
+                Ok(Self)
+            }
+        }
+    };
+}
+
+type_id!(ForwardRequestTypeId, "ForwardRequest");
+type_id!(MetaTxRequestTypeId, "MetaTxRequest");
+
 #[cfg(test)]
 mod test {
     use ethers::prelude::U64;
-    use ethers_signers::{LocalWallet, Signer};
+    use ethers::signers::{LocalWallet, Signer};
 
     use super::*;
 
@@ -139,4 +335,20 @@ mod test {
             serde_json::Value::String(hex_sig),
         )
     }
+
+    #[test]
+    fn type_id_ser() {
+        assert_eq!(
+            serde_json::to_value(ForwardRequestTypeId).unwrap(),
+            serde_json::json!("ForwardRequest")
+        );
+        assert!(
+            serde_json::from_value::<ForwardRequestTypeId>(serde_json::json!("ForwardRequest"))
+                .is_ok()
+        );
+        assert!(
+            serde_json::from_value::<ForwardRequestTypeId>(serde_json::json!("MetaTxRequest"))
+                .is_err()
+        );
+    }
 }
@@ -0,0 +1,191 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+};
+
+#[derive(Debug, Default)]
+struct Inner {
+    triggered: AtomicBool,
+    active: AtomicUsize,
+    // components waiting for shutdown to be triggered
+    signal_wakers: Mutex<Vec<Waker>>,
+    // callers waiting for components to drain
+    drain_wakers: Mutex<Vec<Waker>>,
+}
+
+impl Inner {
+    fn wake_all(wakers: &Mutex<Vec<Waker>>) {
+        let wakers = std::mem::take(&mut *wakers.lock().unwrap_or_else(|e| e.into_inner()));
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    fn register(wakers: &Mutex<Vec<Waker>>, waker: &Waker) {
+        let mut wakers = wakers.lock().unwrap_or_else(|e| e.into_inner());
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+}
+
+/// Coordinates graceful shutdown of background components.
+///
+/// Each component holds a [`ShutdownSignal`]. When [`Shutdown::shutdown`] is
+/// called, components stop accepting work, flush their pending state, and
+/// drop their signal. The returned future resolves once every signal has been
+/// dropped.
+#[derive(Debug, Clone, Default)]
+pub struct Shutdown {
+    inner: Arc<Inner>,
+}
+
+impl Shutdown {
+    /// Instantiate a new shutdown coordinator
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Get a signal for a component. The component is considered active
+    /// until the signal (and all its clones) are dropped
+    pub fn signal(&self) -> ShutdownSignal {
+        self.inner.active.fetch_add(1, Ordering::SeqCst);
+        ShutdownSignal {
+            inner: self.inner.clone(),
+        }
+    }
+
+    /// True if shutdown has been triggered
+    pub fn is_triggered(&self) -> bool {
+        self.inner.triggered.load(Ordering::SeqCst)
+    }
+
+    /// The number of components still holding a signal
+    pub fn active(&self) -> usize {
+        self.inner.active.load(Ordering::SeqCst)
+    }
+
+    /// Trigger shutdown. Resolves when all components have drained
+    pub fn shutdown(&self) -> Drained {
+        self.inner.triggered.store(true, Ordering::SeqCst);
+        Inner::wake_all(&self.inner.signal_wakers);
+        Drained {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// Future that resolves when all components of a [`Shutdown`] have drained
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Drained {
+    inner: Arc<Inner>,
+}
+
+impl Future for Drained {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.inner.active.load(Ordering::SeqCst) == 0 {
+            return Poll::Ready(());
+        }
+        Inner::register(&self.inner.drain_wakers, cx.waker());
+        // re-check in case the last signal dropped while registering
+        if self.inner.active.load(Ordering::SeqCst) == 0 {
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    }
+}
+
+/// A background component's handle on a [`Shutdown`]. Dropping it marks the
+/// component as drained
+#[derive(Debug)]
+pub struct ShutdownSignal {
+    inner: Arc<Inner>,
+}
+
+impl Clone for ShutdownSignal {
+    fn clone(&self) -> Self {
+        self.inner.active.fetch_add(1, Ordering::SeqCst);
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Drop for ShutdownSignal {
+    fn drop(&mut self) {
+        if self.inner.active.fetch_sub(1, Ordering::SeqCst) == 1 {
+            Inner::wake_all(&self.inner.drain_wakers);
+        }
+    }
+}
+
+impl ShutdownSignal {
+    /// True if shutdown has been triggered
+    pub fn is_shutdown(&self) -> bool {
+        self.inner.triggered.load(Ordering::SeqCst)
+    }
+
+    /// Poll for shutdown, registering the waker to be woken when shutdown is
+    /// triggered
+    pub fn poll_shutdown(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_shutdown() {
+            return Poll::Ready(());
+        }
+        Inner::register(&self.inner.signal_wakers, cx.waker());
+        if self.is_shutdown() {
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    }
+
+    /// Resolves when shutdown is triggered
+    pub fn triggered(&self) -> Triggered<'_> {
+        Triggered { signal: self }
+    }
+}
+
+/// Future that resolves when shutdown is triggered
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Triggered<'a> {
+    signal: &'a ShutdownSignal,
+}
+
+impl<'a> Future for Triggered<'a> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.signal.poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_util::FutureExt;
+
+    use super::*;
+
+    #[test]
+    fn it_drains() {
+        let shutdown = Shutdown::new();
+        let signal = shutdown.signal();
+        let other = signal.clone();
+        assert_eq!(shutdown.active(), 2);
+        assert!(!signal.is_shutdown());
+
+        let mut drained = shutdown.shutdown();
+        assert!(signal.is_shutdown());
+        assert!(signal.triggered().now_or_never().is_some());
+
+        drop(signal);
+        assert!((&mut drained).now_or_never().is_none());
+        drop(other);
+        assert!(drained.now_or_never().is_some());
+    }
+}
@@ -0,0 +1,296 @@
+use async_trait::async_trait;
+use ethers_core::types::{
+    transaction::{eip2718::TypedTransaction, eip712::Eip712},
+    Address, Signature,
+};
+use ethers_signers::Signer;
+use futures_util::{future, lock::Mutex as AsyncMutex, pin_mut};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    shutdown::Shutdown,
+    time::Instant,
+    timer::{FuturesTimer, Timer},
+};
+
+/// Errors from a [`SigningQueue`]
+#[derive(Debug, thiserror::Error)]
+pub enum SigningQueueError<E: std::error::Error + 'static> {
+    /// The inner signer errored
+    #[error("{0}")]
+    Signer(#[source] E),
+    /// The operation did not complete within the timeout, including time
+    /// spent queued
+    #[error("Signing timed out after {0:?}")]
+    Timeout(Duration),
+    /// Shutdown was triggered before the operation got the signer
+    #[error("Shutdown was triggered before signing")]
+    Shutdown,
+}
+
+/// Counters describing the activity of a [`SigningQueue`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SigningMetrics {
+    /// Operations currently waiting for, or holding, the signer
+    pub pending: usize,
+    /// Operations that produced a signature
+    pub completed: u64,
+    /// Operations where the signer errored
+    pub failed: u64,
+    /// Operations that timed out
+    pub timed_out: u64,
+    /// Operations rejected because of shutdown
+    pub rejected: u64,
+    /// Total time operations spent waiting for the signer
+    pub total_queued: Duration,
+    /// Total time the signer spent signing
+    pub total_signing: Duration,
+}
+
+// Counts an operation as pending until dropped, so operations whose futures
+// are dropped before completing are not counted forever
+struct PendingGuard<'a>(&'a Mutex<SigningMetrics>);
+
+impl<'a> PendingGuard<'a> {
+    fn new(metrics: &'a Mutex<SigningMetrics>) -> Self {
+        metrics.lock().unwrap_or_else(|e| e.into_inner()).pending += 1;
+        Self(metrics)
+    }
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).pending -= 1;
+    }
+}
+
+/// Serializes signing operations to a single signer.
+///
+/// HSM and KMS-backed signers often allow one operation at a time. The
+/// queue admits one operation at a time, in arrival order, and fails
+/// operations that don't complete within the timeout. It implements
+/// [`Signer`], so it can be passed to the builders in place of the signer it
+/// wraps
+#[derive(Debug)]
+pub struct SigningQueue<S> {
+    signer: S,
+    lock: AsyncMutex<()>,
+    timeout: Option<Duration>,
+    timer: Arc<dyn Timer>,
+    metrics: Mutex<SigningMetrics>,
+    shutdown: Option<Shutdown>,
+}
+
+impl<S> SigningQueue<S>
+where
+    S: Signer,
+{
+    /// Wrap a signer. Operations never time out by default
+    pub fn new(signer: S) -> Self {
+        Self {
+            signer,
+            lock: AsyncMutex::new(()),
+            timeout: None,
+            timer: Arc::new(FuturesTimer),
+            metrics: Default::default(),
+            shutdown: None,
+        }
+    }
+
+    /// Fail operations not completed within `timeout`, including time spent
+    /// queued
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timer used for timeouts. Defaults to [`FuturesTimer`], which
+    /// works with any runtime
+    #[must_use]
+    pub fn timer(mut self, timer: impl Timer + 'static) -> Self {
+        self.timer = Arc::new(timer);
+        self
+    }
+
+    /// Stop signing when `shutdown` is triggered. Later operations, and
+    /// operations queued for the signer, fail with
+    /// [`SigningQueueError::Shutdown`]. The operation holding the signer
+    /// completes, and shutdown drains once it has
+    #[must_use]
+    pub fn with_shutdown(mut self, shutdown: &Shutdown) -> Self {
+        self.shutdown = Some(shutdown.clone());
+        self
+    }
+
+    /// The wrapped signer
+    pub fn inner(&self) -> &S {
+        &self.signer
+    }
+
+    /// A snapshot of the queue's metrics
+    pub fn metrics(&self) -> SigningMetrics {
+        *self.metrics.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn update(&self, f: impl FnOnce(&mut SigningMetrics)) {
+        f(&mut self.metrics.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    async fn serialized<F, T>(&self, op: F) -> Result<T, SigningQueueError<S::Error>>
+    where
+        F: std::future::Future<Output = Result<T, S::Error>>,
+    {
+        let pending = PendingGuard::new(&self.metrics);
+        let queued_at = Instant::now();
+        // held until the operation completes, so that shutdown drains it
+        let signal = self.shutdown.as_ref().map(Shutdown::signal);
+
+        let run = async {
+            let lock = self.lock.lock();
+            let _guard = match &signal {
+                Some(signal) if signal.is_shutdown() => return Err(SigningQueueError::Shutdown),
+                Some(signal) => match future::select(lock, signal.triggered()).await {
+                    future::Either::Left((guard, _)) => guard,
+                    future::Either::Right(_) => return Err(SigningQueueError::Shutdown),
+                },
+                None => lock.await,
+            };
+            let signing_at = Instant::now();
+            self.update(|m| m.total_queued += signing_at - queued_at);
+            let result = op.await;
+            self.update(|m| m.total_signing += signing_at.elapsed());
+            result.map_err(SigningQueueError::Signer)
+        };
+
+        let result = match self.timeout {
+            Some(timeout) => {
+                pin_mut!(run);
+                match future::select(run, self.timer.sleep(timeout)).await {
+                    future::Either::Left((result, _)) => result,
+                    future::Either::Right(_) => Err(SigningQueueError::Timeout(timeout)),
+                }
+            }
+            None => run.await,
+        };
+        drop(signal);
+
+        drop(pending);
+        self.update(|m| match &result {
+            Ok(_) => m.completed += 1,
+            Err(SigningQueueError::Signer(_)) => m.failed += 1,
+            Err(SigningQueueError::Timeout(_)) => m.timed_out += 1,
+            Err(SigningQueueError::Shutdown) => m.rejected += 1,
+        });
+        result
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<S> Signer for SigningQueue<S>
+where
+    S: Signer + 'static,
+    S::Error: 'static,
+{
+    type Error = SigningQueueError<S::Error>;
+
+    async fn sign_message<M: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: M,
+    ) -> Result<Signature, Self::Error> {
+        self.serialized(self.signer.sign_message(message)).await
+    }
+
+    async fn sign_transaction(&self, message: &TypedTransaction) -> Result<Signature, Self::Error> {
+        self.serialized(self.signer.sign_transaction(message)).await
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        self.serialized(self.signer.sign_typed_data(payload)).await
+    }
+
+    fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.signer.chain_id()
+    }
+
+    fn with_chain_id<T: Into<u64>>(self, chain_id: T) -> Self {
+        Self {
+            signer: self.signer.with_chain_id(chain_id),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::signers::LocalWallet;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn it_counts_operations() {
+        let wallet: LocalWallet = "11".repeat(32).parse().unwrap();
+        let queue = SigningQueue::new(wallet).timeout(Duration::from_secs(5));
+
+        let (a, b) = futures_util::join!(queue.sign_message("a"), queue.sign_message("b"));
+        a.unwrap();
+        b.unwrap();
+
+        let metrics = queue.metrics();
+        assert_eq!(metrics.completed, 2);
+        assert_eq!(metrics.pending, 0);
+    }
+
+    #[tokio::test]
+    async fn it_stops_counting_dropped_operations() {
+        let wallet: LocalWallet = "11".repeat(32).parse().unwrap();
+        let queue = SigningQueue::new(wallet);
+
+        let held = queue.lock.lock().await;
+        let mut op = Box::pin(queue.sign_message("a"));
+        assert!(futures_util::poll!(&mut op).is_pending());
+        assert_eq!(queue.metrics().pending, 1);
+
+        drop(op);
+        drop(held);
+        assert_eq!(queue.metrics().pending, 0);
+    }
+
+    #[tokio::test]
+    async fn it_rejects_operations_on_shutdown() {
+        let wallet: LocalWallet = "11".repeat(32).parse().unwrap();
+        let shutdown = Shutdown::new();
+        let queue = SigningQueue::new(wallet).with_shutdown(&shutdown);
+        queue.sign_message("a").await.unwrap();
+
+        // an operation queued for the signer is rejected once shutdown
+        // triggers
+        let held = queue.lock.lock().await;
+        let mut op = Box::pin(queue.sign_message("b"));
+        assert!(futures_util::poll!(&mut op).is_pending());
+        assert_eq!(shutdown.active(), 1);
+        let drained = shutdown.shutdown();
+        assert!(matches!(op.await, Err(SigningQueueError::Shutdown)));
+        drop(held);
+        drained.await;
+
+        assert!(matches!(
+            queue.sign_message("c").await,
+            Err(SigningQueueError::Shutdown)
+        ));
+        let metrics = queue.metrics();
+        assert_eq!(metrics.completed, 1);
+        assert_eq!(metrics.rejected, 2);
+    }
+}
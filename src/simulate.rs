@@ -0,0 +1,347 @@
+use ethers_core::{
+    abi::{self, ParamType},
+    types::{Address, Bytes, U256, U64},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    get_forwarder, get_meta_box,
+    rpc::{ForwardCall, ForwardRequest, MetaTxRequest},
+};
+
+static TENDERLY_API: &str = "https://api.tenderly.co/api/v1";
+
+// selector of `Error(string)`
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Simulation errors
+#[derive(Debug, thiserror::Error)]
+pub enum SimulationError {
+    /// Reqwest Error
+    #[error("{0}")]
+    Reqwest(#[from] reqwest::Error),
+    /// Serde Json deser Error
+    #[error("{0}")]
+    SerdeError(#[from] serde_json::Error),
+    /// Tenderly returned an error
+    #[error("Tenderly error: {0}")]
+    Api(String),
+    /// No known Gelato contract forwards calls on this chain
+    #[error("No known Gelato contract on chain {0}")]
+    UnknownChain(u64),
+}
+
+/// A call to simulate
+#[derive(Debug, Clone, Serialize)]
+struct SimulationRequest {
+    network_id: String,
+    #[serde(serialize_with = "crate::ser::serialize_checksum_addr")]
+    from: Address,
+    #[serde(serialize_with = "crate::ser::serialize_checksum_addr")]
+    to: Address,
+    input: Bytes,
+    gas: u64,
+    gas_price: String,
+    value: String,
+    save: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SimulationResponse {
+    Ok { transaction: TenderlyTransaction },
+    Err { error: TenderlyError },
+}
+
+#[derive(Debug, Deserialize)]
+struct TenderlyError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TenderlyTransaction {
+    status: bool,
+    #[serde(default)]
+    gas_used: u64,
+    #[serde(default)]
+    error_message: Option<String>,
+    #[serde(default)]
+    transaction_info: Option<TransactionInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransactionInfo {
+    call_trace: Option<RawCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawCall {
+    #[serde(default)]
+    call_type: Option<String>,
+    from: Address,
+    #[serde(default)]
+    to: Option<Address>,
+    #[serde(default)]
+    gas_used: u64,
+    #[serde(default)]
+    input: Option<Bytes>,
+    #[serde(default)]
+    output: Option<Bytes>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    calls: Option<Vec<RawCall>>,
+}
+
+/// A single call frame of a simulated execution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceCall {
+    /// Call depth. The top-level call is at depth 0
+    pub depth: usize,
+    /// Call type, e.g. `CALL` or `DELEGATECALL`
+    pub call_type: Option<String>,
+    /// Caller
+    pub from: Address,
+    /// Callee
+    pub to: Option<Address>,
+    /// Gas used by the call
+    pub gas_used: u64,
+    /// Call input
+    pub input: Bytes,
+    /// Call output
+    pub output: Bytes,
+    /// Error, if the call reverted
+    pub error: Option<String>,
+}
+
+impl TraceCall {
+    /// The decoded `Error(string)` revert reason, if the call reverted
+    /// with one
+    pub fn revert_reason(&self) -> Option<String> {
+        decode_revert_reason(&self.output)
+    }
+}
+
+// request gas limits are 256-bit, but no chain accepts a limit beyond u64
+fn saturating_gas(gas: U256) -> U64 {
+    gas.min(U64::MAX.as_u64().into()).as_u64().into()
+}
+
+fn flatten(call: RawCall, depth: usize, trace: &mut Vec<TraceCall>) {
+    trace.push(TraceCall {
+        depth,
+        call_type: call.call_type,
+        from: call.from,
+        to: call.to,
+        gas_used: call.gas_used,
+        input: call.input.unwrap_or_default(),
+        output: call.output.unwrap_or_default(),
+        error: call.error,
+    });
+    for inner in call.calls.into_iter().flatten() {
+        flatten(inner, depth + 1, trace);
+    }
+}
+
+/// Decode an `Error(string)` revert payload
+pub fn decode_revert_reason(output: &[u8]) -> Option<String> {
+    let data = output.strip_prefix(&ERROR_SELECTOR)?;
+    abi::decode(&[ParamType::String], data)
+        .ok()?
+        .pop()?
+        .into_string()
+}
+
+/// The result of a simulated execution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation {
+    /// True if the top-level call succeeded
+    pub success: bool,
+    /// Gas used by the simulated transaction
+    pub gas_used: u64,
+    /// Tenderly's description of the failure, if any
+    pub error_message: Option<String>,
+    /// Flattened call trace, in execution order
+    pub trace: Vec<TraceCall>,
+}
+
+impl Simulation {
+    /// The innermost decoded revert reason, if any call reverted with one
+    pub fn revert_reason(&self) -> Option<String> {
+        self.trace
+            .iter()
+            .rev()
+            .filter(|call| call.error.is_some())
+            .find_map(TraceCall::revert_reason)
+    }
+
+    /// The deepest reverted call frame, if any
+    pub fn failing_call(&self) -> Option<&TraceCall> {
+        self.trace
+            .iter()
+            .filter(|call| call.error.is_some())
+            .max_by_key(|call| call.depth)
+    }
+}
+
+/// Simulates would-be forwarded calls with Tenderly's simulation API, for
+/// richer pre-submission diagnostics than `eth_call`
+///
+/// <https://docs.tenderly.co/simulations-and-forks/simulation-api>
+#[derive(Debug, Clone)]
+pub struct TenderlySimulator {
+    client: reqwest::Client,
+    account: String,
+    project: String,
+    access_key: String,
+}
+
+impl TenderlySimulator {
+    /// Instantiate a simulator for a Tenderly account and project
+    pub fn new(
+        account: impl Into<String>,
+        project: impl Into<String>,
+        access_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            client: Default::default(),
+            account: account.into(),
+            project: project.into(),
+            access_key: access_key.into(),
+        }
+    }
+
+    /// Use a specific reqwest Client
+    #[must_use]
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    fn simulate_url(&self) -> String {
+        format!(
+            "{TENDERLY_API}/account/{}/project/{}/simulate",
+            self.account, self.project
+        )
+    }
+
+    /// Simulate a call from `from` to `to` on a specific chain
+    pub async fn simulate_call(
+        &self,
+        chain_id: u64,
+        from: Address,
+        to: Address,
+        input: Bytes,
+        gas: U64,
+    ) -> Result<Simulation, SimulationError> {
+        let request = SimulationRequest {
+            network_id: chain_id.to_string(),
+            from,
+            to,
+            input,
+            gas: gas.as_u64(),
+            gas_price: "0".to_owned(),
+            value: "0".to_owned(),
+            save: false,
+        };
+
+        let text = self
+            .client
+            .post(self.simulate_url())
+            .header("X-Access-Key", &self.access_key)
+            .json(&request)
+            .send()
+            .await?
+            .text()
+            .await?;
+
+        let transaction = match serde_json::from_str(&text)? {
+            SimulationResponse::Ok { transaction } => transaction,
+            SimulationResponse::Err { error } => return Err(SimulationError::Api(error.message)),
+        };
+
+        let mut trace = vec![];
+        if let Some(call) = transaction
+            .transaction_info
+            .and_then(|info| info.call_trace)
+        {
+            flatten(call, 0, &mut trace);
+        }
+
+        Ok(Simulation {
+            success: transaction.status,
+            gas_used: transaction.gas_used,
+            error_message: transaction.error_message,
+            trace,
+        })
+    }
+
+    /// Simulate the call the forwarder makes for a forward call
+    pub async fn simulate_forward_call(
+        &self,
+        call: &ForwardCall,
+    ) -> Result<Simulation, SimulationError> {
+        let forwarder =
+            get_forwarder(call.chain_id).ok_or(SimulationError::UnknownChain(call.chain_id))?;
+        self.simulate_call(
+            call.chain_id,
+            forwarder,
+            call.target,
+            call.data.clone(),
+            call.gas,
+        )
+        .await
+    }
+
+    /// Simulate the call the forwarder makes for a forward request
+    pub async fn simulate_forward_request(
+        &self,
+        req: &ForwardRequest,
+    ) -> Result<Simulation, SimulationError> {
+        let forwarder =
+            get_forwarder(req.chain_id).ok_or(SimulationError::UnknownChain(req.chain_id))?;
+        self.simulate_call(
+            req.chain_id,
+            forwarder,
+            req.target,
+            req.data.clone(),
+            saturating_gas(req.gas),
+        )
+        .await
+    }
+
+    /// Simulate the call the meta box makes for a meta tx request. The meta
+    /// box appends the user address to the calldata, per ERC-2771
+    pub async fn simulate_meta_tx_request(
+        &self,
+        req: &MetaTxRequest,
+    ) -> Result<Simulation, SimulationError> {
+        let meta_box =
+            get_meta_box(req.chain_id).ok_or(SimulationError::UnknownChain(req.chain_id))?;
+        let input = [req.data.as_ref(), req.user.as_bytes()].concat();
+        self.simulate_call(
+            req.chain_id,
+            meta_box,
+            req.target,
+            input.into(),
+            saturating_gas(req.gas),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_decodes_revert_reasons() {
+        let reason = [
+            ERROR_SELECTOR.as_ref(),
+            &abi::encode(&[abi::Token::String("nope".to_owned())]),
+        ]
+        .concat();
+        assert_eq!(decode_revert_reason(&reason).as_deref(), Some("nope"));
+        assert_eq!(decode_revert_reason(&[0u8; 4]), None);
+    }
+}
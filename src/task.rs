@@ -1,18 +1,23 @@
-use futures_timer::Delay;
-use futures_util::ready;
+use futures_util::{ready, task::AtomicWaker};
 use pin_project::pin_project;
+use serde::{Deserialize, Serialize};
 
-use ethers_core::types::H256;
 use std::{
     future::Future,
     pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, SystemTime},
 };
 
 use crate::{
     rpc::{self, Check, CheckOrDate, Execution},
-    ClientError, ClientResult, GelatoClient,
+    shutdown::ShutdownSignal,
+    timer::{FuturesTimer, Sleep, Timer},
+    ClientError, ClientResult, GelatoApi, GelatoClient, TaskId,
 };
 
 /// Gelato Task error
@@ -51,90 +56,434 @@ pub enum TaskError {
     /// Too many retries
     #[error("Backend returned too many error responses")]
     TooManyRetries,
+    /// Tracking stopped due to shutdown. The task may still be executed by
+    /// the backend
+    #[error("Tracking of task {task_id:?} stopped due to shutdown")]
+    Shutdown {
+        /// Id of the task that was being tracked
+        task_id: TaskId,
+    },
+    /// Tracking stopped by a [`TaskAbortHandle`]. The task may still be
+    /// executed by the backend
+    #[error("Tracking of task {task_id:?} aborted")]
+    Aborted {
+        /// Id of the task that was being tracked
+        task_id: TaskId,
+    },
+}
+
+#[cfg(feature = "providers")]
+impl From<TaskError> for ethers_providers::ProviderError {
+    fn from(e: TaskError) -> Self {
+        match e {
+            TaskError::ClientError(e) => e.into(),
+            e => ethers_providers::ProviderError::JsonRpcClientError(Box::new(e)),
+        }
+    }
+}
+
+#[cfg(feature = "middleware-errors")]
+impl ethers_providers::RpcError for TaskError {
+    fn as_error_response(&self) -> Option<&ethers_providers::JsonRpcError> {
+        match self {
+            TaskError::ClientError(e) => ethers_providers::RpcError::as_error_response(e),
+            _ => None,
+        }
+    }
+
+    fn as_serde_error(&self) -> Option<&serde_json::Error> {
+        match self {
+            TaskError::ClientError(e) => ethers_providers::RpcError::as_serde_error(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "middleware-errors")]
+impl ethers_providers::MiddlewareError for TaskError {
+    type Inner = ethers_providers::ProviderError;
+
+    fn from_err(e: Self::Inner) -> Self {
+        TaskError::ClientError(ClientError::Provider(e))
+    }
+
+    fn as_inner(&self) -> Option<&Self::Inner> {
+        match self {
+            TaskError::ClientError(e) => ethers_providers::MiddlewareError::as_inner(e),
+            _ => None,
+        }
+    }
+}
+
+/// The status endpoint polled by a [`GelatoTask`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatusEndpoint {
+    /// `tasks/GelatoMetaBox/{taskId}`, served from the API URL
+    #[default]
+    V1,
+    /// `tasks/status/{taskId}`, served from the relay URL. See
+    /// [`GelatoApi::get_task_status_v2`]
+    V2,
+}
+
+/// Delays between status polls of a [`GelatoTask`].
+///
+/// Delays grow exponentially from `initial` up to `max`, so new tasks are
+/// checked quickly and long-running tasks are polled at a steady rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PollingBackoff {
+    /// Delay before the first poll
+    pub initial: Duration,
+    /// Factor applied to the delay after each poll. Factors below 1 are
+    /// treated as 1, so delays never shrink
+    pub multiplier: u32,
+    /// Upper bound on the delay between polls
+    pub max: Duration,
+}
+
+impl Default for PollingBackoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(2),
+            multiplier: 2,
+            max: Duration::from_secs(DEFAULT_DELAY),
+        }
+    }
+}
+
+impl PollingBackoff {
+    /// A fixed delay between polls
+    pub const fn fixed(delay: Duration) -> Self {
+        Self {
+            initial: delay,
+            multiplier: 1,
+            max: delay,
+        }
+    }
+
+    /// Delay before poll number `poll`, counting from 0
+    pub fn delay(&self, poll: usize) -> Duration {
+        let exponent = u32::try_from(poll).unwrap_or(u32::MAX);
+        self.multiplier
+            .max(1)
+            .checked_pow(exponent)
+            .and_then(|factor| self.initial.checked_mul(factor))
+            .map_or(self.max, |delay| delay.min(self.max))
+    }
+}
+
+#[derive(Debug, Default)]
+struct AbortInner {
+    aborted: AtomicBool,
+    waker: AtomicWaker,
+}
+
+/// Stops the polling of a [`GelatoTask`] from elsewhere in the program. See
+/// [`GelatoTask::abortable`].
+///
+/// The relay API has no cancellation endpoint, so aborting only stops
+/// tracking. The task may still be executed by the backend
+#[derive(Debug, Clone)]
+pub struct TaskAbortHandle {
+    task_id: TaskId,
+    inner: Arc<AbortInner>,
+}
+
+impl TaskAbortHandle {
+    /// The id of the task being tracked
+    pub fn id(&self) -> TaskId {
+        self.task_id
+    }
+
+    /// Stop polling. The task then resolves to [`TaskError::Aborted`]
+    pub fn abort(&self) {
+        self.inner.aborted.store(true, Ordering::SeqCst);
+        self.inner.waker.wake();
+    }
+
+    /// True if [`TaskAbortHandle::abort`] has been called
+    pub fn is_aborted(&self) -> bool {
+        self.inner.aborted.load(Ordering::SeqCst)
+    }
+}
+
+/// The persistable tracking state of a [`GelatoTask`]. See
+/// [`GelatoTask::snapshot`] and [`GelatoTask::resume`].
+///
+/// The timer, status endpoint, shutdown signal and abort handle are not
+/// persisted, and must be set again after resuming
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskSnapshot<P> {
+    /// Task Id
+    pub task_id: TaskId,
+    /// Request payload
+    pub payload: P,
+    /// Retries remaining
+    pub retries: usize,
+    /// Delays between polls
+    pub backoff: PollingBackoff,
+    /// Number of delays so far
+    pub polls: usize,
+    /// When the next poll is due
+    pub next_poll_at: SystemTime,
 }
 
 // convenience
+#[cfg(not(target_arch = "wasm32"))]
 type PinBoxFut<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+// reqwest futures are not `Send` on wasm32
+#[cfg(target_arch = "wasm32")]
+type PinBoxFut<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
 
 /// A pending Gelato task
 ///
-/// Retries are decremented when the server returns "undefined", indicating a
-/// potentially recoverable backend error. Unrecoverable backend errors (e.g.
-/// deserialization errors or HTTP 500-series statuses are not retried.
+/// Retries are decremented when the server returns an API error body, no
+/// status for the task, HTTP 404 or an HTTP 500-series status, indicating a
+/// potentially recoverable backend error. Other errors (e.g. deserialization
+/// errors or other HTTP 400-series statuses) are not retried.
+///
+/// Generic over the [`GelatoApi`] used to poll the task status. Defaults to
+/// [`GelatoClient`]
 #[pin_project(project = TaskProj)]
-pub struct GelatoTask<'a, P> {
+pub struct GelatoTask<'a, P, A: ?Sized = GelatoClient> {
     /// Task Id
-    id: H256,
+    id: TaskId,
     /// Client
-    client: &'a GelatoClient,
+    client: &'a A,
     /// task state
     state: TaskState<'a>,
     /// retries
     retries: usize,
-    /// delay between requests
-    delay: Duration,
+    /// delays between requests
+    backoff: PollingBackoff,
+    /// number of delays so far
+    polls: usize,
+    /// when the current delay ends
+    next_poll: SystemTime,
     /// request payload
     payload: P,
+    /// shutdown signal
+    shutdown: Option<ShutdownSignal>,
+    /// abort flag, shared with a [`TaskAbortHandle`]
+    abort: Option<Arc<AbortInner>>,
+    /// timer used for delays between requests
+    timer: Arc<dyn Timer>,
+    /// status endpoint to poll
+    endpoint: StatusEndpoint,
 }
 
 const DEFAULT_RETRIES: usize = 5;
+// upper bound on the default delay between polls, in seconds
 const DEFAULT_DELAY: u64 = 15;
 
 enum TaskState<'a> {
     // Initial delay to ensure the GettingTx loop doesn't immediately fail
-    Delaying(Pin<Box<Delay>>),
+    Delaying(Sleep),
     // Waiting for API response
     Requesting(PinBoxFut<'a, ClientResult<rpc::TransactionStatus>>),
     // future is over
     Complete,
 }
 
-impl<'a, P> std::fmt::Debug for GelatoTask<'a, P> {
+impl<'a, P, A: ?Sized> std::fmt::Debug for GelatoTask<'a, P, A> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Task").field("id", &self.id).finish()
     }
 }
 
-impl<'a, P> GelatoTask<'a, P> {
+impl<'a, P, A> GelatoTask<'a, P, A>
+where
+    A: GelatoApi + ?Sized,
+{
     /// Instantiate a Task
-    pub fn new(id: H256, client: &'a GelatoClient, payload: P) -> Self {
-        let delay = Duration::from_secs(DEFAULT_DELAY);
+    pub fn new(id: TaskId, client: &'a A, payload: P) -> Self {
+        let backoff = PollingBackoff::default();
+        let timer: Arc<dyn Timer> = Arc::new(FuturesTimer);
         Self {
             id,
             client,
-            state: TaskState::Delaying(Box::pin(Delay::new(delay))),
+            state: TaskState::Delaying(timer.sleep(backoff.delay(0))),
             retries: DEFAULT_RETRIES,
-            delay,
+            backoff,
+            polls: 1,
+            next_poll: crate::time::now() + backoff.delay(0),
             payload,
+            shutdown: None,
+            abort: None,
+            timer,
+            endpoint: StatusEndpoint::default(),
         }
     }
 
+    /// The id of the task being tracked
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+
+    /// The request payload
+    pub fn payload(&self) -> &P {
+        &self.payload
+    }
+
     /// Set the number of retries. Retries are decremented when the server
-    /// returns "undefined", indicating a potentially recoverable backend error.
-    /// Unrecoverable backend errors (e.g. deserialization errors or HTTP
-    /// 500-series statuses are not retried
+    /// returns an API error body, no status for the task, HTTP 404 or an HTTP
+    /// 500-series status, indicating a potentially recoverable backend error.
+    /// Other errors (e.g. deserialization errors) are not retried
     #[must_use]
     pub fn retries(mut self, retries: usize) -> Self {
         self.retries = retries;
         self
     }
 
-    /// Sets the polling delay (the time between poll attempts)
+    /// Sets a fixed polling delay (the time between poll attempts)
     #[must_use]
-    pub fn polling_interval<T: Into<Duration>>(mut self, duration: T) -> Self {
-        self.delay = duration.into();
+    pub fn polling_interval<T: Into<Duration>>(self, duration: T) -> Self {
+        self.backoff(PollingBackoff::fixed(duration.into()))
+    }
+
+    /// Set the delays between poll attempts. Defaults to
+    /// [`PollingBackoff::default`]
+    #[must_use]
+    pub fn backoff(mut self, backoff: PollingBackoff) -> Self {
+        self.backoff = backoff;
 
         if matches!(self.state, TaskState::Delaying(_)) {
-            self.state = TaskState::Delaying(Box::pin(Delay::new(self.delay)))
+            self.polls = 1;
+            self.next_poll = crate::time::now() + self.backoff.delay(0);
+            self.state = TaskState::Delaying(self.timer.sleep(self.backoff.delay(0)))
         }
 
         self
     }
+
+    /// Set the timer used for delays between requests. Defaults to
+    /// [`FuturesTimer`], which works with any runtime
+    #[must_use]
+    pub fn timer(mut self, timer: impl Timer + 'static) -> Self {
+        self.timer = Arc::new(timer);
+
+        if matches!(self.state, TaskState::Delaying(_)) {
+            self.state = TaskState::Delaying(self.timer.sleep(self.until_next_poll()))
+        }
+
+        self
+    }
+
+    /// Set the status endpoint to poll. Defaults to [`StatusEndpoint::V1`]
+    #[must_use]
+    pub fn status_endpoint(mut self, endpoint: StatusEndpoint) -> Self {
+        self.endpoint = endpoint;
+        self
+    }
+
+    /// Stop tracking when shutdown is triggered. The task then resolves to
+    /// [`TaskError::Shutdown`], so the caller can persist its id
+    #[must_use]
+    pub fn with_shutdown(mut self, signal: ShutdownSignal) -> Self {
+        self.shutdown = Some(signal);
+        self
+    }
+
+    /// Split off a handle that stops polling when aborted. The task then
+    /// resolves to [`TaskError::Aborted`]
+    pub fn abortable(mut self) -> (Self, TaskAbortHandle) {
+        let inner = self.abort.get_or_insert_with(Default::default).clone();
+        let handle = TaskAbortHandle {
+            task_id: self.id,
+            inner,
+        };
+        (self, handle)
+    }
+
+    /// The tracking state of the task, for persisting and later resuming
+    /// with [`GelatoTask::resume`]. `None` once the task has resolved
+    pub fn snapshot(&self) -> Option<TaskSnapshot<P>>
+    where
+        P: Clone,
+    {
+        if matches!(self.state, TaskState::Complete) {
+            return None;
+        }
+        Some(TaskSnapshot {
+            task_id: self.id,
+            payload: self.payload.clone(),
+            retries: self.retries,
+            backoff: self.backoff,
+            polls: self.polls,
+            next_poll_at: self.next_poll,
+        })
+    }
+
+    /// Resume tracking a task from a snapshot. Polling starts when the next
+    /// poll was due, or immediately if that time has passed
+    pub fn resume(snapshot: TaskSnapshot<P>, client: &'a A) -> Self {
+        let TaskSnapshot {
+            task_id,
+            payload,
+            retries,
+            backoff,
+            polls,
+            next_poll_at,
+        } = snapshot;
+        let mut task = Self::new(task_id, client, payload);
+        task.retries = retries;
+        task.backoff = backoff;
+        task.polls = polls.max(1);
+        task.next_poll = next_poll_at;
+        task.state = TaskState::Delaying(task.timer.sleep(task.until_next_poll()));
+        task
+    }
+
+    // remaining time in the current delay
+    fn until_next_poll(&self) -> Duration {
+        self.next_poll
+            .duration_since(crate::time::now())
+            .unwrap_or_default()
+    }
+
+    // a task tracking a resubmission of this one, with the same settings
+    pub(crate) fn resubmitted<Q>(&self, id: TaskId, payload: Q) -> GelatoTask<'a, Q, A> {
+        GelatoTask {
+            id,
+            client: self.client,
+            state: TaskState::Delaying(self.timer.sleep(self.backoff.delay(0))),
+            retries: self.retries,
+            backoff: self.backoff,
+            polls: 1,
+            next_poll: crate::time::now() + self.backoff.delay(0),
+            payload,
+            shutdown: self.shutdown.clone(),
+            abort: self.abort.clone(),
+            timer: self.timer.clone(),
+            endpoint: self.endpoint,
+        }
+    }
+
+    pub(crate) fn client(&self) -> &'a A {
+        self.client
+    }
+
+    pub(crate) fn sleep(&self, duration: Duration) -> Sleep {
+        self.timer.sleep(duration)
+    }
+
+    // the steady-state delay between polls
+    pub(crate) fn max_delay(&self) -> Duration {
+        self.backoff.max
+    }
 }
 
 macro_rules! make_request {
     ($cx:ident, $this:ident) => {
-        *$this.state = TaskState::Requesting(Box::pin($this.client.get_task_status(*$this.id)));
+        let fut: PinBoxFut<'_, _> = match *$this.endpoint {
+            StatusEndpoint::V1 => GelatoApi::get_task_status(*$this.client, *$this.id),
+            StatusEndpoint::V2 => {
+                let fut = GelatoApi::get_task_status_v2(*$this.client, *$this.id);
+                Box::pin(async move { fut.await.map(Into::into) })
+            }
+        };
+        *$this.state = TaskState::Requesting(fut);
         $cx.waker().wake_by_ref();
         return Poll::Pending
     };
@@ -146,20 +495,66 @@ macro_rules! complete {
     };
 }
 
+// schedule the next delay
+macro_rules! next_delay {
+    ($this:ident) => {
+        let delay = $this.backoff.delay(*$this.polls);
+        *$this.next_poll = crate::time::now() + delay;
+        *$this.state = TaskState::Delaying($this.timer.sleep(delay));
+        *$this.polls += 1;
+    };
+}
+
 macro_rules! delay_it {
     ($cx:ident, $this:ident) => {
-        *$this.state = TaskState::Delaying(Box::pin(Delay::new(*$this.delay)));
+        next_delay!($this);
         $cx.waker().wake_by_ref();
         return Poll::Pending
     };
 }
 
-impl<'a, P> Future for GelatoTask<'a, P> {
-    type Output = Result<Execution, TaskError>;
+impl<'a, P, A> GelatoTask<'a, P, A>
+where
+    A: GelatoApi + ?Sized,
+{
+    /// Track every status transition of the task, rather than only its
+    /// outcome
+    pub fn into_stream(self) -> TaskStatusStream<'a, P, A> {
+        TaskStatusStream {
+            task: self,
+            last: None,
+            done: false,
+        }
+    }
 
-    #[tracing::instrument(skip(self), fields(task_id = ?self.id, retries_remaining = self.retries))]
-    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
-        let this: TaskProj<_> = self.project();
+    // poll until a status is received. on success, the next delay is
+    // scheduled, but not polled
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self), fields(task_id = ?self.id, retries_remaining = self.retries))
+    )]
+    fn poll_status(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+    ) -> Poll<Result<rpc::TransactionStatus, TaskError>> {
+        let this: TaskProj<_, _> = self.project();
+
+        // on shutdown, release the signal so that the shutdown can drain
+        if let Some(signal) = this.shutdown.as_ref() {
+            if signal.poll_shutdown(cx).is_ready() {
+                *this.shutdown = None;
+                complete!(this);
+                return Poll::Ready(Err(TaskError::Shutdown { task_id: *this.id }));
+            }
+        }
+
+        if let Some(abort) = this.abort.as_ref() {
+            abort.waker.register(cx.waker());
+            if abort.aborted.load(Ordering::SeqCst) {
+                complete!(this);
+                return Poll::Ready(Err(TaskError::Aborted { task_id: *this.id }));
+            }
+        }
 
         let status_fut = match this.state {
             TaskState::Delaying(delay) => {
@@ -178,9 +573,26 @@ impl<'a, P> Future for GelatoTask<'a, P> {
         let status = ready!(status_fut.as_mut().poll(cx));
 
         // if the server returned undefined, decrement retries. according to
-        // gelato docs this is a backend error
-        if let Err(ClientError::Other(_)) = status {
-            tracing::warn!("Undefined status while polling task");
+        // gelato docs this is a backend error. a missing status may be a
+        // task not yet indexed
+        // error statuses are retried for server errors and unindexed tasks
+        // only, so permanent client errors end the future
+        let undefined = match &status {
+            Err(e) => match e.status() {
+                Some(status) => {
+                    status.is_server_error() || status == reqwest::StatusCode::NOT_FOUND
+                }
+                None => matches!(
+                    e,
+                    ClientError::Api { .. }
+                        | ClientError::TaskStatus(_)
+                        | ClientError::TaskNotFound(_)
+                ),
+            },
+            _ => false,
+        };
+        if undefined {
+            trace_warn!("Undefined status while polling task");
             if *this.retries == 0 {
                 complete!(this);
                 return Poll::Ready(Err(TaskError::TooManyRetries));
@@ -190,74 +602,205 @@ impl<'a, P> Future for GelatoTask<'a, P> {
         }
 
         // if reqwest returns a deser or server error, end the future
-        if let Err(e) = status {
-            tracing::error!(error = %e, "Reqwest error in pending tx");
-            complete!(this);
-            return Poll::Ready(Err(TaskError::ClientError(e)));
+        match status {
+            Err(e) => {
+                trace_error!(error = %e, "Reqwest error in pending tx");
+                complete!(this);
+                Poll::Ready(Err(TaskError::ClientError(e)))
+            }
+            Ok(status) => {
+                next_delay!(this);
+                Poll::Ready(Ok(status))
+            }
         }
+    }
+}
 
-        let rpc::TransactionStatus {
-            last_check,
-            execution,
-            ..
-        } = status.expect("checked");
+impl<'a, P, A> Future for GelatoTask<'a, P, A>
+where
+    A: GelatoApi + ?Sized,
+{
+    type Output = Result<Execution, TaskError>;
 
-        // if there's no last check, we poll again later
-        if last_check.is_none() {
-            delay_it!(cx, this);
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        loop {
+            let status = ready!(self.as_mut().poll_status(cx))?;
+            if let Some(outcome) = resolve(status) {
+                self.state = TaskState::Complete;
+                return Poll::Ready(outcome);
+            }
         }
+    }
+}
 
-        // if the last check is a timestamp, we poll again later
-        let last_check = last_check.expect("checked");
-        let last_check = match last_check {
-            CheckOrDate::Date(_) => {
-                delay_it!(cx, this);
-            }
-            CheckOrDate::Check(last_check) => last_check,
-        };
+/// A stream of the status transitions of a [`GelatoTask`]. See
+/// [`GelatoTask::into_stream`].
+///
+/// Yields each status whose task state differs from the previous one. Ends
+/// after yielding a terminal status, or an error
+#[must_use = "streams do nothing unless polled"]
+pub struct TaskStatusStream<'a, P, A: ?Sized = GelatoClient> {
+    task: GelatoTask<'a, P, A>,
+    last: Option<rpc::TaskState>,
+    done: bool,
+}
 
-        match last_check.task_state {
-            // execution is succesful. return the execution object
-            // we assume that there is NO VALID CASE where the API returns
-            // `ExecSuccess` but `execution` is undefined
-            rpc::TaskState::ExecSuccess => {
-                complete!(this);
-                Poll::Ready(Ok(execution.expect("exists if status is sucess")))
-            }
-            // execution occurred but reverted
-            // return an error
-            rpc::TaskState::ExecReverted => {
-                complete!(this);
-                Poll::Ready(Err(TaskError::Reverted {
-                    execution: execution.expect("exists if status is reverted"),
-                    last_check,
-                }))
-            }
-            // request was blacklisted by backend
-            rpc::TaskState::Blacklisted => {
-                complete!(this);
-                Poll::Ready(Err(TaskError::BlackListed {
-                    message: last_check.message,
-                    reason: last_check.reason,
-                }))
-            }
-            // request was cancelled by backend
-            rpc::TaskState::Cancelled => {
-                complete!(this);
-                Poll::Ready(Err(TaskError::Cancelled {
-                    message: last_check.message,
-                    reason: last_check.reason,
-                }))
-            }
-            // request not found by backend
-            rpc::TaskState::NotFound => {
-                complete!(this);
-                Poll::Ready(Err(TaskError::NotFound))
-            }
-            // anything else is a continuation
-            _ => {
-                delay_it!(cx, this);
+impl<'a, P, A: ?Sized> std::fmt::Debug for TaskStatusStream<'a, P, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskStatusStream")
+            .field("id", &self.task.id)
+            .field("last", &self.last)
+            .finish()
+    }
+}
+
+impl<'a, P, A: ?Sized> TaskStatusStream<'a, P, A> {
+    /// The task being tracked
+    pub fn task(&self) -> &GelatoTask<'a, P, A> {
+        &self.task
+    }
+}
+
+impl<'a, P, A> futures_util::Stream for TaskStatusStream<'a, P, A>
+where
+    A: GelatoApi + ?Sized,
+{
+    type Item = Result<rpc::TransactionStatus, TaskError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        while !this.done {
+            let status = match ready!(Pin::new(&mut this.task).poll_status(cx)) {
+                Ok(status) => status,
+                Err(e) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+            };
+
+            if resolve(status.clone()).is_some() {
+                this.done = true;
+                this.task.state = TaskState::Complete;
+            } else if this.last.as_ref() == Some(&status.task_state) {
+                continue;
             }
+            this.last = Some(status.task_state.clone());
+            return Poll::Ready(Some(Ok(status)));
         }
+        Poll::Ready(None)
+    }
+}
+
+/// Resolve a task status to its outcome. `None` if the task has not reached a
+/// terminal state
+pub(crate) fn resolve(status: rpc::TransactionStatus) -> Option<Result<Execution, TaskError>> {
+    let rpc::TransactionStatus {
+        last_check,
+        execution,
+        ..
+    } = status;
+
+    // if there's no last check, or the last check is a timestamp, the task
+    // is still pending
+    let last_check = match last_check? {
+        CheckOrDate::Date(_) => return None,
+        CheckOrDate::Check(last_check) => last_check,
+    };
+
+    match last_check.task_state {
+        // execution is succesful. return the execution object. an execution
+        // not yet reported (e.g. no transaction hash from the v2 endpoint) is
+        // treated as pending
+        rpc::TaskState::ExecSuccess => execution.map(Ok),
+        // execution occurred but reverted
+        // return an error
+        rpc::TaskState::ExecReverted => execution.map(|execution| {
+            Err(TaskError::Reverted {
+                execution,
+                last_check,
+            })
+        }),
+        // request was blacklisted by backend
+        rpc::TaskState::Blacklisted => Some(Err(TaskError::BlackListed {
+            message: last_check.message,
+            reason: last_check.reason,
+        })),
+        // request was cancelled by backend
+        rpc::TaskState::Cancelled => Some(Err(TaskError::Cancelled {
+            message: last_check.message,
+            reason: last_check.reason,
+        })),
+        // request not found by backend
+        rpc::TaskState::NotFound => Some(Err(TaskError::NotFound)),
+        // states added to the API since this release are assumed pending
+        rpc::TaskState::Unknown(ref state) => {
+            trace_warn!(task_state = state.as_str(), "Unknown task state");
+            None
+        }
+        // anything else is a continuation
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_backs_off_polls() {
+        let backoff = PollingBackoff {
+            initial: Duration::from_secs(1),
+            multiplier: 3,
+            max: Duration::from_secs(20),
+        };
+        let delays: Vec<_> = (0..5).map(|poll| backoff.delay(poll).as_secs()).collect();
+        assert_eq!(delays, [1, 3, 9, 20, 20]);
+        assert_eq!(backoff.delay(usize::MAX), Duration::from_secs(20));
+
+        let fixed = PollingBackoff::fixed(Duration::from_millis(5));
+        assert_eq!(fixed.delay(0), fixed.delay(10));
+
+        let zero = PollingBackoff {
+            multiplier: 0,
+            ..fixed
+        };
+        assert_eq!(zero.delay(3), Duration::from_millis(5));
+    }
+
+    #[cfg(feature = "providers")]
+    #[test]
+    fn it_converts_to_provider_errors() {
+        use ethers_providers::ProviderError;
+
+        // converting back does not nest the provider error
+        let err = TaskError::from(ClientError::Provider(ProviderError::CustomError(
+            "boom".into(),
+        )));
+        assert!(matches!(
+            ProviderError::from(err),
+            ProviderError::CustomError(msg) if msg == "boom"
+        ));
+        assert!(matches!(
+            ProviderError::from(TaskError::NotFound),
+            ProviderError::JsonRpcClientError(_)
+        ));
+    }
+
+    #[cfg(feature = "middleware-errors")]
+    #[test]
+    fn it_wraps_provider_errors() {
+        use ethers_providers::{MiddlewareError, ProviderError, RpcError};
+
+        let err = TaskError::from_err(ProviderError::CustomError("boom".into()));
+        assert!(matches!(
+            err.as_inner(),
+            Some(ProviderError::CustomError(msg)) if msg == "boom"
+        ));
+
+        let err = TaskError::from(ClientError::SerdeError(
+            serde_json::from_str::<u64>("x").unwrap_err(),
+        ));
+        assert!(err.as_inner().is_none());
+        assert!(err.is_serde_error());
     }
 }
@@ -0,0 +1,489 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use ethers_core::types::{H256, U256, U64};
+use futures_util::future;
+
+use crate::{
+    rpc::{self, CheckOrDate, Execution, TaskState, TransactionStatus},
+    ApiFuture, ClientError, ClientResult, FeeToken, GelatoApi, TaskId,
+};
+
+/// A request submitted to a [`MockGelatoClient`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Submission {
+    /// A relay transaction
+    Relay {
+        /// The request
+        request: rpc::RelayRequest,
+        /// The target chain
+        chain_id: u64,
+    },
+    /// A relay v2 sponsored call
+    SponsoredCall(rpc::SponsoredCallRequest),
+    /// A signed relay v2 sponsored ERC-2771 call
+    SponsoredCallErc2771(rpc::SignedSponsoredCallErc2771),
+    /// A signed relay v2 sponsored ERC-2771 call using concurrent replay
+    /// protection
+    SponsoredCallConcurrentErc2771(rpc::SignedSponsoredCallConcurrentErc2771),
+    /// A relay v2 call with sync fee
+    CallWithSyncFee(rpc::CallWithSyncFeeRequest),
+    /// A signed relay v2 ERC-2771 call with sync fee
+    CallWithSyncFeeErc2771(rpc::SignedCallWithSyncFeeErc2771),
+    /// A signed relay v2 ERC-2771 call with sync fee using concurrent replay
+    /// protection
+    CallWithSyncFeeConcurrentErc2771(rpc::SignedCallWithSyncFeeConcurrentErc2771),
+    /// A forward call
+    ForwardCall(rpc::ForwardCall),
+    /// A signed forward request
+    ForwardRequest(rpc::SignedForwardRequest),
+    /// A signed meta tx request
+    MetaTxRequest(rpc::SignedMetaTxRequest),
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    submissions: Vec<Submission>,
+    responses: VecDeque<ClientResult<rpc::RelayResponse>>,
+    statuses: HashMap<TaskId, VecDeque<TransactionStatus>>,
+    chains: Vec<u64>,
+    oracle_chains: Vec<u64>,
+    payment_tokens: HashMap<u64, Vec<FeeToken>>,
+    estimated_fee: U256,
+    high_priority_fee: Option<U256>,
+}
+
+/// An in-memory [`GelatoApi`] for unit-testing relay logic without hitting
+/// the real API.
+///
+/// Records submitted requests, and answers them with scripted responses in
+/// order. Task statuses are scripted per task, and the last status of a task
+/// is repeated once its script is exhausted. Clones share state, so a test
+/// can keep a clone to inspect submissions
+#[derive(Debug, Clone, Default)]
+pub struct MockGelatoClient {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockGelatoClient {
+    /// Instantiate a mock with no scripted responses
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Answer the next submission with a response
+    pub fn push_response(&self, response: impl Into<rpc::RelayResponse>) -> &Self {
+        self.state().responses.push_back(Ok(response.into()));
+        self
+    }
+
+    /// Answer the next submission with an error
+    pub fn push_error(&self, error: ClientError) -> &Self {
+        self.state().responses.push_back(Err(error));
+        self
+    }
+
+    /// Append statuses to the script of a task
+    pub fn push_statuses(
+        &self,
+        task_id: impl Into<TaskId>,
+        statuses: impl IntoIterator<Item = TransactionStatus>,
+    ) -> &Self {
+        self.state()
+            .statuses
+            .entry(task_id.into())
+            .or_default()
+            .extend(statuses);
+        self
+    }
+
+    /// Set the chains reported as supported by the relay
+    #[must_use]
+    pub fn with_chains(self, chains: Vec<u64>) -> Self {
+        self.state().chains = chains;
+        self
+    }
+
+    /// Set the chains reported as having a fee oracle
+    #[must_use]
+    pub fn with_oracle_chains(self, chains: Vec<u64>) -> Self {
+        self.state().oracle_chains = chains;
+        self
+    }
+
+    /// Set the payment tokens reported for a chain
+    #[must_use]
+    pub fn with_payment_tokens(self, chain_id: u64, tokens: Vec<FeeToken>) -> Self {
+        self.state().payment_tokens.insert(chain_id, tokens);
+        self
+    }
+
+    /// Set the fee returned by every fee estimate
+    #[must_use]
+    pub fn with_estimated_fee(self, fee: U256) -> Self {
+        self.state().estimated_fee = fee;
+        self
+    }
+
+    /// Set the fee returned by high-priority fee estimates. Defaults to the
+    /// fee set by [`Self::with_estimated_fee`]
+    #[must_use]
+    pub fn with_high_priority_fee(self, fee: U256) -> Self {
+        self.state().high_priority_fee = Some(fee);
+        self
+    }
+
+    /// The requests submitted so far, in order
+    pub fn submissions(&self) -> Vec<Submission> {
+        self.state().submissions.clone()
+    }
+
+    /// Forget the requests submitted so far
+    pub fn clear_submissions(&self) {
+        self.state().submissions.clear();
+    }
+
+    fn submit(&self, submission: Submission) -> ApiFuture<'_, rpc::RelayResponse> {
+        let mut state = self.state();
+        state.submissions.push(submission);
+        let result = state.responses.pop_front().unwrap_or_else(|| {
+            Err(ClientError::Other(
+                "MockGelatoClient: no scripted relay response".to_owned(),
+            ))
+        });
+        Box::pin(future::ready(result))
+    }
+}
+
+impl GelatoApi for MockGelatoClient {
+    fn send_relay_transaction<'a>(
+        &'a self,
+        params: &'a rpc::RelayRequest,
+        chain_id: u64,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        self.submit(Submission::Relay {
+            request: params.clone(),
+            chain_id,
+        })
+    }
+
+    fn sponsored_call<'a>(
+        &'a self,
+        req: &'a rpc::SponsoredCallRequest,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        self.submit(Submission::SponsoredCall(req.clone()))
+    }
+
+    fn sponsored_call_erc2771<'a>(
+        &'a self,
+        req: &'a rpc::SignedSponsoredCallErc2771,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        self.submit(Submission::SponsoredCallErc2771(req.clone()))
+    }
+
+    fn sponsored_call_concurrent_erc2771<'a>(
+        &'a self,
+        req: &'a rpc::SignedSponsoredCallConcurrentErc2771,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        self.submit(Submission::SponsoredCallConcurrentErc2771(req.clone()))
+    }
+
+    fn call_with_sync_fee<'a>(
+        &'a self,
+        req: &'a rpc::CallWithSyncFeeRequest,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        self.submit(Submission::CallWithSyncFee(req.clone()))
+    }
+
+    fn call_with_sync_fee_erc2771<'a>(
+        &'a self,
+        req: &'a rpc::SignedCallWithSyncFeeErc2771,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        self.submit(Submission::CallWithSyncFeeErc2771(req.clone()))
+    }
+
+    fn call_with_sync_fee_concurrent_erc2771<'a>(
+        &'a self,
+        req: &'a rpc::SignedCallWithSyncFeeConcurrentErc2771,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        self.submit(Submission::CallWithSyncFeeConcurrentErc2771(req.clone()))
+    }
+
+    fn send_forward_call<'a>(
+        &'a self,
+        params: &'a rpc::ForwardCall,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        self.submit(Submission::ForwardCall(params.clone()))
+    }
+
+    fn send_forward_request<'a>(
+        &'a self,
+        params: &'a rpc::SignedForwardRequest,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        self.submit(Submission::ForwardRequest(params.clone()))
+    }
+
+    fn send_meta_tx_request<'a>(
+        &'a self,
+        params: &'a rpc::SignedMetaTxRequest,
+    ) -> ApiFuture<'a, rpc::RelayResponse> {
+        self.submit(Submission::MetaTxRequest(params.clone()))
+    }
+
+    fn get_gelato_relay_chains(&self) -> ApiFuture<'_, Vec<u64>> {
+        Box::pin(future::ready(Ok(self.state().chains.clone())))
+    }
+
+    fn get_oracle_chains(&self) -> ApiFuture<'_, Vec<u64>> {
+        Box::pin(future::ready(Ok(self.state().oracle_chains.clone())))
+    }
+
+    fn get_payment_tokens(&self, chain_id: u64) -> ApiFuture<'_, Vec<FeeToken>> {
+        let tokens = self
+            .state()
+            .payment_tokens
+            .get(&chain_id)
+            .cloned()
+            .unwrap_or_default();
+        Box::pin(future::ready(Ok(tokens)))
+    }
+
+    fn get_estimated_fee(
+        &self,
+        _chain_id: u64,
+        _payment_token: FeeToken,
+        _gas_limit: U64,
+        is_high_priority: bool,
+    ) -> ApiFuture<'_, U256> {
+        let state = self.state();
+        let fee = match state.high_priority_fee {
+            Some(fee) if is_high_priority => fee,
+            _ => state.estimated_fee,
+        };
+        Box::pin(future::ready(Ok(fee)))
+    }
+
+    fn get_task_status(&self, task_id: TaskId) -> ApiFuture<'_, TransactionStatus> {
+        let mut state = self.state();
+        let status = state.statuses.get_mut(&task_id).and_then(|script| {
+            if script.len() > 1 {
+                script.pop_front()
+            } else {
+                script.front().cloned()
+            }
+        });
+        let result = status.ok_or(ClientError::TaskNotFound(task_id));
+        Box::pin(future::ready(result))
+    }
+}
+
+/// A task status in `state`, for scripting a [`MockGelatoClient`]. Successful
+/// and reverted statuses include an execution with a zero transaction hash,
+/// and a `status` of `"success"` or `"reverted"`
+pub fn status(task_id: impl Into<TaskId>, state: TaskState) -> TransactionStatus {
+    let created_at = "2022-01-01T00:00:00.000Z".to_owned();
+    let execution_status = match state {
+        TaskState::ExecSuccess => Some("success"),
+        TaskState::ExecReverted => Some("reverted"),
+        _ => None,
+    };
+    let execution = execution_status.map(|status| Execution {
+        status: status.to_owned(),
+        transaction_hash: H256::zero(),
+        block_number: 0,
+        created_at: created_at.clone(),
+    });
+    TransactionStatus {
+        service: "relay".to_owned(),
+        chain: "mock".to_owned(),
+        task_id: task_id.into(),
+        task_state: state.clone(),
+        created_at: created_at.clone(),
+        last_check: Some(CheckOrDate::Check(Box::new(rpc::Check {
+            created_at: Some(created_at.clone()),
+            task_state: state,
+            message: None,
+            payload: None,
+            reason: None,
+        }))),
+        execution,
+        last_execution: created_at,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use ethers_core::types::Bytes;
+
+    use super::*;
+    use crate::GelatoTask;
+
+    #[tokio::test]
+    async fn it_scripts_submissions_and_statuses() {
+        let task_id = TaskId::from(H256::repeat_byte(1));
+        let mock = MockGelatoClient::new();
+        mock.push_response(task_id).push_statuses(
+            task_id,
+            [
+                status(task_id, TaskState::ExecPending),
+                status(task_id, TaskState::ExecSuccess),
+            ],
+        );
+
+        let req = rpc::SponsoredCallRequest::new(5u64, Default::default(), Bytes::default());
+        let resp = mock.sponsored_call(&req).await.unwrap();
+        assert_eq!(resp.task_id(), task_id);
+        assert_eq!(
+            mock.submissions(),
+            vec![Submission::SponsoredCall(req.clone())]
+        );
+        assert!(mock.sponsored_call(&req).await.is_err());
+
+        let execution = GelatoTask::new(task_id, &mock, ())
+            .polling_interval(Duration::from_millis(1))
+            .await
+            .unwrap();
+        assert_eq!(execution.transaction_hash, H256::zero());
+    }
+
+    #[tokio::test]
+    async fn it_polls_the_v2_endpoint() {
+        let task_id = TaskId::from(H256::repeat_byte(2));
+        let mock = MockGelatoClient::new();
+        mock.push_statuses(
+            task_id,
+            [
+                status(task_id, TaskState::CheckPending),
+                status(task_id, TaskState::ExecSuccess),
+            ],
+        );
+
+        let execution = GelatoTask::new(task_id, &mock, ())
+            .status_endpoint(crate::StatusEndpoint::V2)
+            .polling_interval(Duration::from_millis(1))
+            .await
+            .unwrap();
+        assert_eq!(execution.transaction_hash, H256::zero());
+    }
+
+    #[tokio::test]
+    async fn it_scripts_reverts() {
+        let task_id = TaskId::from(H256::repeat_byte(3));
+        let mock = MockGelatoClient::new();
+        mock.push_statuses(task_id, [status(task_id, TaskState::ExecReverted)]);
+
+        let err = GelatoTask::new(task_id, &mock, ())
+            .polling_interval(Duration::from_millis(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            crate::TaskError::Reverted { execution, .. } if execution.status == "reverted"
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_polls_with_backoff() {
+        let task_id = TaskId::from(H256::repeat_byte(3));
+        let mock = MockGelatoClient::new();
+        mock.push_statuses(
+            task_id,
+            [
+                status(task_id, TaskState::CheckPending),
+                status(task_id, TaskState::ExecPending),
+                status(task_id, TaskState::ExecSuccess),
+            ],
+        );
+
+        let execution = GelatoTask::new(task_id, &mock, ())
+            .backoff(crate::PollingBackoff {
+                initial: Duration::from_millis(1),
+                multiplier: 2,
+                max: Duration::from_millis(4),
+            })
+            .await
+            .unwrap();
+        assert_eq!(execution.transaction_hash, H256::zero());
+    }
+
+    #[tokio::test]
+    async fn it_streams_status_transitions() {
+        use futures_util::StreamExt;
+
+        let task_id = TaskId::from(H256::repeat_byte(4));
+        let mock = MockGelatoClient::new();
+        mock.push_statuses(
+            task_id,
+            [
+                status(task_id, TaskState::CheckPending),
+                status(task_id, TaskState::CheckPending),
+                status(task_id, TaskState::ExecPending),
+                status(task_id, TaskState::ExecSuccess),
+            ],
+        );
+
+        let states: Vec<_> = GelatoTask::new(task_id, &mock, ())
+            .polling_interval(Duration::from_millis(1))
+            .into_stream()
+            .map(|status| status.unwrap().task_state)
+            .collect()
+            .await;
+        assert_eq!(
+            states,
+            [
+                TaskState::CheckPending,
+                TaskState::ExecPending,
+                TaskState::ExecSuccess
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_aborts_tasks() {
+        let task_id = TaskId::from(H256::repeat_byte(5));
+        let mock = MockGelatoClient::new();
+        mock.push_statuses(task_id, [status(task_id, TaskState::CheckPending)]);
+
+        let (task, handle) = GelatoTask::new(task_id, &mock, ())
+            .polling_interval(Duration::from_secs(60))
+            .abortable();
+        assert_eq!(handle.id(), task_id);
+        handle.abort();
+        assert!(matches!(
+            task.await,
+            Err(crate::TaskError::Aborted { task_id: id }) if id == task_id
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_resumes_tasks_from_snapshots() {
+        let task_id = TaskId::from(H256::repeat_byte(6));
+        let mock = MockGelatoClient::new();
+        mock.push_statuses(task_id, [status(task_id, TaskState::ExecSuccess)]);
+
+        let task = GelatoTask::new(task_id, &mock, 7u64)
+            .polling_interval(Duration::from_secs(60))
+            .retries(2);
+        let snapshot = task.snapshot().unwrap();
+        assert_eq!(snapshot.retries, 2);
+        drop(task);
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let mut snapshot: crate::TaskSnapshot<u64> = serde_json::from_str(&json).unwrap();
+        assert_eq!(snapshot.payload, 7);
+
+        // the next poll is due now
+        snapshot.next_poll_at = crate::time::now();
+        let mut task = GelatoTask::resume(snapshot, &mock);
+        (&mut task).await.unwrap();
+        assert!(task.snapshot().is_none());
+    }
+}
@@ -0,0 +1,22 @@
+//! Wall-clock and monotonic time sources that work on `wasm32-unknown-unknown`,
+//! where `std::time` panics
+
+use std::time::SystemTime;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::Instant;
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) use instant::Instant;
+
+/// The current system time
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now() -> SystemTime {
+    SystemTime::now()
+}
+
+/// The current system time, read from the JS `Date` API
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now() -> SystemTime {
+    std::time::UNIX_EPOCH + std::time::Duration::from_millis(js_sys::Date::now() as u64)
+}
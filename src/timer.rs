@@ -0,0 +1,52 @@
+use std::{fmt::Debug, future::Future, pin::Pin, time::Duration};
+
+/// A boxed sleep future
+#[cfg(not(target_arch = "wasm32"))]
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// A boxed sleep future. Not `Send` on wasm32, where futures run on a single
+/// thread
+#[cfg(target_arch = "wasm32")]
+pub type Sleep = Pin<Box<dyn Future<Output = ()>>>;
+
+/// An async timer. Abstracts over the delay implementations of different
+/// async runtimes
+pub trait Timer: Debug + Send + Sync {
+    /// A future that resolves after `duration`
+    fn sleep(&self, duration: Duration) -> Sleep;
+}
+
+/// Runtime-agnostic timer backed by `futures-timer`. This is the default, and
+/// also works in the browser on wasm32
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FuturesTimer;
+
+impl Timer for FuturesTimer {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(futures_timer::Delay::new(duration))
+    }
+}
+
+/// Timer backed by the tokio runtime. Must be polled within a tokio runtime
+#[cfg(feature = "tokio")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TokioTimer;
+
+#[cfg(feature = "tokio")]
+impl Timer for TokioTimer {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// Timer backed by the async-std runtime
+#[cfg(feature = "async-std")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AsyncStdTimer;
+
+#[cfg(feature = "async-std")]
+impl Timer for AsyncStdTimer {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(async_std::task::sleep(duration))
+    }
+}
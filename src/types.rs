@@ -1,4 +1,4 @@
-use ethers_core::types::Address;
+use ethers_core::types::{Address, Chain, H256};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
@@ -47,10 +47,82 @@ pub enum PaymentType {
     SyncPullFee = 3,
 }
 
+/// Errors converting to a [`PaymentType`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PaymentTypeError {
+    /// No payment type has this number
+    #[error("Unknown payment type: {0}")]
+    UnknownValue(u8),
+    /// No payment type has this name
+    #[error("Unknown payment type name: {0}")]
+    UnknownName(String),
+}
+
+impl PaymentType {
+    /// Every payment type, in numeric order
+    pub const ALL: [PaymentType; 4] = [
+        PaymentType::Synchronous,
+        PaymentType::AsyncGasTank,
+        PaymentType::SyncGasTank,
+        PaymentType::SyncPullFee,
+    ];
+
+    /// The variant name, e.g. `"AsyncGasTank"`
+    pub const fn name(self) -> &'static str {
+        match self {
+            PaymentType::Synchronous => "Synchronous",
+            PaymentType::AsyncGasTank => "AsyncGasTank",
+            PaymentType::SyncGasTank => "SyncGasTank",
+            PaymentType::SyncPullFee => "SyncPullFee",
+        }
+    }
+}
+
+impl TryFrom<u8> for PaymentType {
+    type Error = PaymentTypeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        PaymentType::ALL
+            .into_iter()
+            .find(|payment_type| *payment_type as u8 == value)
+            .ok_or(PaymentTypeError::UnknownValue(value))
+    }
+}
+
+impl From<PaymentType> for u8 {
+    fn from(payment_type: PaymentType) -> Self {
+        payment_type as u8
+    }
+}
+
+impl std::str::FromStr for PaymentType {
+    type Err = PaymentTypeError;
+
+    /// Parse a payment type from its number, e.g. `"1"`, or its name,
+    /// case-insensitively and ignoring dashes and underscores, e.g.
+    /// `"async-gas-tank"`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(value) = s.trim().parse::<u8>() {
+            return PaymentType::try_from(value);
+        }
+        let normalize = |name: &str| -> String {
+            name.chars()
+                .filter(|c| !c.is_whitespace() && *c != '-' && *c != '_')
+                .flat_map(char::to_lowercase)
+                .collect()
+        };
+        let name = normalize(s);
+        PaymentType::ALL
+            .into_iter()
+            .find(|payment_type| normalize(payment_type.name()) == name)
+            .ok_or_else(|| PaymentTypeError::UnknownName(s.to_owned()))
+    }
+}
+
 /// A gelato fee token is an ERC20 address, which defaults to `0xee..ee`. This
 /// magic value indicates "eth" or the native asset of the chain. This FeeToken
 /// must be allowlisted by Gelato validators
-#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct FeeToken(#[serde(serialize_with = "crate::ser::serialize_checksum_addr")] Address);
 
 impl std::ops::Deref for FeeToken {
@@ -80,3 +152,178 @@ impl From<Address> for FeeToken {
         Self(token)
     }
 }
+
+/// The `deadline` of a [`MetaTxRequest`]. Some verifiers treat an explicit
+/// `deadline: 0` differently from a missing deadline, so the two are kept
+/// distinct. Both are encoded as 0 in the EIP-712 struct hash, where 0 means
+/// no deadline is enforced
+///
+/// Serializes as the timestamp. A missing or `null` deadline deserializes as
+/// [`Deadline::Omitted`]
+///
+/// [`MetaTxRequest`]: crate::rpc::MetaTxRequest
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Deadline {
+    /// Omit `deadline` from the request
+    #[default]
+    Omitted,
+    /// Serialize `deadline` as this unix timestamp, which may be an explicit 0
+    At(u64),
+}
+
+impl Deadline {
+    /// An explicit `deadline: 0`
+    pub const ZERO: Deadline = Deadline::At(0);
+
+    /// True if the deadline is omitted from the request
+    pub fn is_omitted(&self) -> bool {
+        matches!(self, Deadline::Omitted)
+    }
+
+    /// The timestamp, as encoded in the EIP-712 struct hash. 0 if omitted
+    pub fn timestamp(&self) -> u64 {
+        match self {
+            Deadline::Omitted => 0,
+            Deadline::At(timestamp) => *timestamp,
+        }
+    }
+}
+
+impl From<u64> for Deadline {
+    fn from(timestamp: u64) -> Self {
+        Deadline::At(timestamp)
+    }
+}
+
+impl From<Option<u64>> for Deadline {
+    fn from(timestamp: Option<u64>) -> Self {
+        timestamp.map_or(Deadline::Omitted, Deadline::At)
+    }
+}
+
+impl From<Deadline> for Option<u64> {
+    fn from(deadline: Deadline) -> Self {
+        match deadline {
+            Deadline::Omitted => None,
+            Deadline::At(timestamp) => Some(timestamp),
+        }
+    }
+}
+
+impl Serialize for Deadline {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Option::<u64>::from(*self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Deadline {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Option::<u64>::deserialize(deserializer).map(Into::into)
+    }
+}
+
+/// A Gelato task id. Task ids are assigned by the relay when a request is
+/// accepted, and are distinct from the hash of the transaction that
+/// eventually executes the task
+///
+/// Deserializes from hex strings with or without a `0x` prefix
+#[derive(Debug, Default, Copy, Clone, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct TaskId(H256);
+
+impl<'de> Deserialize<'de> for TaskId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.trim().parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl std::ops::Deref for TaskId {
+    type Target = H256;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for TaskId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl std::str::FromStr for TaskId {
+    type Err = <H256 as std::str::FromStr>::Err;
+
+    /// Parse a task id from hex, with or without a `0x` prefix
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix("0x").unwrap_or(s);
+        Ok(Self(s.parse()?))
+    }
+}
+
+impl From<H256> for TaskId {
+    fn from(id: H256) -> Self {
+        Self(id)
+    }
+}
+
+impl From<TaskId> for H256 {
+    fn from(id: TaskId) -> Self {
+        id.0
+    }
+}
+
+/// A type identifying an EVM chain. Implemented for raw chain ids and for
+/// ethers' [`Chain`] enum, so either can be passed to chain id setters
+pub trait IntoChainId {
+    /// The numeric chain id
+    fn into_chain_id(self) -> u64;
+}
+
+impl IntoChainId for u64 {
+    fn into_chain_id(self) -> u64 {
+        self
+    }
+}
+
+impl IntoChainId for Chain {
+    fn into_chain_id(self) -> u64 {
+        self.into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_converts_payment_types() {
+        for payment_type in PaymentType::ALL {
+            assert_eq!(
+                PaymentType::try_from(u8::from(payment_type)),
+                Ok(payment_type)
+            );
+            assert_eq!(payment_type.name().parse::<PaymentType>(), Ok(payment_type));
+        }
+        assert_eq!("2".parse::<PaymentType>(), Ok(PaymentType::SyncGasTank));
+        assert_eq!(
+            "sync_pull_fee".parse::<PaymentType>(),
+            Ok(PaymentType::SyncPullFee)
+        );
+        assert_eq!(
+            PaymentType::try_from(4),
+            Err(PaymentTypeError::UnknownValue(4))
+        );
+        assert!("gasless".parse::<PaymentType>().is_err());
+    }
+}
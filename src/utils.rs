@@ -1,8 +1,13 @@
 use std::collections::HashMap;
 
-use ethers_core::types::Address;
+use ethers_core::types::{Address, U256};
 use once_cell::sync::Lazy;
 
+/// Maximum calldata size accepted by the relay, in bytes. Requests with
+/// larger `data` are rejected by the backend after signing, so builders check
+/// this up front
+pub const MAX_CALLDATA_SIZE: usize = 128 * 1024;
+
 pub static CHAIN_ID_TO_FORWARDER: Lazy<HashMap<u64, Address>> = Lazy::new(|| {
     HashMap::from([
         // Ethereum
@@ -62,6 +67,38 @@ pub fn get_forwarder(chain_id: u64) -> Option<Address> {
     CHAIN_ID_TO_FORWARDER.get(&chain_id).copied()
 }
 
+/// Default maximum gas limit for a single request, by chain id. See
+/// [`get_max_gas`]
+pub static CHAIN_ID_TO_MAX_GAS: Lazy<HashMap<u64, U256>> = Lazy::new(|| {
+    HashMap::from([
+        // Ethereum
+        (1, 10_000_000u64.into()),
+        // Kovan
+        (42, 10_000_000u64.into()),
+        // Goerli
+        (5, 10_000_000u64.into()),
+        // Rinkeby
+        (4, 10_000_000u64.into()),
+        // Evmos
+        (9001, 10_000_000u64.into()),
+        // BSC
+        (56, 10_000_000u64.into()),
+        // Polygon
+        (137, 10_000_000u64.into()),
+    ])
+});
+
+/// Get the default maximum gas limit for a chain id, used by builders when
+/// no `gas_cap` is set.
+///
+/// Gelato does not publish per-chain relay limits, so these are conservative
+/// defaults rather than the relay's own caps, well below the block gas limit
+/// of each chain. Requests needing more gas can raise the limit with
+/// `gas_cap`
+pub fn get_max_gas(chain_id: u64) -> Option<U256> {
+    CHAIN_ID_TO_MAX_GAS.get(&chain_id).copied()
+}
+
 /// Todo: Populate
 pub static CHAIN_ID_TO_META_BOX: Lazy<HashMap<u64, Address>> = Lazy::new(Default::default);
 
@@ -69,3 +106,37 @@ pub static CHAIN_ID_TO_META_BOX: Lazy<HashMap<u64, Address>> = Lazy::new(Default
 pub fn get_meta_box(chain_id: u64) -> Option<Address> {
     CHAIN_ID_TO_META_BOX.get(&chain_id).copied()
 }
+
+/// Verifying contract of the `GelatoRelay1BalanceERC2771` EIP-712 domain.
+/// Deployed at the same address on every supported chain
+pub static GELATO_RELAY_1BALANCE_ERC2771: Lazy<Address> = Lazy::new(|| {
+    "0xd8253782c45a12053594b9deB72d8e8aB2Fca54c"
+        .parse()
+        .expect("!erc2771 relay")
+});
+
+/// Verifying contract of the `GelatoRelay1BalanceConcurrentERC2771` EIP-712
+/// domain. Deployed at the same address on every supported chain
+pub static GELATO_RELAY_1BALANCE_CONCURRENT_ERC2771: Lazy<Address> = Lazy::new(|| {
+    "0xc65d82ECE367EF06bf2AB791B3f3CF037Dc0e816"
+        .parse()
+        .expect("!concurrent erc2771 relay")
+});
+
+/// Verifying contract of the `GelatoRelayERC2771` EIP-712 domain, used by
+/// ERC-2771 calls with sync fee. Deployed at the same address on every
+/// supported chain
+pub static GELATO_RELAY_ERC2771: Lazy<Address> = Lazy::new(|| {
+    "0xb539068872230f20456CF38EC52EF2f91AF4AE49"
+        .parse()
+        .expect("!sync fee erc2771 relay")
+});
+
+/// Verifying contract of the `GelatoRelayConcurrentERC2771` EIP-712 domain,
+/// used by concurrent ERC-2771 calls with sync fee. Deployed at the same
+/// address on every supported chain
+pub static GELATO_RELAY_CONCURRENT_ERC2771: Lazy<Address> = Lazy::new(|| {
+    "0x8598806401A63Ddf52473F1B3C55bC9E33e2d73b"
+        .parse()
+        .expect("!concurrent sync fee erc2771 relay")
+});
@@ -0,0 +1,270 @@
+use ethers_core::types::{
+    transaction::eip712::{EIP712Domain, Eip712},
+    Address, H256,
+};
+use ethers_signers::Signer;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    rpc::{
+        salt_from_seed, ForwardRequest, MetaTxRequest, SponsoredCallConcurrentErc2771,
+        SponsoredCallErc2771,
+    },
+    PaymentType,
+};
+
+/// Placeholder verifying contract for request types whose contract is not
+/// known to the SDK on the canonical chain
+pub const PLACEHOLDER_VERIFYING_CONTRACT: Address = Address::repeat_byte(0x11);
+
+/// Chain id of the canonical inputs
+pub const CANONICAL_CHAIN_ID: u64 = 42;
+
+/// Test vector generation errors
+#[derive(Debug, thiserror::Error)]
+pub enum VectorError {
+    /// EIP-712 encoding failed
+    #[error("EIP-712 encoding failed: {0}")]
+    Eip712(String),
+    /// Signer errored
+    #[error("Signer errored: {0}")]
+    Signer(String),
+    /// Serde Json ser Error
+    #[error("{0}")]
+    SerdeError(#[from] serde_json::Error),
+}
+
+/// A request with an overridden EIP-712 domain. Hashes and signs exactly like
+/// the inner request, but against `domain`
+#[derive(Debug, Clone)]
+pub struct WithDomain<'a, T> {
+    inner: &'a T,
+    domain: EIP712Domain,
+}
+
+impl<'a, T> WithDomain<'a, T> {
+    /// Wrap a request, overriding its domain
+    pub fn new(inner: &'a T, domain: EIP712Domain) -> Self {
+        Self { inner, domain }
+    }
+}
+
+impl<'a, T> Eip712 for WithDomain<'a, T>
+where
+    T: Eip712,
+{
+    type Error = T::Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(self.domain.clone())
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        T::type_hash()
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        self.inner.struct_hash()
+    }
+}
+
+/// An EIP-712 test vector for one request
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Vector {
+    /// Request type id, as sent to the relay
+    pub type_id: &'static str,
+    /// EIP-712 domain
+    pub domain: EIP712Domain,
+    /// Request, as serialized for the relay
+    pub request: Value,
+    /// Hash of the request's EIP-712 type
+    pub type_hash: H256,
+    /// EIP-712 domain separator
+    pub domain_separator: H256,
+    /// EIP-712 struct hash of the request
+    pub struct_hash: H256,
+    /// EIP-712 digest, i.e. the signed hash
+    pub digest: H256,
+    /// Address of the signer
+    pub signer: Address,
+    /// Signature over the digest, as a 0x-prefixed rsv hex string
+    pub signature: String,
+}
+
+/// Compute the test vector for a request. Uses the request's own domain
+/// unless `domain` is provided
+pub async fn vector<T, S>(
+    type_id: &'static str,
+    request: &T,
+    domain: Option<EIP712Domain>,
+    signer: &S,
+) -> Result<Vector, VectorError>
+where
+    T: Eip712 + Serialize + Send + Sync,
+    S: Signer,
+{
+    let eip712 = |e: T::Error| VectorError::Eip712(e.to_string());
+
+    let domain = match domain {
+        Some(domain) => domain,
+        None => request.domain().map_err(eip712)?,
+    };
+    let payload = WithDomain::new(request, domain.clone());
+
+    let signature = signer
+        .sign_typed_data(&payload)
+        .await
+        .map_err(|e| VectorError::Signer(e.to_string()))?;
+
+    Ok(Vector {
+        type_id,
+        request: serde_json::to_value(request)?,
+        type_hash: T::type_hash().map_err(eip712)?.into(),
+        domain_separator: domain.separator().into(),
+        struct_hash: payload.struct_hash().map_err(eip712)?.into(),
+        digest: payload.encode_eip712().map_err(eip712)?.into(),
+        signer: signer.address(),
+        signature: format!("0x{signature}"),
+        domain,
+    })
+}
+
+/// The canonical forward request input, sponsored by `sponsor`
+pub fn canonical_forward_request(sponsor: Address) -> ForwardRequest {
+    ForwardRequest {
+        chain_id: CANONICAL_CHAIN_ID,
+        target: "0x61bBe925A5D646cE074369A6335e5095Ea7abB7A"
+            .parse()
+            .expect("valid address"),
+        data: "4b327067000000000000000000000000eeeeeeeeeeeeeeeeeeeeeeeeaeeeeeeeeeeeeeeeee"
+            .parse()
+            .expect("valid bytes"),
+        fee_token: Default::default(),
+        payment_type: PaymentType::AsyncGasTank,
+        max_fee: 10000000000000000000u64.into(),
+        gas: 200000u64.into(),
+        sponsor,
+        sponsor_chain_id: CANONICAL_CHAIN_ID,
+        nonce: 0,
+        enforce_sponsor_nonce: false,
+        enforce_sponsor_nonce_ordering: false,
+    }
+}
+
+/// The canonical meta tx request input, by and sponsored by `user`
+pub fn canonical_meta_tx_request(user: Address) -> MetaTxRequest {
+    let forward = canonical_forward_request(user);
+    MetaTxRequest {
+        chain_id: forward.chain_id,
+        target: forward.target,
+        data: forward.data,
+        fee_token: forward.fee_token,
+        payment_type: forward.payment_type,
+        max_fee: forward.max_fee,
+        gas: forward.gas,
+        user,
+        sponsor: Some(user),
+        sponsor_chain_id: Some(CANONICAL_CHAIN_ID),
+        nonce: 0,
+        deadline: crate::Deadline::ZERO,
+    }
+}
+
+/// The canonical ERC-2771 sponsored call input, by `user`
+pub fn canonical_sponsored_call_erc2771(user: Address) -> SponsoredCallErc2771 {
+    let forward = canonical_forward_request(user);
+    SponsoredCallErc2771 {
+        chain_id: forward.chain_id,
+        target: forward.target,
+        data: forward.data,
+        user,
+        user_nonce: 0,
+        user_deadline: 0,
+    }
+}
+
+/// The canonical concurrent ERC-2771 sponsored call input, by `user`
+pub fn canonical_sponsored_call_concurrent_erc2771(
+    user: Address,
+) -> SponsoredCallConcurrentErc2771 {
+    let forward = canonical_forward_request(user);
+    SponsoredCallConcurrentErc2771 {
+        chain_id: forward.chain_id,
+        target: forward.target,
+        data: forward.data,
+        user,
+        user_salt: salt_from_seed("gelato-sdk"),
+        user_deadline: 0,
+    }
+}
+
+/// Generate vectors for every request type from the canonical inputs, all
+/// signed by `signer`.
+///
+/// Meta box addresses are not yet known to the SDK, so meta tx vectors are
+/// computed against [`PLACEHOLDER_VERIFYING_CONTRACT`]
+pub async fn generate<S: Signer>(signer: &S) -> Result<Vec<Vector>, VectorError> {
+    let address = signer.address();
+
+    let forward_request = canonical_forward_request(address);
+    let meta_tx_request = canonical_meta_tx_request(address);
+    let erc2771 = canonical_sponsored_call_erc2771(address);
+    let concurrent_erc2771 = canonical_sponsored_call_concurrent_erc2771(address);
+    let meta_tx_domain = meta_tx_request.domain().unwrap_or_else(|_| EIP712Domain {
+        name: "GelatoMetaBox".to_owned(),
+        version: "V1".to_owned(),
+        chain_id: CANONICAL_CHAIN_ID.into(),
+        verifying_contract: PLACEHOLDER_VERIFYING_CONTRACT,
+        salt: None,
+    });
+
+    Ok(vec![
+        vector("ForwardRequest", &forward_request, None, signer).await?,
+        vector(
+            "MetaTxRequest",
+            &meta_tx_request,
+            Some(meta_tx_domain),
+            signer,
+        )
+        .await?,
+        vector("SponsoredCallERC2771", &erc2771, None, signer).await?,
+        vector(
+            "SponsoredCallConcurrentERC2771",
+            &concurrent_erc2771,
+            None,
+            signer,
+        )
+        .await?,
+    ])
+}
+
+/// Serialize vectors as a JSON array
+pub fn to_json(vectors: &[Vector]) -> Result<Value, VectorError> {
+    Ok(serde_json::to_value(vectors)?)
+}
+
+#[cfg(test)]
+mod test {
+    use ethers::signers::LocalWallet;
+
+    use super::*;
+
+    // matches the forward request test in `rpc::forward_req`
+    const SPONSOR_KEY: &str = "9cb3a530d61728e337290409d967db069f5219279f89e5ddb5ae4af76a8da5f4";
+    const SPONSOR_SIGNATURE: &str = "0x23c272c0cba2b897de0fd8fe87d419f0f273c82ef10917520b733da889688b1c6fec89412c6f121fccbc30ce89b20a3de2f405018f1ac1249b9ff705fdb62a521b";
+
+    #[tokio::test]
+    async fn it_generates_vectors() {
+        let sponsor: LocalWallet = SPONSOR_KEY.parse().unwrap();
+        let vectors = generate(&sponsor).await.unwrap();
+
+        assert_eq!(vectors.len(), 4);
+        assert_eq!(vectors[0].signature, SPONSOR_SIGNATURE);
+
+        let digest = vectors[1].digest;
+        let signature: ethers_core::types::Signature = vectors[1].signature.parse().unwrap();
+        assert_eq!(signature.recover(digest).unwrap(), sponsor.address());
+    }
+}
@@ -0,0 +1,178 @@
+use futures_util::{stream, SinkExt, Stream, StreamExt};
+use once_cell::sync::Lazy;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::{rpc::TransactionStatus, TaskId};
+
+static DEFAULT_WS_URL: Lazy<Url> =
+    Lazy::new(|| "wss://api.gelato.digital/tasks/ws/status".parse().unwrap());
+
+/// Websocket errors
+#[derive(Debug, thiserror::Error)]
+pub enum WsError {
+    /// Websocket transport error
+    #[error("{0}")]
+    Transport(#[from] tokio_tungstenite::tungstenite::Error),
+    /// Serde Json Error
+    #[error("{0}")]
+    SerdeError(#[from] serde_json::Error),
+    /// The API reported an error
+    #[error("Gelato websocket error: {0}")]
+    Api(String),
+}
+
+/// A stream of status updates for a single task
+pub type WsStatusStream = Pin<Box<dyn Stream<Item = Result<TransactionStatus, WsError>> + Send>>;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Subscription {
+    action: &'static str,
+    task_id: TaskId,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "event", content = "payload", rename_all = "camelCase")]
+enum WsMessage {
+    Update(Box<TransactionStatus>),
+    Error(ErrorPayload),
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorPayload {
+    message: String,
+}
+
+// continue with the next message, end the stream, or yield an item
+enum Next {
+    Skip,
+    End,
+    Yield(Result<TransactionStatus, WsError>),
+}
+
+fn handle(task_id: TaskId, message: Result<Message, WsError>) -> Next {
+    let text = match message {
+        Ok(Message::Text(text)) => text,
+        Ok(Message::Close(_)) => return Next::End,
+        Ok(_) => return Next::Skip,
+        Err(e) => return Next::Yield(Err(e)),
+    };
+
+    match serde_json::from_str(&text) {
+        Ok(WsMessage::Update(status)) if status.task_id == task_id => Next::Yield(Ok(*status)),
+        Ok(WsMessage::Update(_)) => Next::Skip,
+        Ok(WsMessage::Error(e)) => Next::Yield(Err(WsError::Api(e.message))),
+        Err(e) => {
+            trace_warn!(error = %e, message = text.as_str(), "Unexpected websocket message");
+            Next::Skip
+        }
+    }
+}
+
+/// Subscribes to task status updates over Gelato's websocket API, as an
+/// alternative to polling. Requires a tokio runtime
+#[derive(Debug, Clone)]
+pub struct GelatoWsClient {
+    url: Url,
+}
+
+impl Default for GelatoWsClient {
+    fn default() -> Self {
+        Self {
+            url: DEFAULT_WS_URL.clone(),
+        }
+    }
+}
+
+impl GelatoWsClient {
+    /// Instantiate a client using the default websocket URL
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Use a specific websocket URL
+    #[must_use]
+    pub fn with_url(mut self, url: Url) -> Self {
+        self.url = url;
+        self
+    }
+
+    /// The websocket URL
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Subscribe to status updates for a task, over a dedicated connection.
+    /// The stream ends after the task reaches a terminal state, or when the
+    /// server closes the connection
+    pub async fn subscribe(&self, task_id: impl Into<TaskId>) -> Result<WsStatusStream, WsError> {
+        let task_id = task_id.into();
+        let (mut socket, _) = tokio_tungstenite::connect_async(self.url.as_str()).await?;
+
+        let subscription = Subscription {
+            action: "subscribe",
+            task_id,
+        };
+        socket
+            .send(Message::Text(serde_json::to_string(&subscription)?))
+            .await?;
+
+        let updates = stream::unfold(Some(socket), move |socket| async move {
+            let mut socket = socket?;
+            loop {
+                let message = socket.next().await?.map_err(WsError::from);
+                match handle(task_id, message) {
+                    Next::Skip => continue,
+                    Next::End => return None,
+                    Next::Yield(Ok(status)) if status.task_state.is_terminal() => {
+                        let _ = socket.close(None).await;
+                        return Some((Ok(status), None));
+                    }
+                    Next::Yield(item) => return Some((item, Some(socket))),
+                }
+            }
+        });
+        Ok(Box::pin(updates))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_filters_messages() {
+        let task_id = TaskId::from(ethers_core::types::H256::repeat_byte(1));
+        let update = serde_json::json!({
+            "event": "update",
+            "payload": {
+                "service": "relay",
+                "chain": "goerli",
+                "taskId": format!("{task_id}"),
+                "taskState": "ExecPending",
+                "created_at": "2022-06-02T12:00:00.000Z",
+                "lastExecution": "2022-06-02T12:00:00.000Z"
+            }
+        })
+        .to_string();
+
+        assert!(matches!(
+            handle(task_id, Ok(Message::Text(update.clone()))),
+            Next::Yield(Ok(_))
+        ));
+        let other = TaskId::from(ethers_core::types::H256::repeat_byte(2));
+        assert!(matches!(
+            handle(other, Ok(Message::Text(update))),
+            Next::Skip
+        ));
+
+        let error = r#"{"event": "error", "payload": {"message": "nope"}}"#.to_owned();
+        assert!(matches!(
+            handle(task_id, Ok(Message::Text(error))),
+            Next::Yield(Err(WsError::Api(_)))
+        ));
+    }
+}
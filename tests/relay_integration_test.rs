@@ -14,13 +14,13 @@ async fn simple_queries() -> Result<(), ClientError> {
     let task_status = gelato
         .get_task_status(
             "0xce52ae7a6a3032848d76b161ac4c131fa995dcc67e3be5392dfb8466275d6679"
-                .parse()
+                .parse::<TaskId>()
                 .unwrap(),
         )
         .await;
 
     match task_status {
-        Err(ClientError::Other(_)) => {}
+        Err(ClientError::Api { .. } | ClientError::TaskStatus(_) | ClientError::Http { .. }) => {}
         Ok(_) => {}
         _ => panic!("Incorrect status {task_status:?}"),
     }